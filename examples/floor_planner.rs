@@ -0,0 +1,152 @@
+use std::marker::PhantomData;
+
+use halo2_playground::{
+    chips::{
+        is_zero::{IsZeroChip, IsZeroConfig},
+        range_check::{RangeCheckChip, RangeCheckConfig},
+    },
+    prove::prove_and_verify,
+};
+use halo2_proofs::{
+    circuit::{floor_planner::V1, FloorPlanner, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+/// This example runs the same circuit under `SimpleFloorPlanner` and the `V1`
+/// floor planner. The circuit range checks a few values and exposes whether
+/// each of them is zero, so it is made of many small regions over different
+/// columns, which is where the planners differ: `SimpleFloorPlanner` places
+/// the regions one after another in synthesis order, `V1` measures them
+/// first and packs them into free space. The floor planner is a type
+/// parameter of the circuit, nothing else changes between the two runs.
+///
+/// The tests run with `cargo test`.
+
+struct MyCircuit<F: FieldExt, P: FloorPlanner> {
+    values: Vec<Value<F>>,
+    _planner: PhantomData<P>,
+}
+
+impl<F: FieldExt, P: FloorPlanner> MyCircuit<F, P> {
+    fn new(values: &[u64]) -> Self {
+        Self {
+            values: values.iter().map(|v| Value::known(F::from(*v))).collect(),
+            _planner: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct MyCircuitConfig<F: FieldExt> {
+    advice: Column<Advice>,
+    range_check_config: RangeCheckConfig<F, 8>,
+    is_zero_config: IsZeroConfig<F>,
+    instance: Column<Instance>,
+}
+
+impl<F: FieldExt, P: FloorPlanner> Circuit<F> for MyCircuit<F, P> {
+    type Config = MyCircuitConfig<F>;
+
+    type FloorPlanner = P;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+            _planner: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let bit = meta.advice_column();
+        let acc = meta.advice_column();
+        let value = meta.advice_column();
+        let value_inverse = meta.advice_column();
+        let result = meta.advice_column();
+        let instance = meta.instance_column();
+
+        for column in [advice, acc, value, value_inverse, result] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        MyCircuitConfig {
+            advice,
+            range_check_config: RangeCheckChip::configure(meta, bit, acc),
+            is_zero_config: IsZeroChip::configure(meta, value, value_inverse, result),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let range_check_chip = RangeCheckChip::construct(config.range_check_config);
+        let is_zero_chip = IsZeroChip::construct(config.is_zero_config);
+
+        for (row, value) in self.values.iter().enumerate() {
+            let cell = layouter.assign_region(
+                || "load value",
+                |mut region| region.assign_advice(|| "value", config.advice, 0, || *value),
+            )?;
+            range_check_chip.range_check(layouter.namespace(|| "range check"), cell.clone())?;
+
+            let value = is_zero_chip.load_cell(layouter.namespace(|| "load cell"), cell)?;
+            let is_zero = is_zero_chip.is_zero(layouter.namespace(|| "is zero"), value)?;
+            layouter.constrain_instance(is_zero.cell(), config.instance, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+// the smallest k the circuit fits in and verifies at
+fn min_k<P: FloorPlanner>(circuit: &MyCircuit<Fp, P>, public_inputs: &[Fp]) -> u32 {
+    (4..=16)
+        .find(|k| {
+            MockProver::run(*k, circuit, vec![public_inputs.to_vec()])
+                .map(|prover| prover.verify().is_ok())
+                .unwrap_or(false)
+        })
+        .expect("circuit doesn't fit in 2^16 rows")
+}
+
+fn main() {
+    let values = [0, 7, 200, 0, 31];
+    let public_inputs: Vec<Fp> = values.iter().map(|v| Fp::from(*v == 0)).collect();
+
+    let simple = MyCircuit::<Fp, SimpleFloorPlanner>::new(&values);
+    let simple_k = min_k(&simple, &public_inputs);
+    println!("SimpleFloorPlanner: min k = {simple_k}");
+
+    let v1 = MyCircuit::<Fp, V1>::new(&values);
+    let v1_k = min_k(&v1, &public_inputs);
+    println!("V1: min k = {v1_k}");
+
+    // Both layouts produce proofs that verify at their own minimum k.
+    assert!(prove_and_verify(simple_k, simple, &[&public_inputs]).is_ok());
+    assert!(prove_and_verify(v1_k, v1, &[&public_inputs]).is_ok());
+    println!("success");
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::check_synthesis_determinism;
+
+    use super::*;
+
+    #[test]
+    fn test_synthesis_determinism() {
+        // both planners, each at the k it needs
+        let values = [0, 7, 200, 0, 31];
+        let public_inputs: Vec<Fp> = values.iter().map(|v| Fp::from(*v == 0)).collect();
+        let simple = MyCircuit::<Fp, SimpleFloorPlanner>::new(&values);
+        check_synthesis_determinism(min_k(&simple, &public_inputs), &simple).unwrap();
+        let v1 = MyCircuit::<Fp, V1>::new(&values);
+        check_synthesis_determinism(min_k(&v1, &public_inputs), &v1).unwrap();
+    }
+}
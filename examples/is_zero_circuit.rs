@@ -11,6 +11,8 @@ use halo2_proofs::{
 /// is_zero value. This basically proves that the prover knows a non-zero
 /// number, though not practically useful, but just to play aroung with
 /// halo2 gadgets.
+///
+/// The tests run with `cargo test`.
 
 #[derive(Default)]
 struct MyCircuit<F: FieldExt> {
@@ -56,7 +58,7 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     ) -> Result<(), halo2_proofs::plonk::Error> {
         let chip = IsZeroChip::<F>::construct(config.is_zero_config);
         let value = chip.load_value(layouter.namespace(|| "load value"), self.number)?;
-        let result_cell = chip.is_zero(layouter.namespace(|| "load value"), value)?;
+        let result_cell = chip.is_zero(layouter.namespace(|| "is zero"), value)?;
 
         layouter.constrain_instance(result_cell.cell(), config.instance, 0)?;
 
@@ -82,3 +84,28 @@ fn main() {
     let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
     assert!(prover.verify().is_err());
 }
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::{check_synthesis_determinism, record};
+
+    use super::*;
+
+    #[test]
+    fn test_no_duplicate_namespaces() {
+        let circuit = MyCircuit::<Fp> {
+            number: Value::known(Fp::from(5)),
+        };
+        let trace = record(4, &circuit).unwrap();
+        assert_eq!(trace.namespaces.len(), 2);
+        assert_eq!(trace.duplicate_namespaces(), Vec::<Vec<String>>::new());
+    }
+
+    #[test]
+    fn test_synthesis_determinism() {
+        let circuit = MyCircuit::<Fp> {
+            number: Value::known(Fp::from(5)),
+        };
+        check_synthesis_determinism(4, &circuit).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+use halo2_playground::{
+    chips::{
+        comparator::{ComparatorChip, ComparatorConfig},
+        range_check::RangeCheckChip,
+        xor::XorChip,
+    },
+    prove::prove_and_verify,
+    utils::CellCache,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+/// This example proves knowledge of a printable ASCII plaintext and a key
+/// that encrypt to a public ciphertext under a one-time pad, in other words
+/// "this ciphertext decrypts to readable text under some key I know". Each
+/// ciphertext byte is `plaintext ^ key` through the `XorChip` lookup, which
+/// also keeps every byte below 256, and each plaintext byte is checked to lie
+/// in `0x20..=0x7e` with the `ComparatorChip`. The bounds come from a
+/// `CellCache`, every byte's comparisons copy the same two constant cells.
+///
+/// The byte XOR table has 2^16 rows, so the circuit needs k = 17.
+///
+/// The tests run with `cargo test`.
+
+const K: u32 = 17;
+const LEN: usize = 16;
+
+const PRINTABLE_FIRST: u64 = 0x20;
+const PRINTABLE_LAST: u64 = 0x7e;
+
+struct OtpCircuit<F: FieldExt> {
+    plaintext: [Value<F>; LEN],
+    key: [Value<F>; LEN],
+}
+
+impl<F: FieldExt> OtpCircuit<F> {
+    fn new(plaintext: &[u8; LEN], key: &[u8; LEN]) -> Self {
+        Self {
+            plaintext: plaintext.map(|byte| Value::known(F::from(byte as u64))),
+            key: key.map(|byte| Value::known(F::from(byte as u64))),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct OtpCircuitConfig<F: FieldExt> {
+    advice: Column<Advice>,
+    xor_chip: XorChip<F, 8>,
+    comparator_config: ComparatorConfig<F, 8>,
+    instance: Column<Instance>,
+}
+
+impl<F: FieldExt> Circuit<F> for OtpCircuit<F> {
+    type Config = OtpCircuitConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            plaintext: [Value::unknown(); LEN],
+            key: [Value::unknown(); LEN],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let diff = meta.advice_column();
+        let out = meta.advice_column();
+        let bit = meta.advice_column();
+        let acc = meta.advice_column();
+        let constants = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        for column in [advice, a, b, diff, out, acc] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+        // the printable bounds and the expected comparison results
+        meta.enable_constant(constants);
+
+        let range_check = RangeCheckChip::configure(meta, bit, acc);
+        OtpCircuitConfig {
+            advice,
+            xor_chip: XorChip::construct(meta),
+            comparator_config: ComparatorChip::configure(meta, a, b, diff, out, range_check),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let comparator = ComparatorChip::construct(config.comparator_config);
+        config
+            .xor_chip
+            .xor_table
+            .load(&mut layouter.namespace(|| "xor table"))?;
+
+        let cache = CellCache::new(config.advice);
+
+        for (i, (plaintext, key)) in self.plaintext.iter().zip(self.key.iter()).enumerate() {
+            let (plaintext, key) = layouter.assign_region(
+                || "load byte",
+                |mut region| {
+                    let plaintext =
+                        region.assign_advice(|| "plaintext", config.advice, 0, || *plaintext)?;
+                    let key = region.assign_advice(|| "key", config.advice, 1, || *key)?;
+                    Ok((plaintext, key))
+                },
+            )?;
+
+            let ciphertext = config.xor_chip.calculate_xor(
+                layouter.namespace(|| "encrypt"),
+                plaintext.clone(),
+                key,
+            )?;
+            layouter.constrain_instance(ciphertext.cell(), config.instance, i)?;
+
+            let first = cache.constant(
+                layouter.namespace(|| "first printable"),
+                F::from(PRINTABLE_FIRST),
+            )?;
+            let last = cache.constant(
+                layouter.namespace(|| "last printable"),
+                F::from(PRINTABLE_LAST),
+            )?;
+            let above = comparator.gte(
+                layouter.namespace(|| "plaintext >= first"),
+                plaintext.clone(),
+                first,
+            )?;
+            let below =
+                comparator.lte(layouter.namespace(|| "plaintext <= last"), plaintext, last)?;
+            assert_true(layouter.namespace(|| "printable"), [above, below])?;
+        }
+
+        Ok(())
+    }
+}
+
+fn assert_true<F: FieldExt>(
+    mut layouter: impl Layouter<F>,
+    cells: [AssignedCell<F, F>; 2],
+) -> Result<(), Error> {
+    layouter.assign_region(
+        || "assert true",
+        |mut region| {
+            for cell in cells.iter() {
+                region.constrain_constant(cell.cell(), F::one())?;
+            }
+            Ok(())
+        },
+    )
+}
+
+fn encrypt(plaintext: &[u8; LEN], key: &[u8; LEN]) -> Vec<Fp> {
+    plaintext
+        .iter()
+        .zip(key.iter())
+        .map(|(p, k)| Fp::from((p ^ k) as u64))
+        .collect()
+}
+
+fn main() {
+    let plaintext = b"attack at dawn!!";
+    let key = [
+        0x3a, 0x91, 0x5c, 0x07, 0xe2, 0x48, 0xb6, 0x1d, 0x73, 0xc9, 0x0f, 0xa4, 0x55, 0x8e, 0x2b,
+        0xf0,
+    ];
+    let ciphertext = encrypt(plaintext, &key);
+
+    let circuit = OtpCircuit::new(plaintext, &key);
+    let prover = MockProver::run(K, &circuit, vec![ciphertext.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    assert!(prove_and_verify(K, circuit, &[&ciphertext]).is_ok());
+    println!("printable plaintext: proof verifies");
+
+    // A bell character makes the plaintext unreadable, the ciphertext still
+    // matches but the printable check fails.
+    let mut unreadable = *plaintext;
+    unreadable[6] = 0x07;
+    let ciphertext = encrypt(&unreadable, &key);
+    let prover = MockProver::run(K, &OtpCircuit::new(&unreadable, &key), vec![ciphertext]).unwrap();
+    assert!(prover.verify().is_err());
+    println!("non printable plaintext: rejected");
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::{check_synthesis_determinism, record, AssignmentEvent};
+
+    use super::*;
+
+    #[test]
+    fn test_bounds_assigned_once() {
+        let circuit = OtpCircuit::<Fp>::new(b"attack at dawn!!", &[0x5a; LEN]);
+        let trace = record(K, &circuit).unwrap();
+        let constants = trace
+            .events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    AssignmentEvent::Advice { region: Some(region), .. }
+                        if region == "cached constant"
+                )
+            })
+            .count();
+        // the two bounds, for all LEN bytes
+        assert_eq!(constants, 2);
+    }
+
+    #[test]
+    fn test_synthesis_determinism() {
+        let circuit = OtpCircuit::<Fp>::new(b"attack at dawn!!", &[0x5a; LEN]);
+        check_synthesis_determinism(K, &circuit).unwrap();
+    }
+}
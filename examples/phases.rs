@@ -0,0 +1,86 @@
+use halo2_playground::chips::phase_demo::{rlc, PhaseDemoChip, PhaseDemoConfig};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{Circuit, ConstraintSystem, Error, FirstPhase, SecondPhase},
+};
+
+/// This example shows the smallest useful multi-phase circuit. The values are
+/// witnessed in the first phase, then a challenge is drawn, and the second
+/// phase folds them into a random linear combination using that challenge.
+/// The challenge can't be known while the values are being chosen, which is
+/// what makes RLC based arguments (permutations, multisets, ...) sound.
+///
+/// The tests run with `cargo test`.
+
+#[derive(Default)]
+struct MyCircuit<F: FieldExt> {
+    values: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = PhaseDemoConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // first phase column for the values
+        let value = meta.advice_column();
+        // second phase column, assigned after the challenge is known
+        let acc = meta.advice_column_in(SecondPhase);
+        let challenge = meta.challenge_usable_after(FirstPhase);
+
+        PhaseDemoChip::configure(meta, value, acc, challenge)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PhaseDemoChip::construct(config);
+        let acc = chip.accumulate(layouter.namespace(|| "rlc"), &self.values)?;
+
+        acc.value()
+            .map(|acc| println!("accumulator computed in the second phase: {acc:?}"));
+        Ok(())
+    }
+}
+
+fn main() {
+    let k = 4;
+
+    let values = [1, 2, 3, 4].map(Fp::from);
+    let circuit = MyCircuit::<Fp> {
+        values: values.iter().copied().map(Value::known).collect(),
+    };
+
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Off circuit, the same fold for a challenge of our own choosing.
+    println!("rlc with c = 2: {:?}", rlc(&values, Fp::from(2)));
+    println!("success");
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::check_synthesis_determinism;
+
+    use super::*;
+
+    #[test]
+    fn test_synthesis_determinism() {
+        let circuit = MyCircuit::<Fp> {
+            values: [1, 2, 3, 4].map(|v| Value::known(Fp::from(v))).to_vec(),
+        };
+        check_synthesis_determinism(4, &circuit).unwrap();
+    }
+}
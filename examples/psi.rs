@@ -0,0 +1,279 @@
+use halo2_playground::chips::zero_count::{ZeroCountChip, ZeroCountConfig};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+/// This example computes the size of a private set intersection: the
+/// prover holds a private list of 16 values, the verifier a public list of
+/// 16 values, and the circuit exposes how many of the private values are in
+/// the public list without revealing which.
+///
+/// Comparing every pair with an is equal gadget and OR-ing the results
+/// would take 16 * 16 is zero rows. Instead every private value `v` gets one
+/// running product `(v - p_0) * ... * (v - p_15)` over the public values,
+/// copied from the instance column:
+///
+/// | value | public | product                     | q_first | q_step |
+/// |-------|--------|-----------------------------|---------|--------|
+/// | v     | p_0    | v - p_0                     | 1       | 0      |
+/// | v     | p_1    | product_0 * (v - p_1)       | 0       | 1      |
+/// | ...   | ...    | ...                         | 0       | 1      |
+///
+/// The product is zero exactly when `v` is one of the public values, so the
+/// cardinality is the number of zero products, which the `ZeroCountChip`
+/// counts with one is zero row each and a running sum. That is 256 rows of
+/// two multiplications and 16 is zero rows.
+///
+/// Duplicates count with the multiplicity of the private list: every
+/// private value in the public list counts once, however often it is in the
+/// public list. So private `[5, 5]` against public `[5]` counts 2, and
+/// private `[5]` against public `[5, 5]` counts 1.
+///
+/// The instance is the public list in rows 0..16 and the count in row 16.
+/// The tests run with `cargo test`.
+
+const K: u32 = 9;
+const LEN: usize = 16;
+
+struct PsiCircuit<F: FieldExt> {
+    private: [Value<F>; LEN],
+}
+
+impl<F: FieldExt> PsiCircuit<F> {
+    fn new(private: &[u64; LEN]) -> Self {
+        Self {
+            private: private.map(|value| Value::known(F::from(value))),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PsiConfig<F: FieldExt> {
+    value: Column<Advice>,
+    public: Column<Advice>,
+    product: Column<Advice>,
+    q_first: Selector,
+    q_step: Selector,
+    zero_count_config: ZeroCountConfig<F>,
+    instance: Column<Instance>,
+}
+
+impl<F: FieldExt> Circuit<F> for PsiCircuit<F> {
+    type Config = PsiConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            private: [Value::unknown(); LEN],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let public = meta.advice_column();
+        let product = meta.advice_column();
+        let [count_value, value_inverse, is_zero, count] = [(); 4].map(|_| meta.advice_column());
+        let constants = meta.fixed_column();
+        let instance = meta.instance_column();
+
+        for column in [public, product, count_value, count] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        meta.create_gate("product first", |meta| {
+            let q = meta.query_selector(q_first);
+            let value = meta.query_advice(value, Rotation::cur());
+            let public = meta.query_advice(public, Rotation::cur());
+            let product = meta.query_advice(product, Rotation::cur());
+            vec![q * (product - (value - public))]
+        });
+        meta.create_gate("product step", |meta| {
+            let q = meta.query_selector(q_step);
+            let value = meta.query_advice(value, Rotation::cur());
+            let value_prev = meta.query_advice(value, Rotation::prev());
+            let public = meta.query_advice(public, Rotation::cur());
+            let product = meta.query_advice(product, Rotation::cur());
+            let product_prev = meta.query_advice(product, Rotation::prev());
+            vec![
+                q.clone() * (value.clone() - value_prev),
+                q * (product - product_prev * (value - public)),
+            ]
+        });
+
+        PsiConfig {
+            value,
+            public,
+            product,
+            q_first,
+            q_step,
+            zero_count_config: ZeroCountChip::configure(
+                meta,
+                count_value,
+                value_inverse,
+                is_zero,
+                count,
+                constants,
+            ),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let zero_count = ZeroCountChip::construct(config.zero_count_config.clone());
+
+        let products = self
+            .private
+            .iter()
+            .map(|value| product(&config, layouter.namespace(|| "membership"), *value))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let count = zero_count.count_zeros(layouter.namespace(|| "count"), &products)?;
+        layouter.constrain_instance(count.cell(), config.instance, LEN)
+    }
+}
+
+// `(value - p_0) * ... * (value - p_15)` over the public list
+fn product<F: FieldExt>(
+    config: &PsiConfig<F>,
+    mut layouter: impl Layouter<F>,
+    value: Value<F>,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "product",
+        |mut region| {
+            let mut acc: Option<AssignedCell<F, F>> = None;
+            for offset in 0..LEN {
+                if offset == 0 {
+                    config.q_first.enable(&mut region, offset)?;
+                } else {
+                    config.q_step.enable(&mut region, offset)?;
+                }
+
+                region.assign_advice(|| "value", config.value, offset, || value)?;
+                let public = region.assign_advice_from_instance(
+                    || "public",
+                    config.instance,
+                    offset,
+                    config.public,
+                    offset,
+                )?;
+                let factor = value - public.value().copied();
+                let next = match &acc {
+                    None => factor,
+                    Some(acc) => acc.value().copied() * factor,
+                };
+                acc = Some(region.assign_advice(|| "product", config.product, offset, || next)?);
+            }
+            Ok(acc.unwrap())
+        },
+    )
+}
+
+fn public_inputs(public: &[u64; LEN], count: u64) -> Vec<Fp> {
+    public
+        .iter()
+        .chain([&count])
+        .map(|value| Fp::from(*value))
+        .collect()
+}
+
+// the number of private values that are in the public list
+fn cardinality(private: &[u64; LEN], public: &[u64; LEN]) -> u64 {
+    private
+        .iter()
+        .filter(|value| public.contains(value))
+        .count() as u64
+}
+
+fn verify(private: &[u64; LEN], public: &[u64; LEN], count: u64) -> bool {
+    let circuit = PsiCircuit::<Fp>::new(private);
+    let prover = MockProver::run(K, &circuit, vec![public_inputs(public, count)]).unwrap();
+    prover.verify().is_ok()
+}
+
+fn main() {
+    let private = [
+        3, 14, 15, 92, 65, 35, 89, 79, 32, 38, 46, 26, 43, 38, 32, 79,
+    ];
+    let public = [2, 71, 82, 81, 82, 84, 59, 4, 52, 35, 36, 0, 28, 74, 71, 3];
+    // 3 and 35
+    let count = cardinality(&private, &public);
+    assert_eq!(count, 2);
+
+    assert!(verify(&private, &public, count));
+    println!("{count} common values: verifies");
+
+    assert!(!verify(&private, &public, count + 1));
+    println!("{} common values: rejected", count + 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::check_synthesis_determinism;
+
+    use super::*;
+
+    fn list(start: u64) -> [u64; LEN] {
+        std::array::from_fn(|i| start + i as u64)
+    }
+
+    fn check(private: &[u64; LEN], public: &[u64; LEN], count: u64) {
+        assert_eq!(cardinality(private, public), count);
+        assert!(verify(private, public, count));
+        assert!(!verify(private, public, count + 1));
+        if count > 0 {
+            assert!(!verify(private, public, count - 1));
+        }
+    }
+
+    #[test]
+    fn test_disjoint() {
+        check(&list(0), &list(100), 0);
+    }
+
+    #[test]
+    fn test_identical() {
+        check(&list(7), &list(7), 16);
+        // the same values in another order
+        let mut reversed = list(7);
+        reversed.reverse();
+        check(&list(7), &reversed, 16);
+    }
+
+    #[test]
+    fn test_partial_overlap() {
+        // 10..16 of the private list are 10..16 of the public one
+        check(&list(0), &list(10), 6);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        // a private value counts every time it occurs privately
+        let mut private = list(100);
+        private[..3].copy_from_slice(&[5, 5, 5]);
+        check(&private, &list(0), 3);
+
+        // but only once however often it is in the public list
+        let mut public = list(100);
+        public[..4].copy_from_slice(&[5, 5, 5, 5]);
+        let mut private = list(200);
+        private[0] = 5;
+        check(&private, &public, 1);
+    }
+
+    #[test]
+    fn test_synthesis_determinism() {
+        check_synthesis_determinism(K, &PsiCircuit::<Fp>::new(&list(0))).unwrap();
+    }
+}
@@ -0,0 +1,254 @@
+use halo2_playground::{
+    chips::{
+        hash::{DummyHashChip, DummyHashConfig, HashGadget},
+        poly::{PolynomialEvalChip, PolynomialEvalConfig},
+    },
+    utils::{load_private, load_public},
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+/// This example proves that SHARES public Shamir shares `(x_i, y_i)` lie on
+/// one polynomial of degree DEGREE, a threshold of `t = DEGREE + 1`, whose
+/// constant term is the secret behind a public commitment. The prover
+/// witnesses the coefficients, the `PolynomialEvalChip` evaluates them at
+/// every public `x_i` and the evaluation is constrained to the public `y_i`.
+/// The commitment is `hash(c_0, blinding)` with the `HashGadget`, the
+/// blinding keeps a low entropy secret from being searched for.
+///
+/// Any `t` of the shares reconstruct the secret, so a verifier that accepts
+/// knows every `t` of them reconstruct the same one, the committed secret.
+/// The hash is the `DummyHashChip`, which a real deployment would replace
+/// with a collision resistant one.
+///
+/// The instance is the `x_i` in rows 0..SHARES, the `y_i` in rows
+/// SHARES..2 * SHARES and the commitment in row 2 * SHARES. The tests run
+/// with `cargo test`.
+
+const K: u32 = 6;
+const SHARES: usize = 5;
+
+struct ShamirCircuit<F: FieldExt, const DEGREE: usize> {
+    // DEGREE + 1 of them, `coefficients[0]` is the secret
+    coefficients: Vec<Value<F>>,
+    blinding: Value<F>,
+}
+
+impl<F: FieldExt, const DEGREE: usize> ShamirCircuit<F, DEGREE> {
+    fn new(coefficients: &[F], blinding: F) -> Self {
+        assert_eq!(coefficients.len(), DEGREE + 1);
+        Self {
+            coefficients: coefficients.iter().copied().map(Value::known).collect(),
+            blinding: Value::known(blinding),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ShamirConfig<F: FieldExt, const DEGREE: usize> {
+    advice: Column<Advice>,
+    poly_config: PolynomialEvalConfig<F, DEGREE>,
+    hash_config: DummyHashConfig<F>,
+    instance: Column<Instance>,
+}
+
+impl<F: FieldExt, const DEGREE: usize> Circuit<F> for ShamirCircuit<F, DEGREE> {
+    type Config = ShamirConfig<F, DEGREE>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            coefficients: vec![Value::unknown(); DEGREE + 1],
+            blinding: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let [coeff, x, acc] = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        // the hash rows reuse the evaluation columns under their own selector
+        ShamirConfig {
+            advice,
+            poly_config: PolynomialEvalChip::configure(meta, coeff, x, acc),
+            hash_config: DummyHashChip::configure(meta, coeff, x, acc),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let poly = PolynomialEvalChip::construct(config.poly_config.clone());
+        let hash = DummyHashChip::construct(config.hash_config.clone());
+
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|c| load_private(layouter.namespace(|| "coefficient"), config.advice, *c))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let blinding = load_private(
+            layouter.namespace(|| "blinding"),
+            config.advice,
+            self.blinding,
+        )?;
+
+        for i in 0..SHARES {
+            let x = load_public(
+                layouter.namespace(|| "x"),
+                config.advice,
+                config.instance,
+                i,
+            )?;
+            let y = poly.evaluate(layouter.namespace(|| "share"), &coefficients, x)?;
+            layouter.constrain_instance(y.cell(), config.instance, SHARES + i)?;
+        }
+
+        let commitment = hash.hash_two(
+            layouter.namespace(|| "commitment"),
+            coefficients[0].clone(),
+            blinding,
+        )?;
+        layouter.constrain_instance(commitment.cell(), config.instance, 2 * SHARES)
+    }
+}
+
+// `y_i` at every `x_i`, by Horner's rule
+fn shares(coefficients: &[Fp], xs: &[Fp; SHARES]) -> [Fp; SHARES] {
+    xs.map(|x| {
+        coefficients
+            .iter()
+            .rev()
+            .fold(Fp::zero(), |acc, c| acc * x + c)
+    })
+}
+
+fn public_inputs(xs: &[Fp; SHARES], ys: &[Fp; SHARES], commitment: Fp) -> Vec<Fp> {
+    xs.iter().chain(ys).copied().chain([commitment]).collect()
+}
+
+fn verify<const DEGREE: usize>(
+    coefficients: &[Fp],
+    blinding: Fp,
+    xs: &[Fp; SHARES],
+    ys: &[Fp; SHARES],
+    commitment: Fp,
+) -> bool {
+    let circuit = ShamirCircuit::<Fp, DEGREE>::new(coefficients, blinding);
+    let instance = public_inputs(xs, ys, commitment);
+    let prover = MockProver::run(K, &circuit, vec![instance]).unwrap();
+    prover.verify().is_ok()
+}
+
+fn xs() -> [Fp; SHARES] {
+    [1, 2, 3, 4, 5].map(Fp::from)
+}
+
+fn main() {
+    // a threshold of 3, the secret is 1234
+    let coefficients = [1234, 166, 94].map(Fp::from);
+    let blinding = Fp::from(987654321);
+    let commitment = DummyHashChip::hash(coefficients[0], blinding);
+    let xs = xs();
+    let ys = shares(&coefficients, &xs);
+
+    assert!(verify::<2>(&coefficients, blinding, &xs, &ys, commitment));
+    println!("{SHARES} shares of the committed secret: verifies");
+
+    let mut corrupted = ys;
+    corrupted[3] += Fp::one();
+    assert!(!verify::<2>(
+        &coefficients,
+        blinding,
+        &xs,
+        &corrupted,
+        commitment
+    ));
+    println!("one corrupted share: rejected");
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::check_synthesis_determinism;
+
+    use super::*;
+
+    fn coefficients(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_consistent_shares() {
+        let coefficients = coefficients(&[1234, 166, 94]);
+        let blinding = Fp::from(55);
+        let commitment = DummyHashChip::hash(coefficients[0], blinding);
+        let ys = shares(&coefficients, &xs());
+        assert!(verify::<2>(&coefficients, blinding, &xs(), &ys, commitment));
+
+        // any x_i, not only 1..=SHARES
+        let xs = [17, 4, 99, 1 << 40, 8].map(Fp::from);
+        let ys = shares(&coefficients, &xs);
+        assert!(verify::<2>(&coefficients, blinding, &xs, &ys, commitment));
+    }
+
+    #[test]
+    fn test_corrupted_share() {
+        let coefficients = coefficients(&[1234, 166, 94]);
+        let blinding = Fp::from(55);
+        let commitment = DummyHashChip::hash(coefficients[0], blinding);
+        let ys = shares(&coefficients, &xs());
+        for i in 0..SHARES {
+            let mut corrupted = ys;
+            corrupted[i] += Fp::one();
+            assert!(!verify::<2>(
+                &coefficients,
+                blinding,
+                &xs(),
+                &corrupted,
+                commitment
+            ));
+        }
+    }
+
+    #[test]
+    fn test_wrong_secret() {
+        // consistent shares of another secret against the commitment
+        let blinding = Fp::from(55);
+        let commitment = DummyHashChip::hash(Fp::from(1234), blinding);
+        let other = coefficients(&[1235, 166, 94]);
+        let ys = shares(&other, &xs());
+        assert!(!verify::<2>(&other, blinding, &xs(), &ys, commitment));
+    }
+
+    #[test]
+    fn test_threshold_one() {
+        // a degree 0 polynomial, every share is the secret itself
+        let secret = coefficients(&[42]);
+        let blinding = Fp::from(7);
+        let commitment = DummyHashChip::hash(secret[0], blinding);
+        let ys = shares(&secret, &xs());
+        assert_eq!(ys, [Fp::from(42); SHARES]);
+        assert!(verify::<0>(&secret, blinding, &xs(), &ys, commitment));
+
+        // shares of a degree 1 polynomial lie on no constant one
+        let line = coefficients(&[42, 1]);
+        let ys = shares(&line, &xs());
+        assert!(!verify::<0>(&secret, blinding, &xs(), &ys, commitment));
+    }
+
+    #[test]
+    fn test_synthesis_determinism() {
+        let circuit = ShamirCircuit::<Fp, 2>::new(&coefficients(&[1, 2, 3]), Fp::zero());
+        check_synthesis_determinism(K, &circuit).unwrap();
+    }
+}
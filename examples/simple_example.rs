@@ -333,3 +333,19 @@ fn main() {
     // print it out to use with command-line tools.
     print!("{}", dot_string);
 }
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::check_synthesis_determinism;
+
+    use super::*;
+
+    #[test]
+    fn test_synthesis_determinism() {
+        let circuit = MyCircuit {
+            a: Value::known(Fp::from(2)),
+            b: Value::known(Fp::from(3)),
+        };
+        check_synthesis_determinism(4, &circuit).unwrap();
+    }
+}
@@ -0,0 +1,327 @@
+use halo2_playground::chips::set_membership::{SetMembershipChip, SetMembershipConfig};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{
+        Advice, Challenge, Circuit, Column, ConstraintSystem, Error, Expression, FirstPhase, Fixed,
+        Instance, SecondPhase, Selector,
+    },
+    poly::Rotation,
+};
+
+/// This example proves knowledge of a solution to a public sudoku puzzle.
+/// The 81 givens are the instance, row by row with 0 for a blank, and the
+/// completed grid is private. The circuit checks that
+///
+/// - every cell is in 1..=9, with a `SetMembershipChip` lookup,
+/// - every row, column and box holds each digit exactly once, with a
+///   running product multiset check against the fixed digits 1..=9,
+/// - every nonzero given matches its cell, `given * (cell - given) = 0`.
+///
+/// The multiset check of a group of 9 cells `x_i` against the digits `d_i`
+/// uses a challenge `c` drawn after the grid is committed:
+///
+/// | value | digit | acc                                        | q_first | q_step | q_last |
+/// |-------|-------|--------------------------------------------|---------|--------|--------|
+/// | x_0   | 1     | (c - x_0) / (c - 1)                        | 1       | 0      | 0      |
+/// | x_1   | 2     | acc_0 * (c - x_1) / (c - 2)                | 0       | 1      | 0      |
+/// | ...   | ...   | ...                                        | 0       | 1      | 0      |
+/// | x_8   | 9     | acc_7 * (c - x_8) / (c - 9), must be 1     | 0       | 1      | 1      |
+///
+/// The gates multiply through by `c - d_i`, so there is no division in the
+/// circuit. The final accumulator is 1 exactly when the two products over
+/// `c - x_i` and `c - d_i` agree, which for a random `c` means the cells are
+/// a permutation of the digits.
+///
+/// The grid takes 81 rows, the 27 groups 243 and the range lookups 81, in
+/// columns of their own, so the circuit fits in k = 9.
+///
+/// The tests run with `cargo test`.
+
+const K: u32 = 9;
+
+type Grid = [[u64; 9]; 9];
+
+struct SudokuCircuit<F: FieldExt> {
+    grid: [[Value<F>; 9]; 9],
+}
+
+impl<F: FieldExt> SudokuCircuit<F> {
+    fn new(grid: &Grid) -> Self {
+        Self {
+            grid: grid.map(|row| row.map(|cell| Value::known(F::from(cell)))),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SudokuConfig<F: FieldExt> {
+    cell: Column<Advice>,
+    given: Column<Advice>,
+    q_given: Selector,
+    value: Column<Advice>,
+    digit: Column<Fixed>,
+    acc: Column<Advice>,
+    challenge: Challenge,
+    q_first: Selector,
+    q_step: Selector,
+    q_last: Selector,
+    range_config: SetMembershipConfig<F>,
+    instance: Column<Instance>,
+}
+
+impl<F: FieldExt> Circuit<F> for SudokuCircuit<F> {
+    type Config = SudokuConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            grid: [[Value::unknown(); 9]; 9],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let cell = meta.advice_column();
+        let given = meta.advice_column();
+        let value = meta.advice_column();
+        let digit = meta.fixed_column();
+        // assigned once the challenge is known
+        let acc = meta.advice_column_in(SecondPhase);
+        let challenge = meta.challenge_usable_after(FirstPhase);
+        let instance = meta.instance_column();
+
+        for column in [cell, given, value] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        let q_given = meta.selector();
+        meta.create_gate("given matches", |meta| {
+            let q = meta.query_selector(q_given);
+            let cell = meta.query_advice(cell, Rotation::cur());
+            let given = meta.query_advice(given, Rotation::cur());
+            vec![q * given.clone() * (cell - given)]
+        });
+
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let q_last = meta.selector();
+        meta.create_gate("multiset first", |meta| {
+            let q = meta.query_selector(q_first);
+            let value = meta.query_advice(value, Rotation::cur());
+            let digit = meta.query_fixed(digit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let c = meta.query_challenge(challenge);
+            vec![q * (acc * (c.clone() - digit) - (c - value))]
+        });
+        meta.create_gate("multiset step", |meta| {
+            let q = meta.query_selector(q_step);
+            let value = meta.query_advice(value, Rotation::cur());
+            let digit = meta.query_fixed(digit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let c = meta.query_challenge(challenge);
+            vec![q * (acc * (c.clone() - digit) - acc_prev * (c - value))]
+        });
+        meta.create_gate("multiset last", |meta| {
+            let q = meta.query_selector(q_last);
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - Expression::Constant(F::one()))]
+        });
+
+        // only the lookup of the chip is used, its `is_member` columns stay
+        // empty
+        let [range_value, product, product_inverse, is_member] =
+            [(); 4].map(|_| meta.advice_column());
+        let element = meta.fixed_column();
+        meta.enable_equality(range_value);
+        let range_config = SetMembershipChip::configure(
+            meta,
+            range_value,
+            element,
+            product,
+            product_inverse,
+            is_member,
+            digits(),
+        );
+
+        SudokuConfig {
+            cell,
+            given,
+            q_given,
+            value,
+            digit,
+            acc,
+            challenge,
+            q_first,
+            q_step,
+            q_last,
+            range_config,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let range_chip = SetMembershipChip::construct(config.range_config.clone());
+        range_chip.load_table(&mut layouter.namespace(|| "digits"))?;
+
+        let grid = layouter.assign_region(
+            || "grid",
+            |mut region| {
+                let mut cells = vec![];
+                for (offset, value) in self.grid.iter().flatten().enumerate() {
+                    config.q_given.enable(&mut region, offset)?;
+                    region.assign_advice_from_instance(
+                        || "given",
+                        config.instance,
+                        offset,
+                        config.given,
+                        offset,
+                    )?;
+                    cells.push(region.assign_advice(|| "cell", config.cell, offset, || *value)?);
+                }
+                Ok(cells)
+            },
+        )?;
+
+        for cell in grid.iter() {
+            range_chip.assert_member(layouter.namespace(|| "digit"), cell.clone())?;
+        }
+
+        let challenge = layouter.get_challenge(config.challenge);
+        for group in groups() {
+            let cells: Vec<_> = group.iter().map(|i| grid[*i].clone()).collect();
+            assert_permutation(
+                &config,
+                layouter.namespace(|| "multiset"),
+                &cells,
+                challenge,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn digits<F: FieldExt>() -> Vec<F> {
+    (1..=9).map(F::from).collect()
+}
+
+// the indices of the 9 rows, 9 columns and 9 boxes in the flattened grid
+fn groups() -> Vec<[usize; 9]> {
+    let rows = (0..9).map(|r| std::array::from_fn(|c| 9 * r + c));
+    let columns = (0..9).map(|c| std::array::from_fn(|r| 9 * r + c));
+    let boxes =
+        (0..9).map(|b| std::array::from_fn(|i| 9 * (3 * (b / 3) + i / 3) + 3 * (b % 3) + i % 3));
+    rows.chain(columns).chain(boxes).collect()
+}
+
+fn assert_permutation<F: FieldExt>(
+    config: &SudokuConfig<F>,
+    mut layouter: impl Layouter<F>,
+    cells: &[AssignedCell<F, F>],
+    challenge: Value<F>,
+) -> Result<(), Error> {
+    layouter.assign_region(
+        || "multiset",
+        |mut region| {
+            let mut acc = Value::known(F::one());
+            for (offset, (cell, digit)) in cells.iter().zip(digits::<F>()).enumerate() {
+                if offset == 0 {
+                    config.q_first.enable(&mut region, offset)?;
+                } else {
+                    config.q_step.enable(&mut region, offset)?;
+                }
+                if offset == cells.len() - 1 {
+                    config.q_last.enable(&mut region, offset)?;
+                }
+
+                cell.copy_advice(|| "value", &mut region, config.value, offset)?;
+                region.assign_fixed(|| "digit", config.digit, offset, || Value::known(digit))?;
+                acc = acc
+                    .zip(challenge)
+                    .zip(cell.value())
+                    .map(|((acc, c), x)| acc * (c - x) * (c - digit).invert().unwrap_or(F::zero()));
+                region.assign_advice(|| "acc", config.acc, offset, || acc)?;
+            }
+            Ok(())
+        },
+    )
+}
+
+fn givens(puzzle: &Grid) -> Vec<Fp> {
+    puzzle
+        .iter()
+        .flatten()
+        .map(|given| Fp::from(*given))
+        .collect()
+}
+
+fn verify(grid: &Grid, puzzle: &Grid) -> bool {
+    let circuit = SudokuCircuit::<Fp>::new(grid);
+    let prover = MockProver::run(K, &circuit, vec![givens(puzzle)]).unwrap();
+    prover.verify().is_ok()
+}
+
+fn main() {
+    let puzzle: Grid = [
+        [5, 3, 0, 0, 7, 0, 0, 0, 0],
+        [6, 0, 0, 1, 9, 5, 0, 0, 0],
+        [0, 9, 8, 0, 0, 0, 0, 6, 0],
+        [8, 0, 0, 0, 6, 0, 0, 0, 3],
+        [4, 0, 0, 8, 0, 3, 0, 0, 1],
+        [7, 0, 0, 0, 2, 0, 0, 0, 6],
+        [0, 6, 0, 0, 0, 0, 2, 8, 0],
+        [0, 0, 0, 4, 1, 9, 0, 0, 5],
+        [0, 0, 0, 0, 8, 0, 0, 7, 9],
+    ];
+    let solution: Grid = [
+        [5, 3, 4, 6, 7, 8, 9, 1, 2],
+        [6, 7, 2, 1, 9, 5, 3, 4, 8],
+        [1, 9, 8, 3, 4, 2, 5, 6, 7],
+        [8, 5, 9, 7, 6, 1, 4, 2, 3],
+        [4, 2, 6, 8, 5, 3, 7, 9, 1],
+        [7, 1, 3, 9, 2, 4, 8, 5, 6],
+        [9, 6, 1, 5, 3, 7, 2, 8, 4],
+        [2, 8, 7, 4, 1, 9, 6, 3, 5],
+        [3, 4, 5, 2, 8, 6, 1, 7, 9],
+    ];
+
+    assert!(verify(&solution, &puzzle));
+    println!("valid solution: verifies");
+
+    // A blank cell changed to repeat a digit of its row, the givens still
+    // match but the row is no longer a permutation of 1..=9.
+    let mut duplicate = solution;
+    duplicate[0][2] = 3;
+    assert!(!verify(&duplicate, &puzzle));
+    println!("duplicate in a row: rejected");
+
+    // The same valid grid against a puzzle whose givens it doesn't match.
+    let mut other_puzzle = puzzle;
+    other_puzzle[0][0] = 1;
+    assert!(!verify(&solution, &other_puzzle));
+    println!("givens mismatch: rejected");
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_playground::dev::check_synthesis_determinism;
+
+    use super::*;
+
+    #[test]
+    fn test_synthesis_determinism() {
+        // a valid grid, shifted rows of 1..=9
+        let grid: Grid = std::array::from_fn(|row| {
+            let shift = (row % 3) * 3 + row / 3;
+            std::array::from_fn(|col| ((col + shift) % 9 + 1) as u64)
+        });
+        check_synthesis_determinism(K, &SudokuCircuit::<Fp>::new(&grid)).unwrap();
+    }
+}
@@ -0,0 +1,36 @@
+use std::time::Instant;
+
+use halo2_playground::{chips::is_zero::IsZeroWitness, witness::WitnessPlan};
+use halo2_proofs::{circuit::Value, halo2curves::pasta::Fp};
+
+/// Times the is zero witnesses of 100k rows, one inversion each, computed on
+/// one thread and with rayon. Run with `--release` for meaningful numbers.
+fn main() {
+    const ROWS: u64 = 100_000;
+    let values: Vec<_> = (0..ROWS)
+        .map(|i| Value::known(Fp::from(i * 0x9e37_79b9)))
+        .collect();
+
+    let start = Instant::now();
+    let serial = IsZeroWitness::compute_serial(&values);
+    let serial_time = start.elapsed();
+
+    let start = Instant::now();
+    let parallel = IsZeroWitness::compute(&values);
+    let parallel_time = start.elapsed();
+
+    assert_eq!(serial.len(), parallel.len());
+    for (serial, parallel) in serial.iter().zip(&parallel) {
+        serial
+            .inverse
+            .zip(parallel.inverse)
+            .assert_if_known(|(serial, parallel)| serial == parallel);
+    }
+
+    println!("{ROWS} rows");
+    println!("single threaded: {serial_time:?}");
+    println!(
+        "{} threads: {parallel_time:?}",
+        rayon::current_num_threads()
+    );
+}
@@ -1,2 +1,57 @@
+pub mod assert_boolean;
+pub mod bit_decomposition;
+pub mod bit_reverse;
+pub mod bloom;
+pub mod byte_mul;
+pub mod carry_save;
+pub mod comparator;
+pub mod concat;
+pub mod cond_swap;
+pub mod conditional_copy;
+pub mod const_set;
+pub mod coprime;
+pub mod dot;
+pub mod dummy_griffin;
+pub mod ecdsa;
+pub mod expr;
+pub mod field_inv;
+pub mod field_sign;
+pub mod greater_than;
+pub mod hash;
+pub mod incremental_merkle;
+pub mod interval;
+pub mod inverse;
+pub mod iop;
+pub mod is_equal_lookup;
 pub mod is_zero;
+pub mod less_than;
+pub mod linear_combination;
+pub mod log2;
+pub mod merkle;
+pub mod min_max;
+pub mod parity_bit;
+pub mod phase_demo;
+pub mod poly;
+pub mod public_input_hash;
+pub mod range_check;
+pub mod range_check_u64;
+pub mod scalar_mul_demo;
+pub mod select;
+pub mod set_membership;
+pub mod sha256;
+pub mod sha256_helpers;
+pub mod shift;
+pub mod sorted;
+pub mod sorting_network;
+pub mod sparse_dot;
+pub mod sponge;
+pub mod sum_mod;
+pub mod uniqueness;
+pub mod wide_mul;
+pub mod wiring;
 pub mod xor;
+pub mod xor4_gate;
+pub mod xor_const;
+pub mod xor_tree;
+pub mod xor_u8;
+pub mod zero_count;
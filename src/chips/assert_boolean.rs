@@ -0,0 +1,169 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct AssertBooleanConfig<F: FieldExt> {
+    value: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AssertBooleanChip<F: FieldExt> {
+    config: AssertBooleanConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for AssertBooleanChip<F> {
+    type Config = AssertBooleanConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> AssertBooleanChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("assert boolean", |meta| {
+            let s = meta.query_selector(selector);
+            let v = meta.query_advice(value, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![s * v.clone() * (one - v)] // v is 0 or 1
+        });
+
+        AssertBooleanConfig {
+            value,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Constrains the given cell to be either 0 or 1.
+    pub fn assert_boolean(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "assert boolean",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                value.copy_advice(|| "copy value", &mut region, config.value, 0)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::Circuit,
+    };
+
+    use super::*;
+
+    const K: u32 = 4;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        value: Value<F>,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        assert_boolean_config: AssertBooleanConfig<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let value = meta.advice_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(value);
+
+            TestCircuitConfig {
+                advice,
+                assert_boolean_config: AssertBooleanChip::configure(meta, value),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = AssertBooleanChip::construct(config.assert_boolean_config);
+            let value_cell = layouter.assign_region(
+                || "load value",
+                |mut region| region.assign_advice(|| "value", config.advice, 0, || self.value),
+            )?;
+            chip.assert_boolean(layouter.namespace(|| "assert boolean"), value_cell)
+        }
+    }
+
+    #[test]
+    fn test_boolean_pass() {
+        for v in [0, 1] {
+            let prover = MockProver::run(
+                K,
+                &TestCircuit::<Fp> {
+                    value: Value::known(Fp::from(v)),
+                },
+                vec![],
+            )
+            .unwrap();
+
+            // Should success.
+            assert_eq!(prover.verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_non_boolean_fail() {
+        let prover = MockProver::run(
+            K,
+            &TestCircuit::<Fp> {
+                value: Value::known(Fp::from(2)),
+            },
+            vec![],
+        )
+        .unwrap();
+
+        // Should fail since 2 is not a boolean.
+        assert!(prover.verify().is_err());
+    }
+}
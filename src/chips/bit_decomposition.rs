@@ -0,0 +1,320 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::{testing::mutation_hook, utils::constrain_instances};
+
+/// Returns bit `i` of the little endian representation of `value`.
+pub(crate) fn field_bit<F: FieldExt>(value: &F, i: usize) -> bool {
+    let repr = value.to_repr();
+    let bytes: &[u8] = repr.as_ref();
+    i / 8 < bytes.len() && (bytes[i / 8] >> (i % 8)) & 1 == 1
+}
+
+/// The bits of a decomposed value, least significant first, and the copy of
+/// the value the running sum ends in.
+#[derive(Clone, Debug)]
+pub struct Decomposition<F: FieldExt> {
+    bits: Vec<AssignedCell<F, F>>,
+    value: AssignedCell<F, F>,
+}
+
+impl<F: FieldExt> Decomposition<F> {
+    pub fn bits(&self) -> &[AssignedCell<F, F>] {
+        &self.bits
+    }
+
+    /// Bit `i`, 0 being the least significant.
+    pub fn bit(&self, i: usize) -> &AssignedCell<F, F> {
+        &self.bits[i]
+    }
+
+    pub fn value(&self) -> &AssignedCell<F, F> {
+        &self.value
+    }
+
+    /// Constrains the bits, least significant first, to the consecutive rows
+    /// of `instance` starting at `start_row`.
+    pub fn expose_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        instance: Column<Instance>,
+        start_row: usize,
+    ) -> Result<(), Error> {
+        constrain_instances(layouter, &self.bits, instance, start_row)
+    }
+}
+
+impl<F: FieldExt> From<Decomposition<F>> for Vec<AssignedCell<F, F>> {
+    fn from(decomposition: Decomposition<F>) -> Self {
+        decomposition.bits
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitDecompositionConfig<F: FieldExt, const BITS: usize> {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_step: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Decomposes a value into BITS bits using a running sum, one bit per row.
+///
+/// The bits are laid out most significant first and each row doubles the
+/// previous accumulator and adds its bit:
+///
+/// | bit       | acc                     | q_first | q_step |
+/// |-----------|-------------------------|---------|--------|
+/// | b_{n-1}   | b_{n-1}                 | 1       | 0      |
+/// | b_{n-2}   | 2 * b_{n-1} + b_{n-2}   | 0       | 1      |
+/// | ...       | ...                     | 0       | 1      |
+/// | b_0       | value                   | 0       | 1      |
+///
+/// The last accumulator is copy constrained to the input, which fails for
+/// any value that doesn't fit in BITS bits.
+#[derive(Clone, Debug)]
+pub struct BitDecompositionChip<F: FieldExt, const BITS: usize> {
+    config: BitDecompositionConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for BitDecompositionChip<F, BITS> {
+    type Config = BitDecompositionConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> BitDecompositionChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        meta.create_gate("bit decomposition first", |meta| {
+            let q = meta.query_selector(q_first);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![
+                q.clone() * bit.clone() * (one - bit.clone()), // bit is 0 or 1
+                q * (acc - bit),
+            ]
+        });
+
+        meta.create_gate("bit decomposition step", |meta| {
+            let q = meta.query_selector(q_step);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+            vec![
+                q.clone() * bit.clone() * (one - bit.clone()), // bit is 0 or 1
+                q * (acc - acc_prev * two - bit),
+            ]
+        });
+
+        BitDecompositionConfig {
+            bit,
+            acc,
+            q_first,
+            q_step,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the bits of `value`, least significant first.
+    pub fn decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<Decomposition<F>, Error> {
+        assert!(BITS > 0, "cannot decompose into 0 bits");
+        let config = self.config();
+
+        layouter.assign_region(
+            || "bit decomposition",
+            |mut region| {
+                let mut bits = Vec::with_capacity(BITS);
+                let mut acc = Value::known(F::zero());
+                for offset in 0..BITS {
+                    let index = BITS - 1 - offset;
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+
+                    let bit = value.value().map(|v| F::from(field_bit(v, index)));
+                    acc = acc * Value::known(F::from(2)) + bit;
+
+                    bits.push(region.assign_advice(
+                        || "bit",
+                        config.bit,
+                        offset,
+                        || mutation_hook("BitDecompositionChip bit", bit),
+                    )?);
+                    if offset < BITS - 1 {
+                        region.assign_advice(|| "acc", config.acc, offset, || acc)?;
+                    }
+                }
+                let value = value.copy_advice(|| "value", &mut region, config.acc, BITS - 1)?;
+
+                bits.reverse();
+                Ok(Decomposition { bits, value })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+    use crate::testing::mutation_test;
+
+    const K: u32 = 5;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        value: Value<F>,
+        // the instance row of the least significant bit
+        start_row: usize,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        decomposition_config: BitDecompositionConfig<F, 4>,
+        instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(bit);
+            meta.enable_equality(acc);
+            meta.enable_equality(instance);
+
+            TestCircuitConfig {
+                advice,
+                decomposition_config: BitDecompositionChip::configure(meta, bit, acc),
+                instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = BitDecompositionChip::construct(config.decomposition_config);
+            let value = layouter.assign_region(
+                || "load value",
+                |mut region| region.assign_advice(|| "value", config.advice, 0, || self.value),
+            )?;
+
+            let decomposition = chip.decompose(layouter.namespace(|| "decompose"), value)?;
+            decomposition.expose_public(&mut layouter, config.instance, self.start_row)
+        }
+    }
+
+    fn run(value: u64, bits: [u64; 4]) -> MockProver<Fp> {
+        MockProver::run(
+            K,
+            &TestCircuit::<Fp> {
+                value: Value::known(Fp::from(value)),
+                start_row: 0,
+            },
+            vec![bits.map(Fp::from).to_vec()],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decompose_pass() {
+        // Bits are least significant first.
+        assert_eq!(run(0b1011, [1, 1, 0, 1]).verify(), Ok(()));
+        assert_eq!(run(0b1111, [1, 1, 1, 1]).verify(), Ok(()));
+        assert_eq!(run(0, [0, 0, 0, 0]).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_decompose_wrong_bits_fail() {
+        assert!(run(0b1011, [1, 1, 0, 0]).verify().is_err());
+    }
+
+    #[test]
+    fn test_decompose_out_of_range_fail() {
+        // 16 doesn't fit in 4 bits.
+        assert!(run(16, [0, 0, 0, 0]).verify().is_err());
+    }
+
+    #[test]
+    fn test_expose_public() {
+        let circuit = TestCircuit {
+            value: Value::known(Fp::from(0b1011)),
+            start_row: 1,
+        };
+        let bits = [1u64, 1, 0, 1].map(Fp::from);
+        let shifted = [Fp::zero()].into_iter().chain(bits).collect();
+        assert_eq!(
+            MockProver::run(K, &circuit, vec![shifted])
+                .unwrap()
+                .verify(),
+            Ok(())
+        );
+        let prover = MockProver::run(K, &circuit, vec![bits.to_vec()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_mutations() {
+        let build = || TestCircuit::<Fp> {
+            value: Value::known(Fp::from(0b1011)),
+            start_row: 0,
+        };
+        let report = mutation_test(K, build, vec![[1u64, 1, 0, 1].map(Fp::from).to_vec()]);
+        assert_eq!(report.mutations.len(), 4);
+        report.assert_all_caught();
+    }
+}
@@ -0,0 +1,177 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+use super::bit_decomposition::{field_bit, BitDecompositionChip, BitDecompositionConfig};
+
+#[derive(Clone, Debug)]
+pub struct BitReverseConfig<F: FieldExt, const N: usize> {
+    decomposition: BitDecompositionConfig<F, N>,
+    reversed: Column<Advice>,
+}
+
+/// Returns the N-bit value with the bits of the input in reverse order, e.g.
+/// `0b0001 -> 0b1000` for N = 4.
+///
+/// The input and the witnessed result are both decomposed into N bits and
+/// bit `i` of one is copy constrained to bit `N - 1 - i` of the other. The
+/// input must fit in N bits, the decomposition fails otherwise.
+#[derive(Clone, Debug)]
+pub struct BitReverseChip<F: FieldExt, const N: usize> {
+    config: BitReverseConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for BitReverseChip<F, N> {
+    type Config = BitReverseConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> BitReverseChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `bit` and `acc` go to the `BitDecompositionChip`, `bit` also needs
+    /// equality for the copies between the two decompositions.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+        reversed: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        BitReverseConfig {
+            decomposition: BitDecompositionChip::configure(meta, bit, acc),
+            reversed,
+        }
+    }
+
+    pub fn bit_reverse(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let decomposition = BitDecompositionChip::construct(config.decomposition.clone());
+
+        let reversed = layouter.assign_region(
+            || "bit reverse",
+            |mut region| {
+                let reversed = value.value().map(|v| {
+                    (0..N).fold(F::zero(), |acc, i| {
+                        acc * F::from(2) + F::from(field_bit(v, i))
+                    })
+                });
+                region.assign_advice(|| "reversed", config.reversed, 0, || reversed)
+            },
+        )?;
+
+        let bits = decomposition.decompose(layouter.namespace(|| "decompose value"), value)?;
+        let reversed_bits = decomposition.decompose(
+            layouter.namespace(|| "decompose reversed"),
+            reversed.clone(),
+        )?;
+
+        layouter.assign_region(
+            || "reverse bits",
+            |mut region| {
+                for (bit, reversed_bit) in bits.bits().iter().zip(reversed_bits.bits().iter().rev())
+                {
+                    region.constrain_equal(bit.cell(), reversed_bit.cell())?;
+                }
+                Ok(())
+            },
+        )?;
+
+        Ok(reversed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    // outputs the reversal and the reversal of that
+    struct BitReverseTest;
+
+    impl<F: FieldExt> HarnessChip<F> for BitReverseTest {
+        type Config = BitReverseConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let reversed = meta.advice_column();
+
+            for column in [bit, acc, reversed] {
+                meta.enable_equality(column);
+            }
+
+            BitReverseChip::configure(meta, bit, acc, reversed)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = BitReverseChip::construct(config);
+            let reversed =
+                chip.bit_reverse(layouter.namespace(|| "reverse"), witnesses[0].clone())?;
+            let round_trip =
+                chip.bit_reverse(layouter.namespace(|| "reverse again"), reversed.clone())?;
+            Ok(vec![reversed, round_trip])
+        }
+    }
+
+    #[test]
+    fn test_bit_reverse() {
+        let harness = TestHarness::auto();
+        for (value, reversed) in [(0b0001, 0b1000), (0b0110, 0b0110), (0b1101, 0b1011)] {
+            harness.expect_pass(
+                BitReverseTest,
+                vec![Fp::from(value)],
+                vec![Fp::from(reversed), Fp::from(value)],
+            );
+        }
+        harness.expect_fail(
+            BitReverseTest,
+            vec![Fp::from(0b0001)],
+            vec![Fp::from(0b0001), Fp::from(0b0001)],
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let harness = TestHarness::auto();
+        for value in 0..16 {
+            let reversed = (0..4).fold(0, |acc, i| (acc << 1) | ((value >> i) & 1));
+            harness.expect_pass(
+                BitReverseTest,
+                vec![Fp::from(value)],
+                vec![Fp::from(reversed), Fp::from(value)],
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_value_fail() {
+        // 0b10001 doesn't fit in 4 bits
+        TestHarness::auto().expect_fail(
+            BitReverseTest,
+            vec![Fp::from(0b10001)],
+            vec![Fp::from(0b1000), Fp::from(0b0001)],
+        );
+    }
+}
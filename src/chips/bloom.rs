@@ -0,0 +1,441 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::{
+    expr::pow2_recompose,
+    range_check::{RangeCheckChip, RangeCheckConfig},
+};
+use crate::{debug_assert_witness, utils::fits_in_bits};
+
+/// Width of the values and randomizers, and of the range checks.
+pub const WORD_BITS: usize = 32;
+
+#[derive(Clone, Debug)]
+pub struct BloomMembershipConfig<F: FieldExt, const K: usize, const M: usize> {
+    randomizers: [u64; K],
+    bitmap: [bool; M],
+    value: Column<Advice>,
+    randomizer: Column<Fixed>,
+    high: Column<Advice>,
+    low: Column<Advice>,
+    rem: Column<Advice>,
+    index: Column<Advice>,
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    q_hash: Selector,
+    q_first: Selector,
+    q_step: Selector,
+    table_index: TableColumn,
+    table_bit: TableColumn,
+    range_check: RangeCheckConfig<F, WORD_BITS>,
+}
+
+/// Membership of a 32-bit value in a Bloom filter of M bits fixed at keygen,
+/// with K hashes.
+///
+/// Hash `i` is multiply-shift with randomizer `r_i`: `low = r_i * v mod 2^32`
+/// from `r_i * v = high * 2^32 + low`, reduced to `index = low * M / 2^32`
+/// rounded down. That division is `rem = low * M - index * 2^32` with `rem`
+/// in `[0, 2^32)`. `value`, `high`, `low` and `rem` are range checked to 32
+/// bits, without the check on `value` the hash gate alone holds for e.g.
+/// `v = 2^32` with `high = r_i` and `low = 0`. `(index, bit)` is looked up
+/// in the bitmap, which also bounds the index by M. One row per hash:
+///
+/// | value | randomizer | high | low | rem | index | bit | acc             |
+/// |-------|------------|------|-----|-----|-------|-----|-----------------|
+/// | v     | r_0        | ...  | ... | ... | i_0   | b_0 | b_0             |
+/// | v     | r_1        | ...  | ... | ... | i_1   | b_1 | acc_0 * b_1     |
+///
+/// `is_member` is the last `acc`. Members always return 1, a non member
+/// returns 1 with probability about `(1 - e^(-K * n / M))^K` for n members,
+/// e.g. 0.5% for 4 members at K = 3 and M = 64.
+#[derive(Clone, Debug)]
+pub struct BloomMembershipChip<F: FieldExt, const K: usize, const M: usize> {
+    config: BloomMembershipConfig<F, K, M>,
+}
+
+impl<F: FieldExt, const K: usize, const M: usize> Chip<F> for BloomMembershipChip<F, K, M> {
+    type Config = BloomMembershipConfig<F, K, M>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+// (high, low, rem, index) of one hash
+fn hash_witness<const M: usize>(randomizer: u64, value: u64) -> (u128, u128, u128, usize) {
+    let product = randomizer as u128 * value as u128;
+    let low = product & ((1 << WORD_BITS) - 1);
+    let index = (low * M as u128) >> WORD_BITS;
+    let rem = low * M as u128 - (index << WORD_BITS);
+    (product >> WORD_BITS, low, rem, index as usize)
+}
+
+impl<F: FieldExt, const K: usize, const M: usize> BloomMembershipChip<F, K, M> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `high`, `low` and `rem` are copied into the range checks and need
+    /// equality, as do `value` and `acc`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        randomizer: Column<Fixed>,
+        [high, low, rem]: [Column<Advice>; 3],
+        index: Column<Advice>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+        range_check: RangeCheckConfig<F, WORD_BITS>,
+        randomizers: [u64; K],
+        bitmap: [bool; M],
+    ) -> <Self as Chip<F>>::Config {
+        assert!(K > 0, "no hashes");
+        assert!(M <= 1 << 16, "the bitmap table has M rows");
+        assert!(
+            randomizers.iter().all(|r| *r < 1 << WORD_BITS),
+            "randomizers must be 32 bit"
+        );
+        let q_hash = meta.complex_selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let table_index = meta.lookup_table_column();
+        let table_bit = meta.lookup_table_column();
+
+        meta.create_gate("bloom hash", |meta| {
+            let q = meta.query_selector(q_hash);
+            let value = meta.query_advice(value, Rotation::cur());
+            let randomizer = meta.query_fixed(randomizer, Rotation::cur());
+            let high = meta.query_advice(high, Rotation::cur());
+            let low = meta.query_advice(low, Rotation::cur());
+            let rem = meta.query_advice(rem, Rotation::cur());
+            let index = meta.query_advice(index, Rotation::cur());
+            let m = Expression::Constant(F::from(M as u64));
+            vec![
+                q.clone() * (randomizer * value - pow2_recompose(&[low.clone(), high], WORD_BITS)),
+                q * (low * m - pow2_recompose(&[rem, index], WORD_BITS)),
+            ]
+        });
+
+        let first = F::from(bitmap[0]);
+        meta.lookup("bloom bitmap", |meta| {
+            let q = meta.query_selector(q_hash);
+            let index = meta.query_advice(index, Rotation::cur());
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let not_q = Expression::Constant(F::one()) - q.clone();
+            vec![
+                (q.clone() * index, table_index),
+                (q * bit + not_q * Expression::Constant(first), table_bit),
+            ]
+        });
+
+        meta.create_gate("bloom first", |meta| {
+            let q = meta.query_selector(q_first);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - bit)]
+        });
+
+        meta.create_gate("bloom step", |meta| {
+            let q = meta.query_selector(q_step);
+            let value = meta.query_advice(value, Rotation::cur());
+            let value_prev = meta.query_advice(value, Rotation::prev());
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            vec![q.clone() * (value - value_prev), q * (acc - acc_prev * bit)]
+        });
+
+        BloomMembershipConfig {
+            randomizers,
+            bitmap,
+            value,
+            randomizer,
+            high,
+            low,
+            rem,
+            index,
+            bit,
+            acc,
+            q_hash,
+            q_first,
+            q_step,
+            table_index,
+            table_bit,
+            range_check,
+        }
+    }
+
+    /// The bitmap with the bits of every member set.
+    pub fn bitmap(randomizers: [u64; K], members: &[u64]) -> [bool; M] {
+        let mut bitmap = [false; M];
+        for member in members {
+            for index in Self::indices(randomizers, *member) {
+                bitmap[index] = true;
+            }
+        }
+        bitmap
+    }
+
+    /// The K bitmap positions of `value`.
+    pub fn indices(randomizers: [u64; K], value: u64) -> [usize; K] {
+        randomizers.map(|randomizer| hash_witness::<M>(randomizer, value).3)
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_table(
+            || "bloom bitmap",
+            |mut table| {
+                for (offset, bit) in config.bitmap.iter().enumerate() {
+                    table.assign_cell(
+                        || "index",
+                        config.table_index,
+                        offset,
+                        || Value::known(F::from(offset as u64)),
+                    )?;
+                    table.assign_cell(
+                        || "bit",
+                        config.table_bit,
+                        offset,
+                        || Value::known(F::from(*bit)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns 1 if all K bits of `value` are set and 0 otherwise.
+    pub fn is_member(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let (is_member, words) = layouter.assign_region(
+            || "bloom is member",
+            |mut region| {
+                debug_assert_witness!(
+                    "BloomMembershipChip",
+                    "bloom is member",
+                    value.value(),
+                    |v: &F| fits_in_bits(v, WORD_BITS),
+                    format!("a {WORD_BITS} bit value"),
+                );
+                // truncated for wide values, which fail the range check
+                let v = value.value().map(|v| v.get_lower_128() as u64);
+
+                let mut acc = Value::known(F::one());
+                let mut is_member = None;
+                let mut words = vec![];
+                for (offset, randomizer) in config.randomizers.iter().enumerate() {
+                    config.q_hash.enable(&mut region, offset)?;
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                        value.copy_advice(|| "value", &mut region, config.value, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                        region.assign_advice(
+                            || "value",
+                            config.value,
+                            offset,
+                            || value.value().copied(),
+                        )?;
+                    }
+                    region.assign_fixed(
+                        || "randomizer",
+                        config.randomizer,
+                        offset,
+                        || Value::known(F::from(*randomizer)),
+                    )?;
+
+                    let hash = v.map(|v| hash_witness::<M>(*randomizer, v));
+                    for (name, column, word) in [
+                        ("high", config.high, hash.map(|h| h.0)),
+                        ("low", config.low, hash.map(|h| h.1)),
+                        ("rem", config.rem, hash.map(|h| h.2)),
+                    ] {
+                        words.push(region.assign_advice(
+                            || name,
+                            column,
+                            offset,
+                            || word.map(F::from_u128),
+                        )?);
+                    }
+                    region.assign_advice(
+                        || "index",
+                        config.index,
+                        offset,
+                        || hash.map(|h| F::from(h.3 as u64)),
+                    )?;
+
+                    let bit = hash.map(|h| F::from(config.bitmap[h.3]));
+                    region.assign_advice(|| "bit", config.bit, offset, || bit)?;
+                    acc = acc * bit;
+                    is_member = Some(region.assign_advice(|| "acc", config.acc, offset, || acc)?);
+                }
+
+                Ok((is_member.expect("K > 0"), words))
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::construct(config.range_check.clone());
+        range_check_chip.range_check(layouter.namespace(|| "range check value"), value)?;
+        for word in words {
+            range_check_chip.range_check(layouter.namespace(|| "range check word"), word)?;
+        }
+
+        Ok(is_member)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        dev::without_witness_checks,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    const RANDOMIZERS: [u64; 3] = [0x9e37_79b1, 0x85eb_ca77, 0xc2b2_ae3d];
+    const MEMBERS: [u64; 4] = [3, 17, 42, 0xdead_beef];
+
+    type Bloom = BloomMembershipChip<Fp, 3, 64>;
+
+    struct BloomTest;
+
+    impl<F: FieldExt> HarnessChip<F> for BloomTest {
+        type Config = BloomMembershipConfig<F, 3, 64>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let randomizer = meta.fixed_column();
+            let words = [(); 3].map(|_| meta.advice_column());
+            let index = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let range_bit = meta.advice_column();
+            let range_acc = meta.advice_column();
+
+            for column in [value, acc, range_acc].into_iter().chain(words) {
+                meta.enable_equality(column);
+            }
+
+            let range_check = RangeCheckChip::configure(meta, range_bit, range_acc);
+            let bitmap = BloomMembershipChip::<F, 3, 64>::bitmap(RANDOMIZERS, &MEMBERS);
+            BloomMembershipChip::configure(
+                meta,
+                value,
+                randomizer,
+                words,
+                index,
+                bit,
+                acc,
+                range_check,
+                RANDOMIZERS,
+                bitmap,
+            )
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = BloomMembershipChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "bitmap"))?;
+            let is_member =
+                chip.is_member(layouter.namespace(|| "is member"), witnesses[0].clone())?;
+            Ok(vec![is_member])
+        }
+    }
+
+    fn is_positive(value: u64) -> bool {
+        let bitmap = Bloom::bitmap(RANDOMIZERS, &MEMBERS);
+        Bloom::indices(RANDOMIZERS, value)
+            .iter()
+            .all(|index| bitmap[*index])
+    }
+
+    #[test]
+    fn test_member() {
+        let harness = TestHarness::auto();
+        for member in MEMBERS {
+            harness.expect_pass(BloomTest, vec![Fp::from(member)], vec![Fp::one()]);
+        }
+        harness.expect_fail(BloomTest, vec![Fp::from(MEMBERS[0])], vec![Fp::zero()]);
+    }
+
+    #[test]
+    fn test_non_member() {
+        let harness = TestHarness::auto();
+        let non_member = (0..).find(|v| !is_positive(*v)).unwrap();
+        harness.expect_pass(BloomTest, vec![Fp::from(non_member)], vec![Fp::zero()]);
+        harness.expect_fail(BloomTest, vec![Fp::from(non_member)], vec![Fp::one()]);
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        // about 0.5% expected, false positives verify like members
+        let positives = (0..10_000)
+            .filter(|v| !MEMBERS.contains(v) && is_positive(*v))
+            .count();
+        assert!(positives < 200, "{positives} false positives");
+
+        if let Some(false_positive) = (0..10_000).find(|v| !MEMBERS.contains(v) && is_positive(*v))
+        {
+            TestHarness::auto().expect_pass(
+                BloomTest,
+                vec![Fp::from(false_positive)],
+                vec![Fp::one()],
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_witness() {
+        for value in [0, 1, 42, u32::MAX as u64] {
+            for randomizer in RANDOMIZERS {
+                let (high, low, rem, index) = hash_witness::<64>(randomizer, value);
+                assert_eq!(high * (1 << 32) + low, randomizer as u128 * value as u128);
+                assert!(low < 1 << 32 && rem < 1 << 32 && index < 64);
+                assert_eq!(low * 64, index as u128 * (1 << 32) + rem);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "BloomMembershipChip")]
+    fn test_wide_value_panics() {
+        TestHarness::auto().run(BloomTest, vec![Fp::from(1 << 32)], vec![Fp::zero()]);
+    }
+
+    #[test]
+    fn test_wide_value_fail() {
+        // 2^32 satisfies every hash gate with high = r_i and low = 0, as 0
+        // would, only the range check on the value rejects it
+        let harness = TestHarness::auto();
+        let zero_is_member = is_positive(0);
+        without_witness_checks(|| {
+            for wide in [Fp::from(1 << 32), -Fp::one()] {
+                harness.expect_fail(BloomTest, vec![wide], vec![Fp::from(zero_is_member)]);
+                harness.expect_fail(BloomTest, vec![wide], vec![Fp::from(!zero_is_member)]);
+            }
+        });
+    }
+}
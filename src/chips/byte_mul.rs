@@ -0,0 +1,355 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::wide_mul::WideMulOutput;
+use crate::{
+    debug_assert_witness,
+    utils::{fits_in_bits, min_k_for_rows},
+};
+
+/// The `2^(2 * BITS)` rows `(a, b, lo, hi)` with `a * b = hi * 2^BITS + lo`.
+#[derive(Clone, Debug)]
+pub struct MulTableConfig<F: FieldExt, const BITS: usize = 8> {
+    pub a: TableColumn,
+    pub b: TableColumn,
+    pub lo: TableColumn,
+    pub hi: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> MulTableConfig<F, BITS> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            a: meta.lookup_table_column(),
+            b: meta.lookup_table_column(),
+            lo: meta.lookup_table_column(),
+            hi: meta.lookup_table_column(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The smallest k the table fits in, 17 for bytes.
+    pub fn required_k() -> u32 {
+        min_k_for_rows(1 << (2 * BITS))
+    }
+
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load mul table",
+            |mut table| {
+                let mut offset = 0;
+                for a in 0..(1u64 << BITS) {
+                    for b in 0..(1u64 << BITS) {
+                        let product = a * b;
+                        for (name, column, value) in [
+                            ("a", self.a, a),
+                            ("b", self.b, b),
+                            ("lo", self.lo, product & ((1 << BITS) - 1)),
+                            ("hi", self.hi, product >> BITS),
+                        ] {
+                            table.assign_cell(
+                                || name,
+                                column,
+                                offset,
+                                || Value::known(F::from(value)),
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ByteMulConfig<F: FieldExt, const BITS: usize = 8> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    lo: Column<Advice>,
+    hi: Column<Advice>,
+    q_lookup: Selector,
+    pub mul_table: MulTableConfig<F, BITS>,
+}
+
+/// Multiplies BITS-bit limbs, bytes by default, with one lookup of
+/// `(a, b, lo, hi)` into the `MulTableConfig`. The lookup also range checks
+/// the operands and both halves of the product. Disabled rows look up
+/// `(0, 0, 0, 0)`, which is in the table.
+///
+/// The byte table needs `k >= 17`, tests use `BITS = 4` and `k = 9`.
+#[derive(Clone, Debug)]
+pub struct ByteMulChip<F: FieldExt, const BITS: usize = 8> {
+    config: ByteMulConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for ByteMulChip<F, BITS> {
+    type Config = ByteMulConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> ByteMulChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        lo: Column<Advice>,
+        hi: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 16, "the mul table has 2^(2 * BITS) rows");
+        let q_lookup = meta.complex_selector();
+        let mul_table = MulTableConfig::configure(meta);
+
+        // the operands are copied in and the result halves copied out
+        for column in [a, b, lo, hi] {
+            meta.enable_equality(column);
+        }
+
+        meta.lookup("byte mul", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let lo = meta.query_advice(lo, Rotation::cur());
+            let hi = meta.query_advice(hi, Rotation::cur());
+            vec![
+                (q.clone() * a, mul_table.a),
+                (q.clone() * b, mul_table.b),
+                (q.clone() * lo, mul_table.lo),
+                (q * hi, mul_table.hi),
+            ]
+        });
+
+        ByteMulConfig {
+            a,
+            b,
+            lo,
+            hi,
+            q_lookup,
+            mul_table,
+        }
+    }
+
+    /// The smallest k the table fits in, see `MulTableConfig::required_k`.
+    pub fn required_k() -> u32 {
+        MulTableConfig::<F, BITS>::required_k()
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config().mul_table.load(layouter)
+    }
+
+    /// Returns `lo` and `hi` with `a * b = hi * 2^BITS + lo`.
+    pub fn mul_bytes(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<WideMulOutput<F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "byte mul",
+            |mut region| {
+                config.q_lookup.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "b", &mut region, config.b, 0)?;
+
+                for operand in [&a, &b] {
+                    debug_assert_witness!(
+                        "ByteMulChip",
+                        "byte mul",
+                        operand.value(),
+                        |v: &F| fits_in_bits(v, BITS),
+                        format!("a {BITS} bit operand"),
+                    );
+                }
+
+                let product = a
+                    .value()
+                    .zip(b.value())
+                    // wraps for wide operands, which the lookup rejects
+                    .map(|(a, b)| a.get_lower_128().wrapping_mul(b.get_lower_128()));
+                let lo = product.map(|p| F::from_u128(p & ((1 << BITS) - 1)));
+                let hi = product.map(|p| F::from_u128(p >> BITS));
+
+                let lo = region.assign_advice(|| "lo", config.lo, 0, || lo)?;
+                let hi = region.assign_advice(|| "hi", config.hi, 0, || hi)?;
+                Ok(WideMulOutput::new(lo, hi))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        dev::{run_auto, without_witness_checks},
+        testing::{HarnessChip, TestCircuit, TestHarness},
+    };
+
+    fn configure<F: FieldExt, const BITS: usize>(
+        meta: &mut ConstraintSystem<F>,
+    ) -> ByteMulConfig<F, BITS> {
+        let [a, b, lo, hi] = [(); 4].map(|_| meta.advice_column());
+        ByteMulChip::configure(meta, a, b, lo, hi)
+    }
+
+    struct MulTest<const BITS: usize>;
+
+    impl<F: FieldExt, const BITS: usize> HarnessChip<F> for MulTest<BITS> {
+        type Config = ByteMulConfig<F, BITS>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = ByteMulChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "mul table"))?;
+            let (lo, hi) = chip
+                .mul_bytes(
+                    layouter.namespace(|| "mul"),
+                    witnesses[0].clone(),
+                    witnesses[1].clone(),
+                )?
+                .into();
+            Ok(vec![lo, hi])
+        }
+    }
+
+    // the lookup row laid out by hand with the lo and hi witnesses given
+    struct ForgedMulTest;
+
+    impl<F: FieldExt> HarnessChip<F> for ForgedMulTest {
+        type Config = ByteMulConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            config
+                .mul_table
+                .load(&mut layouter.namespace(|| "mul table"))?;
+            layouter.assign_region(
+                || "forged mul",
+                |mut region| {
+                    config.q_lookup.enable(&mut region, 0)?;
+                    for (column, witness) in [config.a, config.b, config.lo, config.hi]
+                        .into_iter()
+                        .zip(witnesses.iter())
+                    {
+                        witness.copy_advice(|| "forged", &mut region, column, 0)?;
+                    }
+                    Ok(())
+                },
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    fn mul<const BITS: usize>(a: u64, b: u64) -> Vec<Fp> {
+        vec![
+            Fp::from((a * b) & ((1 << BITS) - 1)),
+            Fp::from((a * b) >> BITS),
+        ]
+    }
+
+    #[test]
+    fn test_required_k() {
+        assert_eq!(ByteMulChip::<Fp, 8>::required_k(), 17);
+        assert_eq!(ByteMulChip::<Fp, 4>::required_k(), 9);
+
+        let circuit = TestCircuit::<Fp, MulTest<4>>::new(vec![Fp::from(7), Fp::from(9)]);
+        let (k, _) = run_auto(&circuit, vec![mul::<4>(7, 9)]).unwrap();
+        assert_eq!(k, ByteMulChip::<Fp, 4>::required_k());
+    }
+
+    #[test]
+    fn test_mul_exhaustive() {
+        let harness = TestHarness::new(ByteMulChip::<Fp, 4>::required_k());
+        for a in 0..16 {
+            for b in 0..16 {
+                harness.expect_pass(MulTest::<4>, vec![Fp::from(a), Fp::from(b)], mul::<4>(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_forged_lo_hi_fail() {
+        let harness = TestHarness::auto();
+        // 13 * 11 = 143 = 8 * 16 + 15
+        let forged = |lo: u64, hi: u64| [13, 11, lo, hi].map(Fp::from).to_vec();
+        harness.expect_pass(ForgedMulTest, forged(15, 8), vec![]);
+        harness.expect_fail(ForgedMulTest, forged(8, 15), vec![]);
+        harness.expect_fail(ForgedMulTest, forged(0, 9), vec![]);
+        // the right product with an unreduced lo
+        harness.expect_fail(ForgedMulTest, forged(31, 7), vec![]);
+        harness.expect_fail(ForgedMulTest, forged(143, 0), vec![]);
+    }
+
+    #[test]
+    fn test_wide_operand_fail() {
+        without_witness_checks(|| {
+            TestHarness::auto().expect_fail(
+                MulTest::<4>,
+                vec![Fp::from(16), Fp::from(1)],
+                mul::<4>(16, 1),
+            )
+        });
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "ByteMulChip")]
+    fn test_wide_operand_panics() {
+        TestHarness::auto().run(
+            MulTest::<4>,
+            vec![Fp::from(16), Fp::from(1)],
+            mul::<4>(16, 1),
+        );
+    }
+
+    #[test]
+    #[ignore = "needs k = 17"]
+    fn test_mul_bytes() {
+        let harness = TestHarness::new(ByteMulChip::<Fp, 8>::required_k());
+        for (a, b) in [(0, 0), (255, 255), (0xa5, 0x3c), (1, 200)] {
+            harness.expect_pass(MulTest::<8>, vec![Fp::from(a), Fp::from(b)], mul::<8>(a, b));
+        }
+        harness.expect_fail(
+            MulTest::<8>,
+            vec![Fp::from(255), Fp::from(255)],
+            mul::<8>(255, 254),
+        );
+    }
+}
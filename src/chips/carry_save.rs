@@ -0,0 +1,681 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use super::xor::bitwise::BitwiseChip;
+use crate::utils::constrain_instances;
+
+/// A `BITS * CHUNKS` bit word as BITS bit chunks, least significant first.
+pub type Word<F, const CHUNKS: usize> = [AssignedCell<F, F>; CHUNKS];
+
+/// The result of `csa`, `a + b + c == sum + carry`.
+#[derive(Clone, Debug)]
+pub struct CsaOutput<F: FieldExt, const CHUNKS: usize> {
+    sum: Word<F, CHUNKS>,
+    carry: Word<F, CHUNKS>,
+}
+
+impl<F: FieldExt, const CHUNKS: usize> CsaOutput<F, CHUNKS> {
+    pub fn sum(&self) -> &Word<F, CHUNKS> {
+        &self.sum
+    }
+
+    /// The carry word, already shifted into place.
+    pub fn carry(&self) -> &Word<F, CHUNKS> {
+        &self.carry
+    }
+
+    /// Constrains the chunks of `sum` and then those of `carry` to the
+    /// consecutive rows of `instance` starting at `start_row`.
+    pub fn expose_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        instance: Column<Instance>,
+        start_row: usize,
+    ) -> Result<(), Error> {
+        constrain_instances(layouter, &self.sum, instance, start_row)?;
+        constrain_instances(layouter, &self.carry, instance, start_row + CHUNKS)
+    }
+}
+
+impl<F: FieldExt, const CHUNKS: usize> From<CsaOutput<F, CHUNKS>>
+    for (Word<F, CHUNKS>, Word<F, CHUNKS>)
+{
+    fn from(output: CsaOutput<F, CHUNKS>) -> Self {
+        (output.sum, output.carry)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CarrySaveConfig<F: FieldExt, const BITS: usize, const CHUNKS: usize> {
+    bitwise: BitwiseChip<F, BITS>,
+    columns: [Column<Advice>; 4],
+    q_shift: Selector,
+    q_shift_first: Selector,
+    q_shift_next: Selector,
+    q_add: Selector,
+    q_add_first: Selector,
+    q_add_next: Selector,
+}
+
+/// Multi-operand addition of chunked words. `csa` reduces three words to a
+/// sum and a carry word with the lookups of a `BitwiseChip`, per chunk
+/// `sum = a ^ b ^ c` and `carry = (a & b) | (c & (a ^ b))`, so
+/// `a + b + c == sum + 2 * carry`. `add_many` reduces any number of words
+/// with it down to two and adds those with a ripple carry.
+///
+/// Shifting the carry word left by one moves the top bit of every chunk
+/// into the next chunk. Each carry chunk is split into its top bit `hi` and
+/// the rest `lo`, one chunk per row:
+///
+/// | carry     | hi    | lo    | shifted              | q_shift_first | q_shift_next |
+/// |-----------|-------|-------|----------------------|---------------|--------------|
+/// | carry_0   | hi_0  | lo_0  | 2 * lo_0             | 1             | 0            |
+/// | carry_1   | hi_1  | lo_1  | 2 * lo_1 + hi_0      | 0             | 1            |
+/// | ...       | ...   | ...   | ...                  | 0             | 1            |
+///
+/// with `carry == hi * 2^(BITS - 1) + lo` and `hi` boolean on every row.
+/// The shifted chunk is looked up in the table, which is what pins the
+/// split: with a wrong `hi` the `lo` of the row is negative or too large
+/// and the shifted chunk doesn't fit in BITS bits.
+///
+/// The ripple add rows are `a + b + carry_prev == sum + 2^BITS * carry` with
+/// a boolean carry, the same first and next selectors and `(a, b)` and
+/// `sum` looked up in the table.
+///
+/// All arithmetic is modulo `2^(BITS * CHUNKS)`: the top bit of the last
+/// carry chunk and the carry out of the last ripple chunk are dropped,
+/// a sum that overflows wraps around like `u32::wrapping_add` does.
+#[derive(Clone, Debug)]
+pub struct CarrySaveChip<F: FieldExt, const BITS: usize, const CHUNKS: usize> {
+    config: CarrySaveConfig<F, BITS, CHUNKS>,
+}
+
+impl<F: FieldExt, const BITS: usize, const CHUNKS: usize> Chip<F>
+    for CarrySaveChip<F, BITS, CHUNKS>
+{
+    type Config = CarrySaveConfig<F, BITS, CHUNKS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize, const CHUNKS: usize> CarrySaveChip<F, BITS, CHUNKS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// The shift and ripple add rows share `columns`, which get equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bitwise: BitwiseChip<F, BITS>,
+        columns: [Column<Advice>; 4],
+    ) -> <Self as Chip<F>>::Config {
+        for column in columns {
+            meta.enable_equality(column);
+        }
+        let [q_shift, q_add] = [(); 2].map(|_| meta.complex_selector());
+        let [q_shift_first, q_shift_next, q_add_first, q_add_next] =
+            [(); 4].map(|_| meta.selector());
+
+        let one = Expression::Constant(F::one());
+        let two = Expression::Constant(F::from(2));
+        let top_bit = Expression::Constant(F::from_u128(1 << (BITS - 1)));
+        let radix = Expression::Constant(F::from_u128(1 << BITS));
+
+        meta.create_gate("carry shift", |meta| {
+            let s = meta.query_selector(q_shift);
+            let [carry, hi, lo] = [0, 1, 2].map(|i| meta.query_advice(columns[i], Rotation::cur()));
+            vec![
+                s.clone() * hi.clone() * (one.clone() - hi.clone()),
+                s * (carry - hi * top_bit - lo),
+            ]
+        });
+        meta.create_gate("carry shift first", |meta| {
+            let s = meta.query_selector(q_shift_first);
+            let lo = meta.query_advice(columns[2], Rotation::cur());
+            let shifted = meta.query_advice(columns[3], Rotation::cur());
+            vec![s * (shifted - two.clone() * lo)]
+        });
+        meta.create_gate("carry shift next", |meta| {
+            let s = meta.query_selector(q_shift_next);
+            let hi_prev = meta.query_advice(columns[1], Rotation::prev());
+            let lo = meta.query_advice(columns[2], Rotation::cur());
+            let shifted = meta.query_advice(columns[3], Rotation::cur());
+            vec![s * (shifted - two.clone() * lo - hi_prev)]
+        });
+        meta.lookup("carry shift range", |meta| {
+            let s = meta.query_selector(q_shift);
+            let shifted = meta.query_advice(columns[3], Rotation::cur());
+            vec![(s * shifted, bitwise.table.left)]
+        });
+
+        meta.create_gate("ripple add", |meta| {
+            let s = meta.query_selector(q_add);
+            let carry = meta.query_advice(columns[3], Rotation::cur());
+            vec![s * carry.clone() * (one.clone() - carry)]
+        });
+        meta.create_gate("ripple add first", |meta| {
+            let s = meta.query_selector(q_add_first);
+            let [a, b, sum, carry] =
+                columns.map(|column| meta.query_advice(column, Rotation::cur()));
+            vec![s * (a + b - sum - carry * radix.clone())]
+        });
+        meta.create_gate("ripple add next", |meta| {
+            let s = meta.query_selector(q_add_next);
+            let carry_prev = meta.query_advice(columns[3], Rotation::prev());
+            let [a, b, sum, carry] =
+                columns.map(|column| meta.query_advice(column, Rotation::cur()));
+            vec![s * (a + b + carry_prev - sum - carry * radix.clone())]
+        });
+        meta.lookup("ripple add operands", |meta| {
+            let s = meta.query_selector(q_add);
+            let a = meta.query_advice(columns[0], Rotation::cur());
+            let b = meta.query_advice(columns[1], Rotation::cur());
+            vec![
+                (s.clone() * a, bitwise.table.left),
+                (s * b, bitwise.table.right),
+            ]
+        });
+        meta.lookup("ripple add sum", |meta| {
+            let s = meta.query_selector(q_add);
+            let sum = meta.query_advice(columns[2], Rotation::cur());
+            vec![(s * sum, bitwise.table.left)]
+        });
+
+        CarrySaveConfig {
+            bitwise,
+            columns,
+            q_shift,
+            q_shift_first,
+            q_shift_next,
+            q_add,
+            q_add_first,
+            q_add_next,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.bitwise.table.load(layouter)
+    }
+
+    /// Returns `sum` and `carry` with `a + b + c == sum + carry` modulo
+    /// `2^(BITS * CHUNKS)`, the carry already shifted into place.
+    pub fn csa(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Word<F, CHUNKS>,
+        b: &Word<F, CHUNKS>,
+        c: &Word<F, CHUNKS>,
+    ) -> Result<CsaOutput<F, CHUNKS>, Error> {
+        let bitwise = &self.config.bitwise;
+        let mut sum = Vec::with_capacity(CHUNKS);
+        let mut majority = Vec::with_capacity(CHUNKS);
+        for ((a, b), c) in a.iter().zip(b).zip(c) {
+            let (a_xor_b, a_and_b) = bitwise
+                .xor_and(layouter.namespace(|| "a ^ b"), a.clone(), b.clone())?
+                .into();
+            let (a_xor_b_xor_c, c_and_a_xor_b) = bitwise
+                .xor_and(layouter.namespace(|| "a ^ b ^ c"), a_xor_b, c.clone())?
+                .into();
+            let carry =
+                bitwise.all_ops(layouter.namespace(|| "majority"), a_and_b, c_and_a_xor_b)?;
+            sum.push(a_xor_b_xor_c);
+            majority.push(carry.or().clone());
+        }
+
+        let top_bit = |carry: &F| F::from((carry.get_lower_128() >> (BITS - 1)) as u64 & 1);
+        let carry = self.assign_shift(layouter.namespace(|| "carry << 1"), &majority, top_bit)?;
+        Ok(CsaOutput {
+            sum: to_word(sum),
+            carry,
+        })
+    }
+
+    // `top_bit` computes the `hi` witness of a carry chunk, only tests
+    // forge it
+    fn assign_shift(
+        &self,
+        mut layouter: impl Layouter<F>,
+        carries: &[AssignedCell<F, F>],
+        top_bit: impl Fn(&F) -> F,
+    ) -> Result<Word<F, CHUNKS>, Error> {
+        let config = self.config();
+        let [carry_column, hi_column, lo_column, shifted_column] = config.columns;
+        let top_bit_value = F::from_u128(1 << (BITS - 1));
+
+        let shifted = layouter.assign_region(
+            || "carry shift",
+            |mut region| {
+                let mut hi_prev = Value::known(F::zero());
+                let mut shifted = Vec::with_capacity(CHUNKS);
+                for (row, carry) in carries.iter().enumerate() {
+                    config.q_shift.enable(&mut region, row)?;
+                    if row == 0 {
+                        config.q_shift_first.enable(&mut region, row)?;
+                    } else {
+                        config.q_shift_next.enable(&mut region, row)?;
+                    }
+
+                    let carry = carry.copy_advice(|| "carry", &mut region, carry_column, row)?;
+                    let hi = carry.value().map(&top_bit);
+                    let lo = carry
+                        .value()
+                        .zip(hi)
+                        .map(|(carry, hi)| *carry - hi * top_bit_value);
+                    region.assign_advice(|| "hi", hi_column, row, || hi)?;
+                    region.assign_advice(|| "lo", lo_column, row, || lo)?;
+
+                    let value = lo.zip(hi_prev).map(|(lo, hi_prev)| lo.double() + hi_prev);
+                    shifted.push(region.assign_advice(
+                        || "shifted",
+                        shifted_column,
+                        row,
+                        || value,
+                    )?);
+                    hi_prev = hi;
+                }
+                Ok(shifted)
+            },
+        )?;
+        Ok(to_word(shifted))
+    }
+
+    /// `a + b` modulo `2^(BITS * CHUNKS)` with a ripple carry.
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Word<F, CHUNKS>,
+        b: &Word<F, CHUNKS>,
+    ) -> Result<Word<F, CHUNKS>, Error> {
+        let config = self.config();
+        let [a_column, b_column, sum_column, carry_column] = config.columns;
+        let radix = 1u128 << BITS;
+
+        let sum = layouter.assign_region(
+            || "ripple add",
+            |mut region| {
+                let mut carry_prev = Value::known(0);
+                let mut sum = Vec::with_capacity(CHUNKS);
+                for (row, (a, b)) in a.iter().zip(b).enumerate() {
+                    config.q_add.enable(&mut region, row)?;
+                    if row == 0 {
+                        config.q_add_first.enable(&mut region, row)?;
+                    } else {
+                        config.q_add_next.enable(&mut region, row)?;
+                    }
+
+                    let a = a.copy_advice(|| "a", &mut region, a_column, row)?;
+                    let b = b.copy_advice(|| "b", &mut region, b_column, row)?;
+                    let total =
+                        a.value()
+                            .zip(b.value())
+                            .zip(carry_prev)
+                            .map(|((a, b), carry_prev)| {
+                                a.get_lower_128() + b.get_lower_128() + carry_prev
+                            });
+                    let value = total.map(|total| F::from_u128(total % radix));
+                    sum.push(region.assign_advice(|| "sum", sum_column, row, || value)?);
+
+                    carry_prev = total.map(|total| total / radix);
+                    let carry = carry_prev.map(F::from_u128);
+                    region.assign_advice(|| "carry", carry_column, row, || carry)?;
+                }
+                Ok(sum)
+            },
+        )?;
+        Ok(to_word(sum))
+    }
+
+    /// The sum of `words` modulo `2^(BITS * CHUNKS)`, reduced three words at
+    /// a time with `csa` and finished with `add`. Fails with
+    /// `Error::Synthesis` for no words.
+    pub fn add_many(
+        &self,
+        mut layouter: impl Layouter<F>,
+        words: &[Word<F, CHUNKS>],
+    ) -> Result<Word<F, CHUNKS>, Error> {
+        let mut words = words.to_vec();
+        while words.len() > 2 {
+            let rest = words.split_off(3);
+            let (sum, carry) = self
+                .csa(
+                    layouter.namespace(|| "csa"),
+                    &words[0],
+                    &words[1],
+                    &words[2],
+                )?
+                .into();
+            words = rest;
+            words.push(sum);
+            words.push(carry);
+        }
+        match &words[..] {
+            [] => Err(Error::Synthesis),
+            [word] => Ok(word.clone()),
+            [a, b] => self.add(layouter.namespace(|| "final add"), a, b),
+            _ => unreachable!("reduced to at most two words"),
+        }
+    }
+}
+
+fn to_word<F: FieldExt, const CHUNKS: usize>(chunks: Vec<AssignedCell<F, F>>) -> Word<F, CHUNKS> {
+    chunks.try_into().expect("one cell per chunk")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use halo2_proofs::{circuit::SimpleFloorPlanner, halo2curves::pasta::Fp, plonk::Circuit};
+
+    use super::*;
+    use crate::{
+        dev::run_auto,
+        testing::{HarnessChip, TestHarness, TestRng},
+    };
+
+    // 16 bit words as 4 chunks of 4 bits
+    type Config<F> = CarrySaveConfig<F, 4, 4>;
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Config<F> {
+        let bitwise = BitwiseChip::construct(meta);
+        let columns = [(); 4].map(|_| meta.advice_column());
+        CarrySaveChip::configure(meta, bitwise, columns)
+    }
+
+    fn chunks(word: u16) -> Vec<Fp> {
+        (0..4)
+            .map(|i| Fp::from((word >> (4 * i)) as u64 & 0xf))
+            .collect()
+    }
+
+    fn to_words<F: FieldExt>(witnesses: &[AssignedCell<F, F>]) -> Vec<Word<F, 4>> {
+        witnesses
+            .chunks(4)
+            .map(|chunks| to_word(chunks.to_vec()))
+            .collect()
+    }
+
+    // three words to the chunks of their sum and carry
+    struct CsaTest;
+
+    impl<F: FieldExt> HarnessChip<F> for CsaTest {
+        type Config = Config<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = CarrySaveChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "bitwise table"))?;
+            let words = to_words(&witnesses);
+            let output = chip.csa(
+                layouter.namespace(|| "csa"),
+                &words[0],
+                &words[1],
+                &words[2],
+            )?;
+            Ok(output.sum().iter().chain(output.carry()).cloned().collect())
+        }
+    }
+
+    trait Operands {
+        const N: usize;
+    }
+
+    struct Three;
+
+    impl Operands for Three {
+        const N: usize = 3;
+    }
+
+    struct Five;
+
+    impl Operands for Five {
+        const N: usize = 5;
+    }
+
+    // N words to the chunks of their sum
+    struct AddManyTest<N>(PhantomData<N>);
+
+    fn add_many<N>() -> AddManyTest<N> {
+        AddManyTest(PhantomData)
+    }
+
+    impl<F: FieldExt, N: Operands> HarnessChip<F> for AddManyTest<N> {
+        type Config = Config<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = CarrySaveChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "bitwise table"))?;
+            let words = to_words(&witnesses);
+            assert_eq!(words.len(), N::N);
+            let sum = chip.add_many(layouter.namespace(|| "add many"), &words)?;
+            Ok(sum.to_vec())
+        }
+    }
+
+    // the carry shift of `csa` on the witnesses, with the top bit of every
+    // carry chunk flipped in the split if FORGE
+    struct ShiftTest<const FORGE: bool>;
+
+    impl<F: FieldExt, const FORGE: bool> HarnessChip<F> for ShiftTest<FORGE> {
+        type Config = Config<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = CarrySaveChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "bitwise table"))?;
+            let top_bit = |carry: &F| F::from(((carry.get_lower_128() >> 3) & 1 == 1) != FORGE);
+            let carry =
+                chip.assign_shift(layouter.namespace(|| "carry << 1"), &witnesses, top_bit)?;
+            Ok(carry.to_vec())
+        }
+    }
+
+    fn add_many_inputs(words: &[u16]) -> (Vec<Fp>, Vec<Fp>) {
+        let sum = words.iter().fold(0u16, |sum, word| sum.wrapping_add(*word));
+        (
+            words.iter().flat_map(|word| chunks(*word)).collect(),
+            chunks(sum),
+        )
+    }
+
+    #[test]
+    fn test_csa() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0xc5a);
+        for _ in 0..4 {
+            let [a, b, c] = [(); 3].map(|_| rng.below(1 << 16) as u16);
+            let sum = a ^ b ^ c;
+            let carry = ((a & b) | (c & (a ^ b))) << 1;
+            assert_eq!(sum.wrapping_add(carry), a.wrapping_add(b).wrapping_add(c));
+
+            let witnesses: Vec<_> = [a, b, c].into_iter().flat_map(chunks).collect();
+            let expected: Vec<_> = chunks(sum).into_iter().chain(chunks(carry)).collect();
+            harness.expect_pass(CsaTest, witnesses.clone(), expected.clone());
+
+            // the carry not shifted
+            let unshifted = (a & b) | (c & (a ^ b));
+            let wrong: Vec<_> = chunks(sum).into_iter().chain(chunks(unshifted)).collect();
+            if wrong != expected {
+                harness.expect_fail(CsaTest, witnesses, wrong);
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_many() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0xadd);
+        for _ in 0..4 {
+            let words: Vec<_> = (0..5).map(|_| rng.below(1 << 16) as u16).collect();
+
+            let (witnesses, sum) = add_many_inputs(&words[..3]);
+            harness.expect_pass(add_many::<Three>(), witnesses.clone(), sum.clone());
+            let mut wrong = sum;
+            wrong[0] = Fp::from((wrong[0].get_lower_128() as u64 + 1) % 16);
+            harness.expect_fail(add_many::<Three>(), witnesses, wrong);
+
+            let (witnesses, sum) = add_many_inputs(&words);
+            harness.expect_pass(add_many::<Five>(), witnesses, sum);
+        }
+    }
+
+    #[test]
+    fn test_overflow_wraps() {
+        let harness = TestHarness::auto();
+        // 5 * 0xffff = 0x4_fffb
+        let (witnesses, sum) = add_many_inputs(&[u16::MAX; 5]);
+        assert_eq!(sum, chunks(0xfffb));
+        harness.expect_pass(add_many::<Five>(), witnesses, sum);
+
+        // the carries out of the top chunk are dropped, not kept as a 17th bit
+        let (witnesses, sum) = add_many_inputs(&[0x8000, 0x8000, 0x8000]);
+        assert_eq!(sum, chunks(0x8000));
+        harness.expect_pass(add_many::<Three>(), witnesses, sum);
+    }
+
+    // the shifted chunks for the split of every carry chunk into `hi` and
+    // `carry - 8 * hi`
+    fn shifted(carries: &[u64], hi: impl Fn(u64) -> u64) -> Vec<Fp> {
+        let mut hi_prev = Fp::zero();
+        carries
+            .iter()
+            .map(|carry| {
+                let hi = Fp::from(hi(*carry));
+                let lo = Fp::from(*carry) - hi * Fp::from(8);
+                let shifted = lo.double() + hi_prev;
+                hi_prev = hi;
+                shifted
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_forged_carry() {
+        let harness = TestHarness::auto();
+        // carry chunks with and without their top bit set
+        for carries in [[0x9, 0x1, 0xf, 0x0], [0x7, 0x8, 0x0, 0xf]] {
+            let witnesses: Vec<_> = carries.iter().map(|carry| Fp::from(*carry)).collect();
+            let honest = shifted(&carries, |carry| carry >> 3);
+            harness.expect_pass(ShiftTest::<false>, witnesses.clone(), honest);
+
+            // a flipped split satisfies the gates but leaves the shifted
+            // chunks out of range, so only the lookup catches it
+            let forged = shifted(&carries, |carry| 1 - (carry >> 3));
+            harness.expect_fail(ShiftTest::<true>, witnesses, forged);
+        }
+    }
+
+    // a csa of three words with its output exposed from `start_row` on
+    struct ExposeCircuit<F: FieldExt> {
+        chunks: Vec<Value<F>>,
+        start_row: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for ExposeCircuit<F> {
+        type Config = (Config<F>, Column<Advice>, Column<Instance>);
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                chunks: vec![Value::unknown(); self.chunks.len()],
+                start_row: self.start_row,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+            (configure(meta), advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, advice, instance): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = CarrySaveChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "bitwise table"))?;
+            let cells = layouter.assign_region(
+                || "load words",
+                |mut region| {
+                    self.chunks
+                        .iter()
+                        .enumerate()
+                        .map(|(row, chunk)| {
+                            region.assign_advice(|| "chunk", advice, row, || *chunk)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+            let words = to_words(&cells);
+            let output = chip.csa(
+                layouter.namespace(|| "csa"),
+                &words[0],
+                &words[1],
+                &words[2],
+            )?;
+            output.expose_public(&mut layouter, instance, self.start_row)
+        }
+    }
+
+    #[test]
+    fn test_expose_public() {
+        let [a, b, c] = [0x1234u16, 0xff00, 0x0f0f];
+        let circuit = ExposeCircuit {
+            chunks: [a, b, c]
+                .into_iter()
+                .flat_map(chunks)
+                .map(Value::known)
+                .collect(),
+            start_row: 3,
+        };
+        let sum = a ^ b ^ c;
+        let carry = ((a & b) | (c & (a ^ b))) << 1;
+        let outputs: Vec<_> = chunks(sum).into_iter().chain(chunks(carry)).collect();
+
+        let padded = vec![Fp::zero(); 3]
+            .into_iter()
+            .chain(outputs.clone())
+            .collect();
+        let (_, prover) = run_auto(&circuit, vec![padded]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        let (_, prover) = run_auto(&circuit, vec![outputs]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
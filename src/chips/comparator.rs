@@ -0,0 +1,381 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use crate::{debug_assert_witness, testing::mutation_hook, utils::fits_in_bits};
+
+#[derive(Clone, Debug)]
+pub struct ComparatorConfig<F: FieldExt, const BITS: usize> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    diff: Column<Advice>,
+    out: Column<Advice>,
+    q_lt: Selector,
+    q_lte: Selector,
+    q_and: Selector,
+    range_check: RangeCheckConfig<F, BITS>,
+}
+
+/// All comparisons of BITS-bit values over one set of columns.
+///
+/// `lte` is the `IsLtEqChip` row, `diff = b - a + (1 - out) * 2^BITS` with
+/// `diff` range checked to BITS bits. `lt` is the same row under `q_lt` with
+/// `b - a - 1` in place of `b - a`, as `a < b <=> a + 1 <= b`. `gt` and `gte`
+/// swap the operands, and `eq` is `lte * gte` in a `q_and` row reusing the
+/// `a`, `b` and `out` columns.
+#[derive(Clone, Debug)]
+pub struct ComparatorChip<F: FieldExt, const BITS: usize> {
+    config: ComparatorConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for ComparatorChip<F, BITS> {
+    type Config = ComparatorConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> ComparatorChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        diff: Column<Advice>,
+        out: Column<Advice>,
+        range_check: RangeCheckConfig<F, BITS>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 64, "operands must be at most 64 bits");
+        let q_lt = meta.selector();
+        let q_lte = meta.selector();
+        let q_and = meta.selector();
+
+        meta.create_gate("comparator", |meta| {
+            let q_lt = meta.query_selector(q_lt);
+            let q_lte = meta.query_selector(q_lte);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            let base = Expression::Constant(F::from_u128(1 << BITS));
+            let wrap = (one.clone() - out.clone()) * base;
+            vec![
+                // out is 0 or 1
+                (q_lt.clone() + q_lte.clone()) * out.clone() * (one.clone() - out),
+                q_lte * (diff.clone() - (b.clone() - a.clone()) - wrap.clone()),
+                q_lt * (diff - (b - a - one) - wrap),
+            ]
+        });
+
+        meta.create_gate("comparator and", |meta| {
+            let q = meta.query_selector(q_and);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![q * (out - a * b)]
+        });
+
+        ComparatorConfig {
+            a,
+            b,
+            diff,
+            out,
+            q_lt,
+            q_lte,
+            q_and,
+            range_check,
+        }
+    }
+
+    fn compare(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        strict: bool,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let (diff, out) = layouter.assign_region(
+            || "compare",
+            |mut region| {
+                if strict {
+                    config.q_lt.enable(&mut region, 0)?;
+                } else {
+                    config.q_lte.enable(&mut region, 0)?;
+                }
+                a.copy_advice(|| "copy a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "copy b", &mut region, config.b, 0)?;
+                for operand in [&a, &b] {
+                    debug_assert_witness!(
+                        "ComparatorChip",
+                        "compare",
+                        operand.value(),
+                        |v: &F| fits_in_bits(v, BITS),
+                        format!("a {BITS} bit operand"),
+                    );
+                }
+
+                let values = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| (a.get_lower_128(), b.get_lower_128()));
+                let out = values.map(|(a, b)| if strict { a < b } else { a <= b });
+                // wraps for operands wider than BITS, the range check on
+                // diff then fails
+                let diff = values.zip(out).map(|((a, b), out)| {
+                    let wrap = if out { 0 } else { 1 << BITS };
+                    F::from_u128(
+                        b.wrapping_add(wrap)
+                            .wrapping_sub(a)
+                            .wrapping_sub(u128::from(strict)),
+                    )
+                });
+
+                let diff = region.assign_advice(
+                    || "diff",
+                    config.diff,
+                    0,
+                    || mutation_hook("ComparatorChip diff", diff),
+                )?;
+                let out = region.assign_advice(
+                    || "out",
+                    config.out,
+                    0,
+                    || mutation_hook("ComparatorChip out", out.map(F::from)),
+                )?;
+                Ok((diff, out))
+            },
+        )?;
+
+        RangeCheckChip::construct(config.range_check.clone())
+            .range_check(layouter.namespace(|| "range check diff"), diff)?;
+
+        Ok(out)
+    }
+
+    pub fn lt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, a, b, true)
+    }
+
+    pub fn lte(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, a, b, false)
+    }
+
+    pub fn gt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, b, a, true)
+    }
+
+    pub fn gte(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, b, a, false)
+    }
+
+    pub fn eq(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let lte = self.lte(layouter.namespace(|| "lte"), a.clone(), b.clone())?;
+        let gte = self.gte(layouter.namespace(|| "gte"), a, b)?;
+        self.and(layouter.namespace(|| "eq"), lte, gte)
+    }
+
+    /// `a * b`, the AND of two booleans such as the results of comparisons.
+    /// The operands aren't constrained to be boolean here.
+    pub fn and(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "and",
+            |mut region| {
+                config.q_and.enable(&mut region, 0)?;
+                a.copy_advice(|| "copy a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "copy b", &mut region, config.b, 0)?;
+                let out = a.value().copied() * b.value();
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::linear_combination::{LinearCombinationChip, LinearCombinationConfig},
+        dev::without_witness_checks,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    // outputs lt, lte, gt, gte, eq and lt + gt + eq
+    struct ComparatorTest;
+
+    impl<F: FieldExt> HarnessChip<F> for ComparatorTest {
+        type Config = (ComparatorConfig<F, 4>, LinearCombinationConfig<F, 3>);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let diff = meta.advice_column();
+            let out = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let sum = meta.advice_column();
+
+            for column in [a, b, diff, out, acc, sum] {
+                meta.enable_equality(column);
+            }
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            (
+                ComparatorChip::configure(meta, a, b, diff, out, range_check),
+                LinearCombinationChip::configure(meta, [a, b, diff], sum, [F::one(); 3]),
+            )
+        }
+
+        fn synthesize(
+            (comparator_config, sum_config): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = ComparatorChip::construct(comparator_config);
+            let (a, b) = (witnesses[0].clone(), witnesses[1].clone());
+
+            let lt = chip.lt(layouter.namespace(|| "lt"), a.clone(), b.clone())?;
+            let lte = chip.lte(layouter.namespace(|| "lte"), a.clone(), b.clone())?;
+            let gt = chip.gt(layouter.namespace(|| "gt"), a.clone(), b.clone())?;
+            let gte = chip.gte(layouter.namespace(|| "gte"), a.clone(), b.clone())?;
+            let eq = chip.eq(layouter.namespace(|| "eq"), a, b)?;
+
+            let sum = LinearCombinationChip::construct(sum_config).combine(
+                layouter.namespace(|| "lt + gt + eq"),
+                &[lt.clone(), gt.clone(), eq.clone()],
+            )?;
+
+            Ok(vec![lt, lte, gt, gte, eq, sum])
+        }
+    }
+
+    fn expected(outputs: [u64; 5]) -> Vec<Fp> {
+        // exactly one of lt, gt and eq fires
+        outputs
+            .iter()
+            .chain([1].iter())
+            .map(|v| Fp::from(*v))
+            .collect()
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let harness = TestHarness::auto();
+        // lt, lte, gt, gte, eq
+        for (a, b, outputs) in [
+            (3, 5, [1, 1, 0, 0, 0]),
+            (5, 5, [0, 1, 0, 1, 1]),
+            (7, 3, [0, 0, 1, 1, 0]),
+        ] {
+            harness.expect_pass(
+                ComparatorTest,
+                vec![Fp::from(a), Fp::from(b)],
+                expected(outputs),
+            );
+        }
+    }
+
+    #[test]
+    fn test_comparisons_wrong_result_fail() {
+        let harness = TestHarness::auto();
+        // lt and lte swapped for 5, 5
+        harness.expect_fail(
+            ComparatorTest,
+            vec![Fp::from(5), Fp::from(5)],
+            expected([1, 0, 0, 1, 1]),
+        );
+        // 3 and 5 compared as equal
+        harness.expect_fail(
+            ComparatorTest,
+            vec![Fp::from(3), Fp::from(5)],
+            expected([0, 1, 0, 1, 1]),
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "ComparatorChip")]
+    fn test_wide_operand_panics() {
+        TestHarness::auto().run(
+            ComparatorTest,
+            vec![Fp::from(100), Fp::from(3)],
+            expected([0, 0, 1, 1, 0]),
+        );
+    }
+
+    #[test]
+    fn test_wide_operand_fail() {
+        // b + 2^BITS - a underflows for a = p - 1, every diff then fails the
+        // range check rather than the witness computation panicking
+        let prover = without_witness_checks(|| {
+            TestHarness::auto().run(
+                ComparatorTest,
+                vec![-Fp::one(), Fp::from(3)],
+                expected([0, 0, 1, 1, 0]),
+            )
+        });
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_mutations() {
+        let harness = TestHarness::auto();
+        for (a, b, outputs) in [(3, 5, [1, 1, 0, 0, 0]), (5, 5, [0, 1, 0, 1, 1])] {
+            harness
+                .mutation_test(
+                    ComparatorTest,
+                    vec![Fp::from(a), Fp::from(b)],
+                    expected(outputs),
+                )
+                .assert_all_caught();
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use crate::utils::constrain_instances;
+
+/// The limbs of `ConcatChip::split`, `value == high * 2^LOW_BITS + low`.
+#[derive(Clone, Debug)]
+pub struct SplitOutput<F: FieldExt> {
+    high: AssignedCell<F, F>,
+    low: AssignedCell<F, F>,
+}
+
+impl<F: FieldExt> SplitOutput<F> {
+    pub fn high(&self) -> &AssignedCell<F, F> {
+        &self.high
+    }
+
+    pub fn low(&self) -> &AssignedCell<F, F> {
+        &self.low
+    }
+
+    /// Constrains `high` and `low` to the rows `start_row` and
+    /// `start_row + 1` of `instance`.
+    pub fn expose_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        instance: Column<Instance>,
+        start_row: usize,
+    ) -> Result<(), Error> {
+        constrain_instances(
+            layouter,
+            &[self.high.clone(), self.low.clone()],
+            instance,
+            start_row,
+        )
+    }
+}
+
+impl<F: FieldExt> From<SplitOutput<F>> for (AssignedCell<F, F>, AssignedCell<F, F>) {
+    fn from(output: SplitOutput<F>) -> Self {
+        (output.high, output.low)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ConcatConfig<F: FieldExt, const HIGH_BITS: usize, const LOW_BITS: usize> {
+    high: Column<Advice>,
+    low: Column<Advice>,
+    out: Column<Advice>,
+    selector: Selector,
+    range_check_high: RangeCheckConfig<F, HIGH_BITS>,
+    range_check_low: RangeCheckConfig<F, LOW_BITS>,
+}
+
+/// Concatenates a HIGH_BITS limb and a LOW_BITS limb into one value,
+/// `out == high * 2^LOW_BITS + low`, the inverse of splitting a value into
+/// limbs. Both limbs are range checked, without the check on `low` a low
+/// limb of LOW_BITS bits or more would carry into `high`.
+///
+/// `split` is the inverse with the same gate, the limbs witnessed and the
+/// value copied in.
+#[derive(Clone, Debug)]
+pub struct ConcatChip<F: FieldExt, const HIGH_BITS: usize, const LOW_BITS: usize> {
+    config: ConcatConfig<F, HIGH_BITS, LOW_BITS>,
+}
+
+impl<F: FieldExt, const HIGH_BITS: usize, const LOW_BITS: usize> Chip<F>
+    for ConcatChip<F, HIGH_BITS, LOW_BITS>
+{
+    type Config = ConcatConfig<F, HIGH_BITS, LOW_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const HIGH_BITS: usize, const LOW_BITS: usize>
+    ConcatChip<F, HIGH_BITS, LOW_BITS>
+{
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// The limbs are copied in and returned by `split`, `high`, `low` and
+    /// `out` need equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        high: Column<Advice>,
+        low: Column<Advice>,
+        out: Column<Advice>,
+        range_check_high: RangeCheckConfig<F, HIGH_BITS>,
+        range_check_low: RangeCheckConfig<F, LOW_BITS>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(
+            HIGH_BITS + LOW_BITS < 128,
+            "the concatenation must fit in 127 bits"
+        );
+        let selector = meta.selector();
+
+        meta.create_gate("concat", |meta| {
+            let s = meta.query_selector(selector);
+            let high = meta.query_advice(high, Rotation::cur());
+            let low = meta.query_advice(low, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let shift = Expression::Constant(F::from_u128(1 << LOW_BITS));
+            vec![s * (out - (high * shift + low))]
+        });
+
+        ConcatConfig {
+            high,
+            low,
+            out,
+            selector,
+            range_check_high,
+            range_check_low,
+        }
+    }
+
+    /// Returns `high * 2^LOW_BITS + low`. Fails to verify if `high` doesn't
+    /// fit in HIGH_BITS bits or `low` in LOW_BITS bits.
+    pub fn concat(
+        &self,
+        mut layouter: impl Layouter<F>,
+        high: AssignedCell<F, F>,
+        low: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        RangeCheckChip::construct(config.range_check_high.clone())
+            .range_check(layouter.namespace(|| "range check high"), high.clone())?;
+        RangeCheckChip::construct(config.range_check_low.clone())
+            .range_check(layouter.namespace(|| "range check low"), low.clone())?;
+
+        layouter.assign_region(
+            || "concat",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                high.copy_advice(|| "high", &mut region, config.high, 0)?;
+                low.copy_advice(|| "low", &mut region, config.low, 0)?;
+
+                let shift = F::from_u128(1 << LOW_BITS);
+                let out = high.value().copied() * shift + low.value();
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+
+    /// Returns the limbs `high` and `low` with `value == high * 2^LOW_BITS +
+    /// low`. Fails to verify if `value` doesn't fit in HIGH_BITS + LOW_BITS
+    /// bits.
+    ///
+    /// The split point is the LOW_BITS of the chip rather than an argument:
+    /// the gate fixes `2^LOW_BITS` at configure time and the range checks of
+    /// the limbs are sized by it, another split point needs another chip.
+    pub fn split(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<SplitOutput<F>, Error> {
+        let config = self.config();
+        let (high, low) = layouter.assign_region(
+            || "split",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                value.copy_advice(|| "value", &mut region, config.out, 0)?;
+
+                // values beyond 128 bits get the limbs of their lower bits,
+                // which can't add back up to them
+                let lower = value.value().map(|v| v.get_lower_128());
+                let high = lower.map(|v| F::from_u128(v >> LOW_BITS));
+                let low = lower.map(|v| F::from_u128(v & ((1 << LOW_BITS) - 1)));
+                Ok((
+                    region.assign_advice(|| "high", config.high, 0, || high)?,
+                    region.assign_advice(|| "low", config.low, 0, || low)?,
+                ))
+            },
+        )?;
+
+        RangeCheckChip::construct(config.range_check_high.clone())
+            .range_check(layouter.namespace(|| "range check high"), high.clone())?;
+        RangeCheckChip::construct(config.range_check_low.clone())
+            .range_check(layouter.namespace(|| "range check low"), low.clone())?;
+        Ok(SplitOutput { high, low })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> ConcatConfig<F, 4, 4> {
+        let [high, low, out, bit, acc] = [(); 5].map(|_| meta.advice_column());
+        for column in [high, low, out, acc] {
+            meta.enable_equality(column);
+        }
+        // both limbs are 4 bits, one range check serves for both
+        let range_check = RangeCheckChip::configure(meta, bit, acc);
+        ConcatChip::configure(meta, high, low, out, range_check.clone(), range_check)
+    }
+
+    struct ConcatTest;
+
+    impl<F: FieldExt> HarnessChip<F> for ConcatTest {
+        type Config = ConcatConfig<F, 4, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let out = ConcatChip::construct(config).concat(
+                layouter.namespace(|| "concat"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![out])
+        }
+    }
+
+    // the outputs are the limbs, or with ROUND_TRIP their concatenation
+    struct SplitTest<const ROUND_TRIP: bool>;
+
+    impl<F: FieldExt, const ROUND_TRIP: bool> HarnessChip<F> for SplitTest<ROUND_TRIP> {
+        type Config = ConcatConfig<F, 4, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = ConcatChip::construct(config);
+            let (high, low): (AssignedCell<F, F>, AssignedCell<F, F>) = chip
+                .split(layouter.namespace(|| "split"), witnesses[0].clone())?
+                .into();
+            if !ROUND_TRIP {
+                return Ok(vec![high, low]);
+            }
+            let out = chip.concat(layouter.namespace(|| "concat"), high, low)?;
+            layouter.assign_region(
+                || "round trip",
+                |mut region| region.constrain_equal(out.cell(), witnesses[0].cell()),
+            )?;
+            Ok(vec![out])
+        }
+    }
+
+    #[test]
+    fn test_concat() {
+        let harness = TestHarness::auto();
+        for (high, low) in [(0xa, 0x5), (0x0, 0xf), (0xf, 0x0), (0xf, 0xf)] {
+            let limbs = vec![Fp::from(high), Fp::from(low)];
+            harness.expect_pass(ConcatTest, limbs.clone(), vec![Fp::from(high << 4 | low)]);
+            harness.expect_fail(ConcatTest, limbs, vec![Fp::from((high << 4 | low) + 1)]);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_limb() {
+        // 0x10 as the low limb gives the right sum for (0x9, 0x10) == 0xa0,
+        // only the range check rejects it
+        let harness = TestHarness::auto();
+        harness.expect_fail(
+            ConcatTest,
+            vec![Fp::from(0x9), Fp::from(0x10)],
+            vec![Fp::from(0xa0)],
+        );
+        harness.expect_fail(
+            ConcatTest,
+            vec![Fp::from(0x10), Fp::from(0x0)],
+            vec![Fp::from(0x100)],
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let harness = TestHarness::auto();
+        for value in [0xa5u64, 0x00, 0x0f, 0xf0, 0xff] {
+            let limbs = vec![Fp::from(value >> 4), Fp::from(value & 0xf)];
+            harness.expect_pass(SplitTest::<false>, vec![Fp::from(value)], limbs);
+            let swapped = vec![Fp::from(value & 0xf), Fp::from(value >> 4)];
+            if value >> 4 != value & 0xf {
+                harness.expect_fail(SplitTest::<false>, vec![Fp::from(value)], swapped);
+            }
+        }
+        // 0x100 doesn't fit in 8 bits, its high limb would be 0x10
+        harness.expect_fail(
+            SplitTest::<false>,
+            vec![Fp::from(0x100)],
+            vec![Fp::from(0x10), Fp::zero()],
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let harness = TestHarness::auto();
+        for value in [0xa5u64, 0x00, 0x3c, 0xff] {
+            harness.expect_pass(
+                SplitTest::<true>,
+                vec![Fp::from(value)],
+                vec![Fp::from(value)],
+            );
+        }
+    }
+}
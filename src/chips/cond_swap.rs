@@ -0,0 +1,391 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::{debug_assert_witness, utils::constrain_instances};
+
+/// The outputs of a conditional swap, `(a, b)` in order or swapped to
+/// `(b, a)`, from `CondSwapChip::swap` or `SelectChip::conditional_swap`.
+#[derive(Clone, Debug)]
+pub struct SwapOutput<F: FieldExt> {
+    first: AssignedCell<F, F>,
+    second: AssignedCell<F, F>,
+}
+
+impl<F: FieldExt> SwapOutput<F> {
+    pub(super) fn new(first: AssignedCell<F, F>, second: AssignedCell<F, F>) -> Self {
+        Self { first, second }
+    }
+
+    pub fn first(&self) -> &AssignedCell<F, F> {
+        &self.first
+    }
+
+    pub fn second(&self) -> &AssignedCell<F, F> {
+        &self.second
+    }
+
+    /// Constrains `first` and `second` to the rows `start_row` and
+    /// `start_row + 1` of `instance`.
+    pub fn expose_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        instance: Column<Instance>,
+        start_row: usize,
+    ) -> Result<(), Error> {
+        constrain_instances(
+            layouter,
+            &[self.first.clone(), self.second.clone()],
+            instance,
+            start_row,
+        )
+    }
+}
+
+impl<F: FieldExt> From<SwapOutput<F>> for (AssignedCell<F, F>, AssignedCell<F, F>) {
+    fn from(output: SwapOutput<F>) -> Self {
+        (output.first, output.second)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CondSwapConfig<F: FieldExt> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    flag: Column<Advice>,
+    out0: Column<Advice>,
+    out1: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Returns `(b, a)` if `flag` is 1 and `(a, b)` if it is 0, the mux under
+/// Merkle path steps and compare and swap networks. `flag` is constrained to
+/// be boolean, and both outputs and the boolean check share one row:
+///
+/// `out0 = a + flag * (b - a)`, `out1 = b + flag * (a - b)`
+#[derive(Clone, Debug)]
+pub struct CondSwapChip<F: FieldExt> {
+    config: CondSwapConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for CondSwapChip<F> {
+    type Config = CondSwapConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> CondSwapChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        flag: Column<Advice>,
+        out0: Column<Advice>,
+        out1: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("cond swap", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let flag = meta.query_advice(flag, Rotation::cur());
+            let out0 = meta.query_advice(out0, Rotation::cur());
+            let out1 = meta.query_advice(out1, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![
+                s.clone() * flag.clone() * (one - flag.clone()),
+                s.clone() * (out0 - (a.clone() + flag.clone() * (b.clone() - a.clone()))),
+                s * (out1 - (b.clone() + flag * (a - b))),
+            ]
+        });
+
+        CondSwapConfig {
+            a,
+            b,
+            flag,
+            out0,
+            out1,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    fn assign_swap(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        flag: &AssignedCell<F, F>,
+    ) -> Result<SwapOutput<F>, Error> {
+        let config = self.config();
+        config.selector.enable(region, offset)?;
+        a.copy_advice(|| "copy a", region, config.a, offset)?;
+        b.copy_advice(|| "copy b", region, config.b, offset)?;
+        flag.copy_advice(|| "copy flag", region, config.flag, offset)?;
+        debug_assert_witness!(
+            "CondSwapChip",
+            "cond swap",
+            flag.value(),
+            |flag: &F| *flag == F::zero() || *flag == F::one(),
+            "boolean",
+        );
+
+        let swapped = |x: &F, y: &F, flag: &F| *x + *flag * (*y - *x);
+        let out0 = a
+            .value()
+            .zip(b.value())
+            .zip(flag.value())
+            .map(|((a, b), flag)| swapped(a, b, flag));
+        let out1 = a
+            .value()
+            .zip(b.value())
+            .zip(flag.value())
+            .map(|((a, b), flag)| swapped(b, a, flag));
+
+        let out0 = region.assign_advice(|| "out0", config.out0, offset, || out0)?;
+        let out1 = region.assign_advice(|| "out1", config.out1, offset, || out1)?;
+        Ok(SwapOutput::new(out0, out1))
+    }
+
+    pub fn swap(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        flag: AssignedCell<F, F>,
+    ) -> Result<SwapOutput<F>, Error> {
+        layouter.assign_region(
+            || "cond swap",
+            |mut region| self.assign_swap(&mut region, 0, &a, &b, &flag),
+        )
+    }
+
+    /// `swap` for each pair under the flag at the same index, one row each in
+    /// a single region.
+    pub fn swap_many(
+        &self,
+        mut layouter: impl Layouter<F>,
+        pairs: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+        flags: &[AssignedCell<F, F>],
+    ) -> Result<Vec<SwapOutput<F>>, Error> {
+        assert_eq!(pairs.len(), flags.len(), "one flag per pair");
+        layouter.assign_region(
+            || "cond swap many",
+            |mut region| {
+                pairs
+                    .iter()
+                    .zip(flags)
+                    .enumerate()
+                    .map(|(offset, ((a, b), flag))| {
+                        self.assign_swap(&mut region, offset, a, b, flag)
+                    })
+                    .collect()
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::hash::{DummyHashChip, DummyHashConfig, HashGadget},
+        dev::without_witness_checks,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> CondSwapConfig<F> {
+        let columns = [(); 5].map(|_| meta.advice_column());
+        for column in columns {
+            meta.enable_equality(column);
+        }
+        let [a, b, flag, out0, out1] = columns;
+        CondSwapChip::configure(meta, a, b, flag, out0, out1)
+    }
+
+    // witnesses are a, b and flag
+    struct CondSwapTest;
+
+    impl<F: FieldExt> HarnessChip<F> for CondSwapTest {
+        type Config = CondSwapConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (out0, out1) = CondSwapChip::construct(config)
+                .swap(
+                    layouter.namespace(|| "swap"),
+                    witnesses[0].clone(),
+                    witnesses[1].clone(),
+                    witnesses[2].clone(),
+                )?
+                .into();
+            Ok(vec![out0, out1])
+        }
+    }
+
+    // witnesses are (a, b, flag) triples
+    struct SwapManyTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SwapManyTest {
+        type Config = CondSwapConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (pairs, flags): (Vec<_>, Vec<_>) = witnesses
+                .chunks(3)
+                .map(|chunk| ((chunk[0].clone(), chunk[1].clone()), chunk[2].clone()))
+                .unzip();
+            let swapped = CondSwapChip::construct(config).swap_many(
+                layouter.namespace(|| "swap many"),
+                &pairs,
+                &flags,
+            )?;
+            Ok(swapped
+                .into_iter()
+                .flat_map(|swapped| [swapped.first().clone(), swapped.second().clone()])
+                .collect())
+        }
+    }
+
+    // one Merkle path step, the node goes left of its sibling when the path
+    // bit is 0, witnesses are node, sibling and bit
+    struct MerkleStepTest;
+
+    impl<F: FieldExt> HarnessChip<F> for MerkleStepTest {
+        type Config = (CondSwapConfig<F>, DummyHashConfig<F>);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let left = meta.advice_column();
+            let right = meta.advice_column();
+            let out = meta.advice_column();
+            for column in [left, right, out] {
+                meta.enable_equality(column);
+            }
+            (
+                configure(meta),
+                DummyHashChip::configure(meta, left, right, out),
+            )
+        }
+
+        fn synthesize(
+            (swap_config, hash_config): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (left, right) = CondSwapChip::construct(swap_config)
+                .swap(
+                    layouter.namespace(|| "order"),
+                    witnesses[0].clone(),
+                    witnesses[1].clone(),
+                    witnesses[2].clone(),
+                )?
+                .into();
+            let parent = DummyHashChip::construct(hash_config).hash_two(
+                layouter.namespace(|| "hash"),
+                left,
+                right,
+            )?;
+            Ok(vec![parent])
+        }
+    }
+
+    fn values(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_swap() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(CondSwapTest, values(&[3, 5, 0]), values(&[3, 5]));
+        harness.expect_pass(CondSwapTest, values(&[3, 5, 1]), values(&[5, 3]));
+        harness.expect_fail(CondSwapTest, values(&[3, 5, 0]), values(&[5, 3]));
+        harness.expect_fail(CondSwapTest, values(&[3, 5, 1]), values(&[3, 5]));
+    }
+
+    #[test]
+    fn test_swap_equal_inputs() {
+        let harness = TestHarness::auto();
+        for flag in [0, 1] {
+            harness.expect_pass(CondSwapTest, values(&[7, 7, flag]), values(&[7, 7]));
+        }
+    }
+
+    #[test]
+    fn test_non_boolean_flag_fail() {
+        // 2 would give a + 2 * (b - a) and b + 2 * (a - b)
+        without_witness_checks(|| {
+            TestHarness::auto().expect_fail(CondSwapTest, values(&[3, 5, 2]), values(&[7, 1]))
+        });
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "CondSwapChip")]
+    fn test_non_boolean_flag_panics() {
+        TestHarness::auto().run(CondSwapTest, values(&[3, 5, 2]), values(&[7, 1]));
+    }
+
+    #[test]
+    fn test_swap_many() {
+        let harness = TestHarness::auto();
+        let witnesses = values(&[1, 2, 0, 3, 4, 1, 5, 6, 1]);
+        harness.expect_pass(SwapManyTest, witnesses.clone(), values(&[1, 2, 4, 3, 6, 5]));
+        harness.expect_fail(SwapManyTest, witnesses, values(&[1, 2, 4, 3, 5, 6]));
+    }
+
+    #[test]
+    fn test_merkle_step() {
+        let (node, sibling) = (Fp::from(3), Fp::from(4));
+        let harness = TestHarness::auto();
+        harness.expect_pass(
+            MerkleStepTest,
+            vec![node, sibling, Fp::zero()],
+            vec![DummyHashChip::hash(node, sibling)],
+        );
+        harness.expect_pass(
+            MerkleStepTest,
+            vec![node, sibling, Fp::one()],
+            vec![DummyHashChip::hash(sibling, node)],
+        );
+        harness.expect_fail(
+            MerkleStepTest,
+            vec![node, sibling, Fp::one()],
+            vec![DummyHashChip::hash(node, sibling)],
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct ConditionalCopyConfig<F: FieldExt> {
+    condition: Column<Advice>,
+    src: Column<Advice>,
+    dst: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Copies `src` into `dst` only when `condition` is 1. When `condition` is 0
+/// the destination is left unconstrained. The condition is not checked to be
+/// boolean here, combine with `AssertBooleanChip` if it is not already known
+/// to be a bit.
+#[derive(Clone, Debug)]
+pub struct ConditionalCopyChip<F: FieldExt> {
+    config: ConditionalCopyConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for ConditionalCopyChip<F> {
+    type Config = ConditionalCopyConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> ConditionalCopyChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        condition: Column<Advice>,
+        src: Column<Advice>,
+        dst: Column<Advice>,
+        selector: Selector,
+    ) -> <Self as Chip<F>>::Config {
+        meta.create_gate("conditional copy", |meta| {
+            let s = meta.query_selector(selector);
+            let condition = meta.query_advice(condition, Rotation::cur());
+            let src = meta.query_advice(src, Rotation::cur());
+            let dst = meta.query_advice(dst, Rotation::cur());
+            vec![s * condition * (dst - src)] // dst == src if condition == 1
+        });
+
+        ConditionalCopyConfig {
+            condition,
+            src,
+            dst,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Assigns `dst` and returns its cell. If `condition` is 1 the assignment
+    /// must equal `src` for the circuit to verify.
+    pub fn conditional_copy(
+        &self,
+        mut layouter: impl Layouter<F>,
+        condition: AssignedCell<F, F>,
+        src: AssignedCell<F, F>,
+        dst: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "conditional copy",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                condition.copy_advice(|| "copy condition", &mut region, config.condition, 0)?;
+                src.copy_advice(|| "copy src", &mut region, config.src, 0)?;
+                region.assign_advice(|| "dst", config.dst, 0, || dst)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner, dev::MockProver, halo2curves::pasta::Fp, plonk::Circuit,
+    };
+
+    use super::*;
+    use crate::chips::assert_boolean::{AssertBooleanChip, AssertBooleanConfig};
+
+    const K: u32 = 4;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        condition: Value<F>,
+        src: Value<F>,
+        dst: Value<F>,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        conditional_copy_config: ConditionalCopyConfig<F>,
+        assert_boolean_config: AssertBooleanConfig<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let condition = meta.advice_column();
+            let src = meta.advice_column();
+            let dst = meta.advice_column();
+            let selector = meta.selector();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(condition);
+            meta.enable_equality(src);
+            meta.enable_equality(dst);
+
+            TestCircuitConfig {
+                advice,
+                conditional_copy_config: ConditionalCopyChip::configure(
+                    meta, condition, src, dst, selector,
+                ),
+                assert_boolean_config: AssertBooleanChip::configure(meta, condition),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = ConditionalCopyChip::construct(config.conditional_copy_config);
+            let assert_boolean_chip = AssertBooleanChip::construct(config.assert_boolean_config);
+
+            let (condition_cell, src_cell) = layouter.assign_region(
+                || "load values",
+                |mut region| {
+                    let condition = region.assign_advice(
+                        || "condition",
+                        config.advice,
+                        0,
+                        || self.condition,
+                    )?;
+                    let src = region.assign_advice(|| "src", config.advice, 1, || self.src)?;
+                    Ok((condition, src))
+                },
+            )?;
+
+            assert_boolean_chip.assert_boolean(
+                layouter.namespace(|| "assert condition boolean"),
+                condition_cell.clone(),
+            )?;
+            chip.conditional_copy(
+                layouter.namespace(|| "conditional copy"),
+                condition_cell,
+                src_cell,
+                self.dst,
+            )?;
+
+            Ok(())
+        }
+    }
+
+    fn run(condition: u64, src: u64, dst: u64) -> MockProver<Fp> {
+        MockProver::run(
+            K,
+            &TestCircuit::<Fp> {
+                condition: Value::known(Fp::from(condition)),
+                src: Value::known(Fp::from(src)),
+                dst: Value::known(Fp::from(dst)),
+            },
+            vec![],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_condition_1_copies() {
+        // Should success since dst equals src.
+        assert_eq!(run(1, 7, 7).verify(), Ok(()));
+
+        // Should fail since condition forces dst == src.
+        assert!(run(1, 7, 8).verify().is_err());
+    }
+
+    #[test]
+    fn test_condition_0_unconstrained() {
+        // Any dst is allowed when the condition is 0.
+        assert_eq!(run(0, 7, 7).verify(), Ok(()));
+        assert_eq!(run(0, 7, 8).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_non_boolean_condition_fail() {
+        // The copy gate alone is satisfied, but the boolean check is not.
+        assert!(run(2, 7, 7).verify().is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct ConstSetConfig<F: FieldExt, const N: usize> {
+    value: Column<Advice>,
+    elements: [Column<Fixed>; N],
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Constrains a value to be one of at most N constants with the gate
+/// `(value - c_0) * ... * (value - c_{N-1})`, no lookup and one row per
+/// check. The constants are fixed cells of the row, so every call can use a
+/// different set.
+///
+/// The gate has degree N + 1 with the selector, and the degree of the
+/// circuit is that of its highest degree gate, so a large N makes every
+/// other gate more expensive to prove too. N = 2 adds no degree over a
+/// multiplication gate, past a handful of elements a lookup like
+/// `SetMembershipChip::assert_member` is cheaper.
+#[derive(Clone, Debug)]
+pub struct ConstSetChip<F: FieldExt, const N: usize> {
+    config: ConstSetConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for ConstSetChip<F, N> {
+    type Config = ConstSetConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> ConstSetChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `value` needs equality for the copy of the checked cell.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(N > 0, "the set needs at least one element");
+        let elements = [(); N].map(|_| meta.fixed_column());
+        let selector = meta.selector();
+
+        meta.create_gate("in const set", |meta| {
+            let s = meta.query_selector(selector);
+            let v = meta.query_advice(value, Rotation::cur());
+            let product = elements
+                .iter()
+                .map(|element| v.clone() - meta.query_fixed(*element, Rotation::cur()))
+                .reduce(|product, diff| product * diff)
+                .expect("N > 0");
+            vec![s * product]
+        });
+
+        ConstSetConfig {
+            value,
+            elements,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Constrains `value` to be one of `set`. Fails with `Error::Synthesis`
+    /// for an empty set or one with more than N elements, a shorter set is
+    /// padded with its first element.
+    pub fn assert_in_const_set(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        set: &[F],
+    ) -> Result<(), Error> {
+        if set.is_empty() || set.len() > N {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "in const set",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+                for (i, column) in config.elements.iter().enumerate() {
+                    let element = set.get(i).unwrap_or(&set[0]);
+                    region.assign_fixed(|| "element", *column, 0, || Value::known(*element))?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// `(value - c0) * (value - c1) == 0`, see `assert_in_const_set`.
+    pub fn assert_in_const_set2(
+        &self,
+        layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        c0: F,
+        c1: F,
+    ) -> Result<(), Error> {
+        self.assert_in_const_set(layouter, value, &[c0, c1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        report::ConstraintReport,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    fn configure<F: FieldExt, const N: usize>(
+        meta: &mut ConstraintSystem<F>,
+    ) -> ConstSetConfig<F, N> {
+        let value = meta.advice_column();
+        meta.enable_equality(value);
+        ConstSetChip::configure(meta, value)
+    }
+
+    // the witness is in {3, 7}
+    struct PairTest;
+
+    impl<F: FieldExt> HarnessChip<F> for PairTest {
+        type Config = ConstSetConfig<F, 2>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            ConstSetChip::construct(config).assert_in_const_set2(
+                layouter.namespace(|| "in set"),
+                witnesses[0].clone(),
+                F::from(3),
+                F::from(7),
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    trait Set {
+        const SET: &'static [u64];
+    }
+
+    struct Small;
+
+    impl Set for Small {
+        const SET: &'static [u64] = &[2, 4, 8];
+    }
+
+    struct Full;
+
+    impl Set for Full {
+        const SET: &'static [u64] = &[1, 3, 5, 7];
+    }
+
+    struct TooLarge;
+
+    impl Set for TooLarge {
+        const SET: &'static [u64] = &[1, 2, 3, 4, 5];
+    }
+
+    struct Empty;
+
+    impl Set for Empty {
+        const SET: &'static [u64] = &[];
+    }
+
+    struct SetTest<S>(PhantomData<S>);
+
+    // tests take the harness chip by value
+    fn in_set<S>() -> SetTest<S> {
+        SetTest(PhantomData)
+    }
+
+    impl<F: FieldExt, S: Set> HarnessChip<F> for SetTest<S> {
+        type Config = ConstSetConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let set: Vec<F> = S::SET.iter().map(|element| F::from(*element)).collect();
+            ConstSetChip::construct(config).assert_in_const_set(
+                layouter.namespace(|| "in set"),
+                witnesses[0].clone(),
+                &set,
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_pair() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(PairTest, vec![Fp::from(3)], vec![]);
+        harness.expect_pass(PairTest, vec![Fp::from(7)], vec![]);
+        for value in [Fp::zero(), Fp::from(5), -Fp::from(3)] {
+            harness.expect_fail(PairTest, vec![value], vec![]);
+        }
+    }
+
+    #[test]
+    fn test_set() {
+        let harness = TestHarness::auto();
+        for element in Full::SET {
+            harness.expect_pass(in_set::<Full>(), vec![Fp::from(*element)], vec![]);
+        }
+        harness.expect_fail(in_set::<Full>(), vec![Fp::from(4)], vec![]);
+
+        // padded with 2
+        for element in Small::SET {
+            harness.expect_pass(in_set::<Small>(), vec![Fp::from(*element)], vec![]);
+        }
+        harness.expect_fail(in_set::<Small>(), vec![Fp::from(3)], vec![]);
+        harness.expect_fail(in_set::<Small>(), vec![Fp::zero()], vec![]);
+    }
+
+    #[test]
+    fn test_bad_set_is_error() {
+        let harness = TestHarness::auto();
+        for result in [
+            harness.try_run(in_set::<TooLarge>(), vec![Fp::one()], vec![]),
+            harness.try_run(in_set::<Empty>(), vec![Fp::one()], vec![]),
+        ] {
+            assert!(matches!(result, Err(Error::Synthesis)));
+        }
+    }
+
+    #[test]
+    fn test_degree() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        configure::<_, 2>(&mut meta);
+        assert_eq!(ConstraintReport::new(&meta).gates[0].polynomials[0].1, 3);
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        configure::<_, 4>(&mut meta);
+        assert_eq!(ConstraintReport::new(&meta).gates[0].polynomials[0].1, 5);
+    }
+}
@@ -0,0 +1,325 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use crate::utils::fits_in_bits;
+
+// `(u, v)` with `u * a + v * b == gcd(a, b)`, by the extended Euclidean
+// algorithm. `|u| <= max(a, b)` and `|v| <= max(a, b)`.
+fn bezout(a: i128, b: i128) -> (i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_u, mut u) = (1, 0);
+    let (mut old_v, mut v) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_u, u) = (u, old_u - q * u);
+        (old_v, v) = (v, old_v - q * v);
+    }
+    (old_u, old_v)
+}
+
+// `[sign, magnitude]` of `x`, the sign 1 for negative values
+fn sign_magnitude<F: FieldExt>(x: i128) -> [F; 2] {
+    [F::from(x < 0), F::from_u128(x.unsigned_abs())]
+}
+
+#[derive(Clone, Debug)]
+pub struct CoprimeConfig<F: FieldExt, const BITS: usize> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    // sign and magnitude columns, `u` and then `v`
+    bezout: [Column<Advice>; 4],
+    selector: Selector,
+    range_check: RangeCheckConfig<F, BITS>,
+}
+
+/// Proves that two BITS-bit values are coprime by witnessing Bézout
+/// coefficients with `u * a + v * b == 1` over the integers, which no
+/// `u, v` satisfy when `gcd(a, b) > 1`.
+///
+/// The coefficients can be negative, so they are witnessed as a sign bit
+/// and a magnitude, `u = (1 - 2 * u_sign) * u_mag`, in one row:
+///
+/// | a | b | u_sign | u_mag | v_sign | v_mag | selector |
+/// |---|---|--------|-------|--------|-------|----------|
+/// | a | b | s_u    | m_u   | s_v    | m_v   | 1        |
+///
+/// The gate only checks the equation modulo p, where it is easy to satisfy:
+/// `u = a^-1 mod p, v = 0` works for any nonzero `a`. What makes the field
+/// equation an integer one are the bounds. The signs are boolean, and `a`,
+/// `b` and both magnitudes are range checked to BITS bits, so
+///
+/// `|u * a + v * b - 1| <= 2 * (2^BITS - 1)^2 + 1 < 2^(2 * BITS + 1)`
+///
+/// which for BITS <= 64 is below `2^129`, far under the ~`2^254` modulus.
+/// An integer of that size is 0 modulo p only if it is 0, so the field
+/// equation holds exactly when the integer one does. The magnitudes need no
+/// more room than BITS bits, the extended Euclidean algorithm gives
+/// coefficients of at most `max(a, b)`.
+#[derive(Clone, Debug)]
+pub struct CoprimeChip<F: FieldExt, const BITS: usize> {
+    config: CoprimeConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for CoprimeChip<F, BITS> {
+    type Config = CoprimeConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> CoprimeChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `u` and `v` are the `[sign, magnitude]` columns of the coefficients.
+    /// `a`, `b` and the magnitude columns need equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        u: [Column<Advice>; 2],
+        v: [Column<Advice>; 2],
+        range_check: RangeCheckConfig<F, BITS>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(
+            BITS <= 64,
+            "2 * BITS + 1 bits must stay far below the modulus"
+        );
+        let selector = meta.selector();
+        let bezout = [u[0], u[1], v[0], v[1]];
+
+        meta.create_gate("bezout", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let [u_sign, u_mag, v_sign, v_mag] =
+                bezout.map(|column| meta.query_advice(column, Rotation::cur()));
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+
+            let u = (one.clone() - two.clone() * u_sign.clone()) * u_mag;
+            let v = (one.clone() - two * v_sign.clone()) * v_mag;
+            vec![
+                s.clone() * u_sign.clone() * (one.clone() - u_sign),
+                s.clone() * v_sign.clone() * (one.clone() - v_sign),
+                s * (u * a + v * b - one),
+            ]
+        });
+
+        CoprimeConfig {
+            a,
+            b,
+            bezout,
+            selector,
+            range_check,
+        }
+    }
+
+    /// Fails to verify unless `a` and `b` are BITS-bit and coprime.
+    pub fn assert_coprime(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let coefficients = a.value().zip(b.value()).map(|(a, b)| {
+            // out of range operands fail the range check whatever the witness
+            if !fits_in_bits(a, BITS) || !fits_in_bits(b, BITS) {
+                return [F::zero(); 4];
+            }
+            let (u, v) = bezout(a.get_lower_128() as i128, b.get_lower_128() as i128);
+            let [u_sign, u_mag] = sign_magnitude(u);
+            let [v_sign, v_mag] = sign_magnitude(v);
+            [u_sign, u_mag, v_sign, v_mag]
+        });
+        self.assign_bezout(layouter, a, b, coefficients)
+    }
+
+    // `coefficients` are `[u_sign, u_mag, v_sign, v_mag]`, only tests forge
+    // them
+    fn assign_bezout(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        coefficients: Value<[F; 4]>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+        let (u_mag, v_mag) = layouter.assign_region(
+            || "bezout",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "b", &mut region, config.b, 0)?;
+
+                let mut cells = vec![];
+                for (i, column) in config.bezout.iter().enumerate() {
+                    cells.push(region.assign_advice(
+                        || "bezout coefficient",
+                        *column,
+                        0,
+                        || coefficients.map(|coefficients| coefficients[i]),
+                    )?);
+                }
+                Ok((cells[1].clone(), cells[3].clone()))
+            },
+        )?;
+
+        let range_check = RangeCheckChip::construct(config.range_check.clone());
+        range_check.range_check(layouter.namespace(|| "range check a"), a)?;
+        range_check.range_check(layouter.namespace(|| "range check b"), b)?;
+        range_check.range_check(layouter.namespace(|| "range check u"), u_mag)?;
+        range_check.range_check(layouter.namespace(|| "range check v"), v_mag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{arithmetic::Field, halo2curves::pasta::Fp};
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    const BITS: usize = 8;
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> CoprimeConfig<F, BITS> {
+        let [a, b, u_sign, u_mag, v_sign, v_mag, bit, acc] = [(); 8].map(|_| meta.advice_column());
+        for column in [a, b, u_mag, v_mag, acc] {
+            meta.enable_equality(column);
+        }
+        let range_check = RangeCheckChip::configure(meta, bit, acc);
+        CoprimeChip::configure(meta, a, b, [u_sign, u_mag], [v_sign, v_mag], range_check)
+    }
+
+    struct CoprimeTest;
+
+    impl<F: FieldExt> HarnessChip<F> for CoprimeTest {
+        type Config = CoprimeConfig<F, BITS>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            CoprimeChip::construct(config).assert_coprime(
+                layouter.namespace(|| "coprime"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    // the witnesses are a, b and the forged coefficients
+    struct ForgedTest;
+
+    impl<F: FieldExt> HarnessChip<F> for ForgedTest {
+        type Config = CoprimeConfig<F, BITS>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let mut coefficients = Value::known([F::zero(); 4]);
+            for (i, cell) in witnesses[2..].iter().enumerate() {
+                coefficients = coefficients.zip(cell.value()).map(|(mut c, v)| {
+                    c[i] = *v;
+                    c
+                });
+            }
+            CoprimeChip::construct(config).assign_bezout(
+                layouter.namespace(|| "forged bezout"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+                coefficients,
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    fn pair(a: u64, b: u64) -> Vec<Fp> {
+        vec![Fp::from(a), Fp::from(b)]
+    }
+
+    #[test]
+    fn test_bezout() {
+        for (a, b) in [(3, 5), (240, 46), (1, 0), (0, 7), (255, 254)] {
+            let (u, v) = bezout(a, b);
+            let gcd = u * a + v * b;
+            assert_eq!((a % gcd, b % gcd), (0, 0));
+            assert!(u.abs() <= a.max(b) && v.abs() <= a.max(b));
+        }
+    }
+
+    #[test]
+    fn test_coprime() {
+        let harness = TestHarness::auto();
+        for (a, b) in [(3, 5), (8, 15), (255, 254), (17, 255)] {
+            harness.expect_pass(CoprimeTest, pair(a, b), vec![]);
+        }
+        // 1 is coprime to everything, 0 only to 1
+        for b in [1, 12, 255] {
+            harness.expect_pass(CoprimeTest, pair(1, b), vec![]);
+        }
+        harness.expect_pass(CoprimeTest, pair(0, 1), vec![]);
+    }
+
+    #[test]
+    fn test_not_coprime() {
+        let harness = TestHarness::auto();
+        for (a, b) in [(4, 6), (9, 12), (0, 6), (128, 2)] {
+            harness.expect_fail(CoprimeTest, pair(a, b), vec![]);
+        }
+        for a in [2, 7, 255] {
+            harness.expect_fail(CoprimeTest, pair(a, a), vec![]);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        // 256 and 255 are coprime, but 256 isn't 8 bits
+        let harness = TestHarness::auto();
+        harness.expect_fail(CoprimeTest, pair(256, 255), vec![]);
+    }
+
+    #[test]
+    fn test_wraparound_forgery() {
+        // gcd(2, 4) == 2, yet u = 2^-1 mod p and v = 0 satisfy the gate,
+        // only the range check on the magnitude of u rejects it
+        let harness = TestHarness::auto();
+        let inverse = Fp::from(2).invert().unwrap();
+        let forged = [Fp::zero(), inverse, Fp::zero(), Fp::zero()];
+        assert_eq!(inverse * Fp::from(2), Fp::one());
+        harness.expect_fail(ForgedTest, [pair(2, 4), forged.to_vec()].concat(), vec![]);
+
+        // the same in negative form, u = -(p - 2^-1)
+        let forged = [Fp::one(), -inverse, Fp::zero(), Fp::zero()];
+        harness.expect_fail(ForgedTest, [pair(2, 4), forged.to_vec()].concat(), vec![]);
+
+        // honest coefficients pass the same path, -1 * 2 + 1 * 3 == 1
+        let honest = [1, 1, 0, 1].map(Fp::from);
+        harness.expect_pass(ForgedTest, [pair(2, 3), honest.to_vec()].concat(), vec![]);
+    }
+}
@@ -0,0 +1,178 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct DotProductConfig<F: FieldExt> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_step: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Computes `sum(a_i * b_i)` of two vectors of cells with a running sum, one
+/// row per term:
+///
+/// | a   | b   | acc                 | q_first | q_step |
+/// |-----|-----|---------------------|---------|--------|
+/// | a_0 | b_0 | a_0 * b_0           | 1       | 0      |
+/// | a_1 | b_1 | acc_0 + a_1 * b_1   | 0       | 1      |
+/// | ... | ... | ...                 | 0       | 1      |
+#[derive(Clone, Debug)]
+pub struct DotProductChip<F: FieldExt> {
+    config: DotProductConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for DotProductChip<F> {
+    type Config = DotProductConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> DotProductChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// The vectors are copied in, `a` and `b` need equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        meta.create_gate("dot product first", |meta| {
+            let q = meta.query_selector(q_first);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - a * b)]
+        });
+
+        meta.create_gate("dot product step", |meta| {
+            let q = meta.query_selector(q_step);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            vec![q * (acc - acc_prev - a * b)]
+        });
+
+        DotProductConfig {
+            a,
+            b,
+            acc,
+            q_first,
+            q_step,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Fails with `Error::Synthesis` if the vectors are empty or of
+    /// different lengths.
+    pub fn dot(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[AssignedCell<F, F>],
+        b: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if a.is_empty() || a.len() != b.len() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "dot product",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut acc_cell = None;
+                for (offset, (a, b)) in a.iter().zip(b).enumerate() {
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+                    a.copy_advice(|| "a", &mut region, config.a, offset)?;
+                    b.copy_advice(|| "b", &mut region, config.b, offset)?;
+
+                    acc = acc + a.value().copied() * b.value().copied();
+                    acc_cell = Some(region.assign_advice(|| "acc", config.acc, offset, || acc)?);
+                }
+                Ok(acc_cell.unwrap())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    // the first LEN witnesses are a, the rest b
+    struct DotTest<const LEN: usize>;
+
+    impl<F: FieldExt, const LEN: usize> HarnessChip<F> for DotTest<LEN> {
+        type Config = DotProductConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let acc = meta.advice_column();
+            for column in [a, b, acc] {
+                meta.enable_equality(column);
+            }
+            DotProductChip::configure(meta, a, b, acc)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (a, b) = witnesses.split_at(LEN);
+            let dot = DotProductChip::construct(config).dot(layouter.namespace(|| "dot"), a, b)?;
+            Ok(vec![dot])
+        }
+    }
+
+    fn vectors(a: &[u64], b: &[u64]) -> Vec<Fp> {
+        a.iter().chain(b).map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_dot() {
+        let harness = TestHarness::auto();
+        let witnesses = vectors(&[1, 2, 3], &[4, 5, 6]);
+        harness.expect_pass(DotTest::<3>, witnesses.clone(), vec![Fp::from(32)]);
+        harness.expect_fail(DotTest::<3>, witnesses, vec![Fp::from(33)]);
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let harness = TestHarness::auto();
+        let result = harness.try_run(DotTest::<3>, vectors(&[1, 2, 3], &[4, 5]), vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+        let result = harness.try_run(DotTest::<0>, vec![], vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
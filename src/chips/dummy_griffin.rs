@@ -0,0 +1,483 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::expr::linear_combination;
+
+/// The exponent of the power maps, a permutation of any field with
+/// `gcd(5, p - 1) == 1` like the Pasta fields.
+pub const D: u64 = 5;
+
+pub const ROUNDS: usize = 10;
+
+/// The constants of a permutation with the structure of Griffin-π over a
+/// state of T words: the linear layer, the round constants and the
+/// `alpha`, `beta` of the nonlinear layer.
+///
+/// The linear layer is Griffin's `circ(2, 1, 1)` for T = 3 and its `M4`
+/// block matrix for T a multiple of 4. The round constants and `alpha`,
+/// `beta` are not Griffin's, which are sampled with SHAKE128, they come
+/// from a fixed xorshift sequence. So this is not Griffin-π and matches
+/// none of its test vectors: like `DummyHashChip` it is a stand-in with the
+/// real circuit shape.
+#[derive(Clone, Debug)]
+pub struct DummyGriffinParams<F: FieldExt, const T: usize> {
+    matrix: [[F; T]; T],
+    // one per round but the last, which adds none
+    round_constants: Vec<[F; T]>,
+    alpha: F,
+    beta: F,
+    // 1 / D modulo p - 1, little endian limbs
+    d_inv: Vec<u64>,
+}
+
+impl<F: FieldExt, const T: usize> DummyGriffinParams<F, T> {
+    pub fn new() -> Self {
+        assert!(
+            T == 3 || (T > 0 && T % 4 == 0),
+            "Griffin is defined for a state of 3 or 4k words"
+        );
+
+        let mut rng = 0x6772_6966_6669_6eu64;
+        let mut next = || {
+            rng ^= rng << 13;
+            rng ^= rng >> 7;
+            rng ^= rng << 17;
+            F::from(rng)
+        };
+        let round_constants = (0..ROUNDS - 1).map(|_| [(); T].map(|_| next())).collect();
+
+        // x^2 + alpha * x + beta has no root for a nonsquare discriminant,
+        // which keeps every nonlinear word invertible
+        let alpha = next();
+        let beta = loop {
+            let beta = next();
+            let discriminant = alpha.square() - beta * F::from(4);
+            if bool::from(discriminant.sqrt().is_none()) {
+                break beta;
+            }
+        };
+
+        Self {
+            matrix: linear_layer(),
+            round_constants,
+            alpha,
+            beta,
+            d_inv: d_inv::<F>(),
+        }
+    }
+
+    fn linear(&self, state: &[F; T]) -> [F; T] {
+        self.matrix.map(|row| {
+            row.iter()
+                .zip(state)
+                .fold(F::zero(), |sum, (m, x)| sum + *m * x)
+        })
+    }
+
+    /// `x^(1 / D)`, the inverse of `x^D`.
+    pub fn root(&self, x: F) -> F {
+        x.pow_vartime(&self.d_inv)
+    }
+
+    // `(i - 1) * y_0 + y_1 + x_{i-1}`, without the last term for i = 2
+    fn l(i: usize, y0: F, y1: F, state: &[F; T]) -> F {
+        let l = F::from(i as u64 - 1) * y0 + y1;
+        if i == 2 {
+            l
+        } else {
+            l + state[i - 1]
+        }
+    }
+
+    fn nonlinear(&self, state: &[F; T]) -> [F; T] {
+        let y0 = self.root(state[0]);
+        let y1 = state[1].pow_vartime([D]);
+        let mut y = [F::zero(); T];
+        y[0] = y0;
+        y[1] = y1;
+        for (i, (y, x)) in y.iter_mut().zip(state).enumerate().skip(2) {
+            let l = Self::l(i, y0, y1, state);
+            let k = F::from(i as u64 - 1);
+            *y = *x * (l.square() + k * self.alpha * l + k.square() * self.beta);
+        }
+        y
+    }
+
+    /// Round `round` of the permutation, the nonlinear layer, the linear
+    /// layer and the round constants.
+    pub fn round(&self, state: &[F; T], round: usize) -> [F; T] {
+        let mut state = self.linear(&self.nonlinear(state));
+        if let Some(constants) = self.round_constants.get(round) {
+            for (x, c) in state.iter_mut().zip(constants) {
+                *x += c;
+            }
+        }
+        state
+    }
+
+    /// The permutation on plain field elements, the linear layer then the
+    /// ROUNDS rounds.
+    pub fn permute(&self, state: &[F; T]) -> [F; T] {
+        (0..ROUNDS).fold(self.linear(state), |state, round| self.round(&state, round))
+    }
+
+    fn constants(&self, round: usize) -> [F; T] {
+        self.round_constants
+            .get(round)
+            .copied()
+            .unwrap_or([F::zero(); T])
+    }
+}
+
+impl<F: FieldExt, const T: usize> Default for DummyGriffinParams<F, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn linear_layer<F: FieldExt, const T: usize>() -> [[F; T]; T] {
+    let mut matrix = [[F::zero(); T]; T];
+    if T == 3 {
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, m) in row.iter_mut().enumerate() {
+                *m = F::from(if i == j { 2 } else { 1 });
+            }
+        }
+        return matrix;
+    }
+
+    const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, m) in row.iter_mut().enumerate() {
+            let weight = if i / 4 == j / 4 { 2 } else { 1 };
+            *m = F::from(weight * M4[i % 4][j % 4]);
+        }
+    }
+    matrix
+}
+
+// e with `D * e == 1` modulo `p - 1`, as `(k * (p - 1) + 1) / D` for the k
+// that makes it divisible
+fn d_inv<F: FieldExt>() -> Vec<u64> {
+    let repr = (-F::one()).to_repr();
+    let mut p_minus_1: Vec<u64> = repr
+        .as_ref()
+        .chunks(8)
+        .map(|chunk| {
+            let mut limb = [0; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(limb)
+        })
+        .collect();
+    p_minus_1.push(0);
+
+    for k in 1..D {
+        // k * (p - 1) + 1
+        let mut carry = 1u128;
+        let numerator: Vec<u64> = p_minus_1
+            .iter()
+            .map(|limb| {
+                let v = *limb as u128 * k as u128 + carry;
+                carry = v >> 64;
+                v as u64
+            })
+            .collect();
+
+        let mut remainder = 0u128;
+        let mut quotient = vec![0; numerator.len()];
+        for (q, limb) in quotient.iter_mut().zip(&numerator).rev() {
+            let v = (remainder << 64) | *limb as u128;
+            *q = (v / D as u128) as u64;
+            remainder = v % D as u128;
+        }
+        if remainder == 0 {
+            return quotient;
+        }
+    }
+    panic!("x^{D} is not a permutation of this field");
+}
+
+#[derive(Clone, Debug)]
+pub struct DummyGriffinConfig<F: FieldExt, const T: usize> {
+    state: [Column<Advice>; T],
+    // y_0 = x_0^(1 / D) and y_1 = x_1^D of each round
+    root: Column<Advice>,
+    power: Column<Advice>,
+    constants: [Column<Fixed>; T],
+    q_linear: Selector,
+    q_round: Selector,
+    params: DummyGriffinParams<F, T>,
+}
+
+/// The `DummyGriffinParams` permutation over a state of T words, one row
+/// per round:
+///
+/// | state          | root | power | constants | q_linear | q_round |
+/// |----------------|------|-------|-----------|----------|---------|
+/// | input          |      |       |           | 1        | 0       |
+/// | M * input      | y_0  | y_1   | c_0       | 0        | 1       |
+/// | ...            | ...  | ...   | ...       | 0        | 1       |
+/// | round R - 1    | y_0  | y_1   | 0         | 0        | 1       |
+/// | output         |      |       |           | 0        | 0       |
+///
+/// The round gate checks `root^D == x_0` and `power == x_1^D` and that the
+/// next row is `M * y + c`, with the other nonlinear words
+/// `y_i = x_i * (L_i^2 + alpha_i * L_i + beta_i)` inlined. The power maps
+/// make it a degree 6 gate, which raises the degree of the whole circuit.
+#[derive(Clone, Debug)]
+pub struct DummyGriffinChip<F: FieldExt, const T: usize> {
+    config: DummyGriffinConfig<F, T>,
+}
+
+impl<F: FieldExt, const T: usize> Chip<F> for DummyGriffinChip<F, T> {
+    type Config = DummyGriffinConfig<F, T>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const T: usize> DummyGriffinChip<F, T> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// The `state` columns get equality for the input and output copies.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        state: [Column<Advice>; T],
+        root: Column<Advice>,
+        power: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        for column in state {
+            meta.enable_equality(column);
+        }
+        let constants = [(); T].map(|_| meta.fixed_column());
+        let q_linear = meta.selector();
+        let q_round = meta.selector();
+        let params = DummyGriffinParams::<F, T>::new();
+
+        meta.create_gate("dummy griffin linear", |meta| {
+            let s = meta.query_selector(q_linear);
+            let x = state.map(|column| meta.query_advice(column, Rotation::cur()));
+            let next = state.map(|column| meta.query_advice(column, Rotation::next()));
+            next.into_iter()
+                .zip(params.matrix)
+                .map(|(next, row)| {
+                    let terms: Vec<_> = x.iter().cloned().zip(row).collect();
+                    s.clone() * (next - linear_combination(&terms))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("dummy griffin round", |meta| {
+            let s = meta.query_selector(q_round);
+            let x = state.map(|column| meta.query_advice(column, Rotation::cur()));
+            let next = state.map(|column| meta.query_advice(column, Rotation::next()));
+            let c = constants.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let y0 = meta.query_advice(root, Rotation::cur());
+            let y1 = meta.query_advice(power, Rotation::cur());
+            let pow = |x: Expression<F>| (1..D).fold(x.clone(), |acc, _| acc * x.clone());
+
+            let mut y = vec![y0.clone(), y1.clone()];
+            for (index, pair) in x.windows(2).enumerate().skip(1) {
+                // (x_{i-1}, x_i) with i = index + 1, so k = i - 1
+                let k = F::from(index as u64);
+                let mut terms = vec![(y0.clone(), k), (y1.clone(), F::one())];
+                if index > 1 {
+                    terms.push((pair[0].clone(), F::one()));
+                }
+                let l = linear_combination(&terms);
+                let quadratic = l.clone() * l.clone()
+                    + l * (k * params.alpha)
+                    + Expression::Constant(k.square() * params.beta);
+                y.push(pair[1].clone() * quadratic);
+            }
+
+            let mut constraints = vec![
+                s.clone() * (pow(y0) - x[0].clone()),
+                s.clone() * (y1 - pow(x[1].clone())),
+            ];
+            for ((next, row), c) in next.into_iter().zip(params.matrix).zip(c) {
+                let terms: Vec<_> = y.iter().cloned().zip(row).collect();
+                constraints.push(s.clone() * (next - linear_combination(&terms) - c));
+            }
+            constraints
+        });
+
+        DummyGriffinConfig {
+            state,
+            root,
+            power,
+            constants,
+            q_linear,
+            q_round,
+            params,
+        }
+    }
+
+    pub fn params(&self) -> &DummyGriffinParams<F, T> {
+        &self.config.params
+    }
+
+    pub fn permute(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: &[AssignedCell<F, F>; T],
+    ) -> Result<[AssignedCell<F, F>; T], Error> {
+        let config = self.config();
+        let params = &config.params;
+
+        layouter.assign_region(
+            || "dummy griffin",
+            |mut region| {
+                config.q_linear.enable(&mut region, 0)?;
+                for (cell, column) in input.iter().zip(config.state) {
+                    cell.copy_advice(|| "input", &mut region, column, 0)?;
+                }
+
+                let input = input
+                    .iter()
+                    .fold(Value::known(vec![]), |state, cell| {
+                        state.zip(cell.value()).map(|(mut state, x)| {
+                            state.push(*x);
+                            state
+                        })
+                    })
+                    .map(|state| <[F; T]>::try_from(state).expect("T words"));
+                let mut state = input.map(|input| params.linear(&input));
+
+                for round in 0..ROUNDS {
+                    let row = round + 1;
+                    config.q_round.enable(&mut region, row)?;
+                    self.assign_state(&mut region, row, state)?;
+                    let y0 = state.map(|state| params.root(state[0]));
+                    let y1 = state.map(|state| state[1].pow_vartime([D]));
+                    region.assign_advice(|| "root", config.root, row, || y0)?;
+                    region.assign_advice(|| "power", config.power, row, || y1)?;
+                    for (column, c) in config.constants.iter().zip(params.constants(round)) {
+                        region.assign_fixed(
+                            || "round constant",
+                            *column,
+                            row,
+                            || Value::known(c),
+                        )?;
+                    }
+
+                    state = state.map(|state| params.round(&state, round));
+                }
+
+                let output = self.assign_state(&mut region, ROUNDS + 1, state)?;
+                Ok(output.try_into().expect("T words"))
+            },
+        )
+    }
+
+    fn assign_state(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        state: Value<[F; T]>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.config()
+            .state
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                region.assign_advice(|| "state", *column, row, || state.map(|state| state[i]))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness, TestRng};
+
+    struct DummyGriffinTest<const T: usize>;
+
+    impl<F: FieldExt, const T: usize> HarnessChip<F> for DummyGriffinTest<T> {
+        type Config = DummyGriffinConfig<F, T>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let state = [(); T].map(|_| meta.advice_column());
+            let root = meta.advice_column();
+            let power = meta.advice_column();
+            DummyGriffinChip::configure(meta, state, root, power)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let input = witnesses.try_into().expect("T witnesses");
+            let output = DummyGriffinChip::construct(config)
+                .permute(layouter.namespace(|| "dummy griffin"), &input)?;
+            Ok(output.to_vec())
+        }
+    }
+
+    #[test]
+    fn test_root() {
+        let params = DummyGriffinParams::<Fp, 3>::new();
+        let mut rng = TestRng::new(0x9f);
+        for _ in 0..8 {
+            let x = Fp::from(rng.next_u64());
+            assert_eq!(params.root(x).pow_vartime([D]), x);
+            assert_eq!(params.root(x.pow_vartime([D])), x);
+        }
+    }
+
+    #[test]
+    fn test_zero_state() {
+        let params = DummyGriffinParams::<Fp, 3>::new();
+        let output = params.permute(&[Fp::zero(); 3]);
+        assert!(output.iter().all(|x| *x != Fp::zero()));
+
+        let harness = TestHarness::auto();
+        harness.expect_pass(DummyGriffinTest::<3>, vec![Fp::zero(); 3], output.to_vec());
+        let mut wrong = output;
+        wrong[1] += Fp::one();
+        harness.expect_fail(DummyGriffinTest::<3>, vec![Fp::zero(); 3], wrong.to_vec());
+    }
+
+    // a self-consistency check of the circuit against the host side
+    // `permute`, with made up constants there are no test vectors
+    #[test]
+    fn test_matches_host_permutation() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0x6f1);
+
+        let input: Vec<_> = (0..3).map(|_| Fp::from(rng.next_u64())).collect();
+        let output = DummyGriffinParams::<Fp, 3>::new().permute(&input.clone().try_into().unwrap());
+        harness.expect_pass(DummyGriffinTest::<3>, input, output.to_vec());
+
+        // the block matrix linear layer and more nonlinear words
+        let input: Vec<_> = (0..8).map(|_| Fp::from(rng.next_u64())).collect();
+        let output = DummyGriffinParams::<Fp, 8>::new().permute(&input.clone().try_into().unwrap());
+        harness.expect_pass(DummyGriffinTest::<8>, input, output.to_vec());
+    }
+
+    #[test]
+    fn test_round_changes_every_word() {
+        let mut rng = TestRng::new(0x40);
+        for state in [[Fp::zero(); 4], [(); 4].map(|_| Fp::from(rng.next_u64()))] {
+            let params = DummyGriffinParams::<Fp, 4>::new();
+            let next = params.round(&state, 0);
+            assert!(state.iter().zip(&next).all(|(x, y)| x != y));
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+/// An assigned affine point `(x, y)`.
+#[derive(Clone, Debug)]
+pub struct EcPoint<F: FieldExt> {
+    pub x: AssignedCell<F, F>,
+    pub y: AssignedCell<F, F>,
+}
+
+/// The `(r, s)` components of a signature.
+#[derive(Clone, Debug)]
+pub struct EcdsaSignature<F: FieldExt> {
+    pub r: AssignedCell<F, F>,
+    pub s: AssignedCell<F, F>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EcdsaConfig<F: FieldExt> {
+    // public key x, public key y, message hash, r, s
+    advice: [Column<Advice>; 5],
+    _marker: PhantomData<F>,
+}
+
+/// Skeleton of an ECDSA verification chip. Given the public key `Q`, the
+/// message hash `z` and a signature `(r, s)` it is meant to check
+///
+/// ```text
+/// u1 = z * s^-1 mod n
+/// u2 = r * s^-1 mod n
+/// x(u1 * G + u2 * Q) == r
+/// ```
+///
+/// where `G` is the generator and `n` the group order. None of the curve
+/// arithmetic exists yet (it needs non-native field arithmetic), so only the
+/// input layout is implemented and the rest returns `Error::Synthesis`.
+#[derive(Clone, Debug)]
+pub struct EcdsaVerifyChip<F: FieldExt> {
+    config: EcdsaConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for EcdsaVerifyChip<F> {
+    type Config = EcdsaConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> EcdsaVerifyChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        _meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+    ) -> <Self as Chip<F>>::Config {
+        // TODO: gates for point addition, doubling and reduction mod n.
+        EcdsaConfig {
+            advice,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns `scalar * point`. Not implemented yet.
+    pub fn scalar_mul(
+        &self,
+        _layouter: impl Layouter<F>,
+        _scalar: AssignedCell<F, F>,
+        _point: &EcPoint<F>,
+    ) -> Result<EcPoint<F>, Error> {
+        Err(Error::Synthesis)
+    }
+
+    /// Checks `signature` over `msg_hash` against `public_key`.
+    pub fn verify_signature(
+        &self,
+        mut layouter: impl Layouter<F>,
+        public_key: &EcPoint<F>,
+        msg_hash: AssignedCell<F, F>,
+        signature: &EcdsaSignature<F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "ecdsa inputs",
+            |mut region| {
+                let inputs = [
+                    &public_key.x,
+                    &public_key.y,
+                    &msg_hash,
+                    &signature.r,
+                    &signature.s,
+                ];
+                for (cell, column) in inputs.into_iter().zip(config.advice) {
+                    cell.copy_advice(|| "input", &mut region, column, 0)?;
+                }
+                Ok(())
+            },
+        )?;
+
+        // TODO: compute u1 and u2, then u1 * G + u2 * Q with scalar_mul and
+        // compare its x coordinate with r.
+        Err(Error::Synthesis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::Circuit,
+    };
+
+    use super::*;
+
+    const K: u32 = 4;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        public_key: (Value<F>, Value<F>),
+        msg_hash: Value<F>,
+        signature: (Value<F>, Value<F>),
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        ecdsa_config: EcdsaConfig<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let ecdsa_advice = [(); 5].map(|_| meta.advice_column());
+
+            meta.enable_equality(advice);
+            for column in ecdsa_advice {
+                meta.enable_equality(column);
+            }
+
+            TestCircuitConfig {
+                advice,
+                ecdsa_config: EcdsaVerifyChip::configure(meta, ecdsa_advice),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = EcdsaVerifyChip::construct(config.ecdsa_config);
+
+            let (public_key, msg_hash, signature) = layouter.assign_region(
+                || "load inputs",
+                |mut region| {
+                    let mut load = |offset, value| {
+                        region.assign_advice(|| "input", config.advice, offset, || value)
+                    };
+                    let public_key = EcPoint {
+                        x: load(0, self.public_key.0)?,
+                        y: load(1, self.public_key.1)?,
+                    };
+                    let msg_hash = load(2, self.msg_hash)?;
+                    let signature = EcdsaSignature {
+                        r: load(3, self.signature.0)?,
+                        s: load(4, self.signature.1)?,
+                    };
+                    Ok((public_key, msg_hash, signature))
+                },
+            )?;
+
+            chip.verify_signature(
+                layouter.namespace(|| "verify"),
+                &public_key,
+                msg_hash,
+                &signature,
+            )
+        }
+    }
+
+    #[test]
+    fn test_chip_api() {
+        fn assert_chip<C: Chip<Fp, Config = EcdsaConfig<Fp>, Loaded = ()>>() {}
+        assert_chip::<EcdsaVerifyChip<Fp>>();
+    }
+
+    #[test]
+    fn test_verify_signature_not_implemented() {
+        // Hardcoded placeholder values, the chip refuses to synthesize until
+        // the curve arithmetic exists.
+        let circuit = TestCircuit::<Fp> {
+            public_key: (Value::known(Fp::from(3)), Value::known(Fp::from(5))),
+            msg_hash: Value::known(Fp::from(7)),
+            signature: (Value::known(Fp::from(11)), Value::known(Fp::from(13))),
+        };
+
+        assert!(matches!(
+            MockProver::run(K, &circuit, vec![]),
+            Err(Error::Synthesis)
+        ));
+    }
+}
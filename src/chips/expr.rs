@@ -0,0 +1,155 @@
+use halo2_proofs::{halo2curves::FieldExt, plonk::Expression};
+
+/// `sum(expr_i * coeff_i)` as a balanced tree. Constant terms are folded
+/// into one constant, zero coefficients dropped and unit ones left
+/// unscaled, so a wide recomposition costs neither a deep tree nor a
+/// product per term when keygen walks the gates.
+///
+/// Building the same sum by folding from `Expression::Constant(0)` with a
+/// `Constant(coeff)` product per term nests one level per term, which is
+/// what made keygen slow for wide chips.
+pub fn linear_combination<F: FieldExt>(terms: &[(Expression<F>, F)]) -> Expression<F> {
+    let mut constant = F::zero();
+    let mut scaled = Vec::with_capacity(terms.len());
+    for (expr, coeff) in terms {
+        match expr {
+            Expression::Constant(c) => constant += *c * coeff,
+            _ if *coeff == F::zero() => {}
+            _ if *coeff == F::one() => scaled.push(expr.clone()),
+            _ => scaled.push(expr.clone() * *coeff),
+        }
+    }
+    if constant != F::zero() || scaled.is_empty() {
+        scaled.push(Expression::Constant(constant));
+    }
+    balanced_sum(&scaled)
+}
+
+/// `sum(chunks[i] * 2^(i * bits))`, the value of BITS-bit chunks given
+/// least significant first.
+pub fn pow2_recompose<F: FieldExt>(chunks: &[Expression<F>], bits: usize) -> Expression<F> {
+    let radix = (0..bits).fold(F::one(), |radix, _| radix.double());
+    let mut coeff = F::one();
+    let terms: Vec<_> = chunks
+        .iter()
+        .map(|chunk| {
+            let term = (chunk.clone(), coeff);
+            coeff *= radix;
+            term
+        })
+        .collect();
+    linear_combination(&terms)
+}
+
+fn balanced_sum<F: FieldExt>(exprs: &[Expression<F>]) -> Expression<F> {
+    match exprs {
+        [] => Expression::Constant(F::zero()),
+        [expr] => expr.clone(),
+        _ => {
+            let (left, right) = exprs.split_at(exprs.len() / 2);
+            balanced_sum(left) + balanced_sum(right)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{halo2curves::pasta::Fp, plonk::ConstraintSystem, poly::Rotation};
+
+    use super::*;
+
+    // every advice column `i` evaluates to `i + 1`
+    fn eval(expr: &Expression<Fp>) -> Fp {
+        expr.evaluate(
+            &|c| c,
+            &|_| unreachable!("no selectors"),
+            &|_| unreachable!("no fixed columns"),
+            &|query| Fp::from(query.column_index() as u64 + 1),
+            &|_| unreachable!("no instance columns"),
+            &|_| unreachable!("no challenges"),
+            &|a| -a,
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|a, scalar| a * scalar,
+        )
+    }
+
+    fn depth(expr: &Expression<Fp>) -> usize {
+        expr.evaluate(
+            &|_| 1,
+            &|_| 1,
+            &|_| 1,
+            &|_| 1,
+            &|_| 1,
+            &|_| 1,
+            &|a| a + 1,
+            &|a, b| a.max(b) + 1,
+            &|a, b| a.max(b) + 1,
+            &|a, _| a + 1,
+        )
+    }
+
+    // the built and the naive expression over `n` advice queries, with the
+    // coefficients `coeff(i)`
+    fn build(n: usize, coeff: impl Fn(usize) -> Fp) -> (Expression<Fp>, Expression<Fp>) {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let columns: Vec<_> = (0..n).map(|_| meta.advice_column()).collect();
+        let mut exprs = None;
+        meta.create_gate("recompose", |meta| {
+            let terms: Vec<_> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| (meta.query_advice(*column, Rotation::cur()), coeff(i)))
+                .collect();
+            let naive = terms
+                .iter()
+                .fold(Expression::Constant(Fp::zero()), |acc, (expr, coeff)| {
+                    acc + expr.clone() * Expression::Constant(*coeff)
+                });
+            let built = linear_combination(&terms);
+            exprs = Some((built.clone(), naive.clone()));
+            vec![built, naive]
+        });
+        exprs.unwrap()
+    }
+
+    #[test]
+    fn test_matches_naive_fold() {
+        for n in [1, 2, 3, 8, 33] {
+            let (built, naive) = build(n, |i| Fp::from(i as u64 * 7 + 3));
+            assert_eq!(eval(&built), eval(&naive));
+        }
+        // zero and unit coefficients
+        let (built, naive) = build(6, |i| Fp::from(i as u64 % 2));
+        assert_eq!(eval(&built), eval(&naive));
+    }
+
+    #[test]
+    fn test_balanced() {
+        let (built, naive) = build(64, |i| Fp::from(1 << (i % 32)));
+        // a sum of 64 scaled queries, 6 levels of sums over one of scaling
+        assert_eq!(depth(&built), 8);
+        assert_eq!(depth(&naive), 66);
+    }
+
+    #[test]
+    fn test_constants_folded() {
+        let terms = [
+            (Expression::Constant(Fp::from(3)), Fp::from(2)),
+            (Expression::Constant(Fp::from(5)), Fp::one()),
+        ];
+        assert!(matches!(linear_combination(&terms), Expression::Constant(c) if c == Fp::from(11)));
+        assert!(
+            matches!(linear_combination::<Fp>(&[]), Expression::Constant(c) if c == Fp::zero())
+        );
+    }
+
+    #[test]
+    fn test_pow2_recompose() {
+        let chunks = [3, 1, 15, 0].map(|c| Expression::Constant(Fp::from(c)));
+        assert!(matches!(
+            pow2_recompose(&chunks, 4),
+            Expression::Constant(c) if c == Fp::from(0x0f13)
+        ));
+    }
+}
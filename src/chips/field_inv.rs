@@ -0,0 +1,167 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct InverseExistsConfig<F: FieldExt> {
+    value: Column<Advice>,
+    witness: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Proves `value` has a multiplicative inverse, i.e. is nonzero, with the
+/// `InverseChip` gate `value * witness == 1`. The inverse is only assigned as
+/// a private witness and isn't handed back, so nothing else in the circuit can
+/// be constrained against it. A zero value is laid out with a 0 witness and
+/// fails the gate.
+#[derive(Clone, Debug)]
+pub struct InverseExistsChip<F: FieldExt> {
+    config: InverseExistsConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for InverseExistsChip<F> {
+    type Config = InverseExistsConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> InverseExistsChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `witness` needs no equality, its cell never leaves the chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        witness: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("inverse exists", |meta| {
+            let s = meta.query_selector(selector);
+            let v = meta.query_advice(value, Rotation::cur());
+            let w = meta.query_advice(witness, Rotation::cur());
+            vec![s * (v * w - Expression::Constant(F::one()))]
+        });
+
+        InverseExistsConfig {
+            value,
+            witness,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn assert_invertible(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "inverse exists",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+
+                let witness = value.value().map(|v| v.invert().unwrap_or(F::zero()));
+                region.assign_advice(|| "witness", config.witness, 0, || witness)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{arithmetic::Field, halo2curves::pasta::Fp, plonk::Any};
+
+    use super::*;
+    use crate::{
+        dev::{record, AssignmentEvent},
+        testing::{HarnessChip, TestCircuit, TestHarness, TestRng},
+    };
+
+    struct InverseExistsTest;
+
+    impl<F: FieldExt> HarnessChip<F> for InverseExistsTest {
+        type Config = InverseExistsConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let witness = meta.advice_column();
+
+            meta.enable_equality(value);
+
+            InverseExistsChip::configure(meta, value, witness)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            InverseExistsChip::construct(config).assert_invertible(
+                layouter.namespace(|| "inverse exists"),
+                witnesses[0].clone(),
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_nonzero_passes() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0x1234);
+        for value in [1, 7, rng.next_u64()] {
+            harness.expect_pass(InverseExistsTest, vec![Fp::from(value)], vec![]);
+        }
+        harness.expect_pass(InverseExistsTest, vec![-Fp::one()], vec![]);
+    }
+
+    #[test]
+    fn test_zero_fails() {
+        TestHarness::auto().expect_fail(InverseExistsTest, vec![Fp::zero()], vec![]);
+    }
+
+    #[test]
+    fn test_witness_is_private() {
+        let value = Fp::from(7);
+        let circuit = TestCircuit::<Fp, InverseExistsTest>::new(vec![value]);
+        let trace = record(4, &circuit).unwrap();
+        assert!(circuit.outputs().is_empty());
+
+        // the inverse is in the witness, but its cell isn't copied anywhere
+        let inverse = value.invert().unwrap();
+        let column: Column<Any> = trace
+            .events
+            .iter()
+            .find_map(|event| match event {
+                AssignmentEvent::Advice { column, value, .. } if *value == Some(inverse) => {
+                    Some((*column).into())
+                }
+                _ => None,
+            })
+            .expect("inverse is assigned");
+        assert!(!trace.events.iter().any(|event| matches!(
+            event,
+            AssignmentEvent::Copy { left, right }
+                if left.0 == column || right.0 == column
+        )));
+    }
+}
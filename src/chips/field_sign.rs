@@ -0,0 +1,322 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::bit_decomposition::field_bit;
+
+#[derive(Clone, Debug)]
+pub struct FieldLtHalfConfig<F: FieldExt> {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    lt_half: Column<Advice>,
+    eq_half: Column<Advice>,
+    lt_max: Column<Advice>,
+    eq_max: Column<Advice>,
+    half_bit: Column<Fixed>,
+    max_bit: Column<Fixed>,
+    q_init: Selector,
+    q_step: Selector,
+    q_last: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Returns whether a field element is in `[0, (p - 1) / 2]`, the non
+/// negative half when read as a signed integer.
+///
+/// The value is decomposed into `F::NUM_BITS` bits, most significant first,
+/// and compared against the fixed bits of `(p + 1) / 2` and of `p - 1` in the
+/// same rows. Each comparison carries a `lt` and an `eq` flag for the prefix
+/// so far, from `lt = 0, eq = 1` in an initial row:
+///
+/// - `lt = lt_prev + eq_prev * (1 - bit) * c`
+/// - `eq = eq_prev * (bit * c + (1 - bit) * (1 - c))`
+///
+/// The last row requires `lt + eq = 1` against `p - 1`, so the bits are the
+/// canonical representation of the value, and returns `lt` against
+/// `(p + 1) / 2`.
+#[derive(Clone, Debug)]
+pub struct FieldLtHalfChip<F: FieldExt> {
+    config: FieldLtHalfConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for FieldLtHalfChip<F> {
+    type Config = FieldLtHalfConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+// the state after comparing `bit` against `c`
+fn compare_step<F: FieldExt>(
+    lt_prev: Expression<F>,
+    eq_prev: Expression<F>,
+    bit: Expression<F>,
+    c: Expression<F>,
+) -> (Expression<F>, Expression<F>) {
+    let one = Expression::Constant(F::one());
+    let not_bit = one.clone() - bit.clone();
+    let lt = lt_prev + eq_prev.clone() * not_bit.clone() * c.clone();
+    let eq = eq_prev * (bit * c.clone() + not_bit * (one - c));
+    (lt, eq)
+}
+
+impl<F: FieldExt> FieldLtHalfChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `acc` needs equality for the copy of the value.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+        [lt_half, eq_half, lt_max, eq_max]: [Column<Advice>; 4],
+    ) -> <Self as Chip<F>>::Config {
+        let half_bit = meta.fixed_column();
+        let max_bit = meta.fixed_column();
+        let q_init = meta.selector();
+        let q_step = meta.selector();
+        let q_last = meta.selector();
+
+        meta.create_gate("field lt half init", |meta| {
+            let q = meta.query_selector(q_init);
+            let one = Expression::Constant(F::one());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let lt_half = meta.query_advice(lt_half, Rotation::cur());
+            let eq_half = meta.query_advice(eq_half, Rotation::cur());
+            let lt_max = meta.query_advice(lt_max, Rotation::cur());
+            let eq_max = meta.query_advice(eq_max, Rotation::cur());
+            vec![
+                q.clone() * acc,
+                q.clone() * lt_half,
+                q.clone() * (eq_half - one.clone()),
+                q.clone() * lt_max,
+                q * (eq_max - one),
+            ]
+        });
+
+        meta.create_gate("field lt half step", |meta| {
+            let q = meta.query_selector(q_step);
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+
+            let mut constraints = vec![
+                q.clone() * bit.clone() * (one - bit.clone()), // bit is 0 or 1
+                q.clone() * (acc - acc_prev * two - bit.clone()),
+            ];
+            for (lt, eq, c) in [(lt_half, eq_half, half_bit), (lt_max, eq_max, max_bit)] {
+                let (lt_next, eq_next) = compare_step(
+                    meta.query_advice(lt, Rotation::prev()),
+                    meta.query_advice(eq, Rotation::prev()),
+                    bit.clone(),
+                    meta.query_fixed(c, Rotation::cur()),
+                );
+                constraints.push(q.clone() * (meta.query_advice(lt, Rotation::cur()) - lt_next));
+                constraints.push(q.clone() * (meta.query_advice(eq, Rotation::cur()) - eq_next));
+            }
+            constraints
+        });
+
+        meta.create_gate("field lt half canonical", |meta| {
+            let q = meta.query_selector(q_last);
+            let lt_max = meta.query_advice(lt_max, Rotation::cur());
+            let eq_max = meta.query_advice(eq_max, Rotation::cur());
+            vec![q * (lt_max + eq_max - Expression::Constant(F::one()))]
+        });
+
+        FieldLtHalfConfig {
+            bit,
+            acc,
+            lt_half,
+            eq_half,
+            lt_max,
+            eq_max,
+            half_bit,
+            max_bit,
+            q_init,
+            q_step,
+            q_last,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns 1 if `value <= (p - 1) / 2` and 0 otherwise.
+    pub fn is_lt_half(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let num_bits = F::NUM_BITS as usize;
+        // (p + 1) / 2 is the inverse of 2
+        let half = msb_bits(&F::from(2).invert().unwrap());
+        let max = msb_bits(&-F::one());
+
+        let bits = value.value().map(msb_bits);
+        let half_flags = bits.as_ref().map(|bits| prefix_flags(bits, &half));
+        let max_flags = bits.as_ref().map(|bits| prefix_flags(bits, &max));
+
+        layouter.assign_region(
+            || "field lt half",
+            |mut region| {
+                config.q_init.enable(&mut region, 0)?;
+                for (column, init) in [
+                    (config.acc, F::zero()),
+                    (config.lt_half, F::zero()),
+                    (config.eq_half, F::one()),
+                    (config.lt_max, F::zero()),
+                    (config.eq_max, F::one()),
+                ] {
+                    region.assign_advice(|| "init", column, 0, || Value::known(init))?;
+                }
+
+                let mut acc = Value::known(F::zero());
+                let mut lt_half = None;
+                for offset in 1..=num_bits {
+                    let i = offset - 1;
+                    config.q_step.enable(&mut region, offset)?;
+
+                    let bit = bits.as_ref().map(|bits| F::from(bits[i]));
+                    acc = acc * Value::known(F::from(2)) + bit;
+                    region.assign_advice(|| "bit", config.bit, offset, || bit)?;
+                    if offset == num_bits {
+                        value.copy_advice(|| "value", &mut region, config.acc, offset)?;
+                    } else {
+                        region.assign_advice(|| "acc", config.acc, offset, || acc)?;
+                    }
+
+                    region.assign_fixed(
+                        || "half bit",
+                        config.half_bit,
+                        offset,
+                        || Value::known(F::from(half[i])),
+                    )?;
+                    region.assign_fixed(
+                        || "max bit",
+                        config.max_bit,
+                        offset,
+                        || Value::known(F::from(max[i])),
+                    )?;
+
+                    let mut assign_flags = |lt, eq, flags: &Value<Vec<(bool, bool)>>| {
+                        let lt = region.assign_advice(
+                            || "lt",
+                            lt,
+                            offset,
+                            || flags.as_ref().map(|flags| F::from(flags[i].0)),
+                        )?;
+                        region.assign_advice(
+                            || "eq",
+                            eq,
+                            offset,
+                            || flags.as_ref().map(|flags| F::from(flags[i].1)),
+                        )?;
+                        Ok::<_, Error>(lt)
+                    };
+                    lt_half = Some(assign_flags(config.lt_half, config.eq_half, &half_flags)?);
+                    assign_flags(config.lt_max, config.eq_max, &max_flags)?;
+                }
+
+                config.q_last.enable(&mut region, num_bits)?;
+                Ok(lt_half.expect("at least one bit"))
+            },
+        )
+    }
+}
+
+// the `F::NUM_BITS` bits of `value`, most significant first
+fn msb_bits<F: FieldExt>(value: &F) -> Vec<bool> {
+    (0..F::NUM_BITS as usize)
+        .rev()
+        .map(|i| field_bit(value, i))
+        .collect()
+}
+
+// `(lt, eq)` of every prefix of `bits` against the same prefix of `constant`
+fn prefix_flags(bits: &[bool], constant: &[bool]) -> Vec<(bool, bool)> {
+    let (mut lt, mut eq) = (false, true);
+    bits.iter()
+        .zip(constant)
+        .map(|(bit, c)| {
+            lt = lt || (eq && !bit && *c);
+            eq = eq && bit == c;
+            (lt, eq)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    struct LtHalfTest;
+
+    impl<F: FieldExt> HarnessChip<F> for LtHalfTest {
+        type Config = FieldLtHalfConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let flags = [(); 4].map(|_| meta.advice_column());
+
+            meta.enable_equality(acc);
+            meta.enable_equality(flags[0]);
+
+            FieldLtHalfChip::configure(meta, bit, acc, flags)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let is_lt_half = FieldLtHalfChip::construct(config)
+                .is_lt_half(layouter.namespace(|| "lt half"), witnesses[0].clone())?;
+            Ok(vec![is_lt_half])
+        }
+    }
+
+    #[test]
+    fn test_lt_half() {
+        let harness = TestHarness::auto();
+        let half = Fp::from(2).invert().unwrap();
+        // (p - 1) / 2 is the largest value in the lower half, (p + 1) / 2
+        // the smallest one above it
+        for (value, expected) in [
+            (Fp::zero(), Fp::one()),
+            (Fp::one(), Fp::one()),
+            (half - Fp::one(), Fp::one()),
+            (half, Fp::zero()),
+            (-Fp::one(), Fp::zero()),
+        ] {
+            harness.expect_pass(LtHalfTest, vec![value], vec![expected]);
+            harness.expect_fail(LtHalfTest, vec![value], vec![Fp::one() - expected]);
+        }
+    }
+
+    #[test]
+    fn test_prefix_flags() {
+        let flags = prefix_flags(&[true, false, true], &[true, true, false]);
+        assert_eq!(flags, vec![(false, true), (true, false), (true, false)]);
+        let flags = prefix_flags(&[true, true], &[true, false]);
+        assert_eq!(flags, vec![(false, true), (false, false)]);
+        assert_eq!(prefix_flags(&[false], &[false]), vec![(false, true)]);
+    }
+}
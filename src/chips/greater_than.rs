@@ -0,0 +1,126 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+use super::{
+    less_than::{IsLtEqChip, IsLtEqConfig},
+    range_check::RangeCheckConfig,
+};
+
+/// Returns a boolean `is_gte = a >= b` for BITS-bit `a` and `b`, as
+/// `IsLtEqChip` with the arguments swapped.
+#[derive(Clone, Debug)]
+pub struct IsGtEqChip<F: FieldExt, const BITS: usize> {
+    config: IsLtEqConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for IsGtEqChip<F, BITS> {
+    type Config = IsLtEqConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> IsGtEqChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        diff: Column<Advice>,
+        is_gte: Column<Advice>,
+        range_check: RangeCheckConfig<F, BITS>,
+    ) -> <Self as Chip<F>>::Config {
+        IsLtEqChip::configure(meta, a, b, diff, is_gte, range_check)
+    }
+
+    pub fn is_gte(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        IsLtEqChip::construct(self.config().clone()).is_lte(layouter, b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::range_check::RangeCheckChip,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    struct IsGtEqTest;
+
+    impl<F: FieldExt> HarnessChip<F> for IsGtEqTest {
+        type Config = IsLtEqConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let diff = meta.advice_column();
+            let is_gte = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+
+            for column in [a, b, diff, is_gte, acc] {
+                meta.enable_equality(column);
+            }
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            IsGtEqChip::configure(meta, a, b, diff, is_gte, range_check)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = IsGtEqChip::construct(config);
+            let is_gte = chip.is_gte(
+                layouter.namespace(|| "is gte"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![is_gte])
+        }
+    }
+
+    fn expect(a: u64, b: u64, is_gte: bool) {
+        TestHarness::auto().expect_pass(
+            IsGtEqTest,
+            vec![Fp::from(a), Fp::from(b)],
+            vec![Fp::from(is_gte)],
+        );
+    }
+
+    #[test]
+    fn test_is_gte() {
+        expect(5, 5, true);
+        expect(6, 5, true);
+        expect(4, 5, false);
+        expect(0, 0, true);
+    }
+
+    #[test]
+    fn test_is_gte_wrong_result_fail() {
+        let harness = TestHarness::auto();
+        harness.expect_fail(IsGtEqTest, vec![Fp::from(4), Fp::from(5)], vec![Fp::one()]);
+        harness.expect_fail(IsGtEqTest, vec![Fp::from(6), Fp::from(5)], vec![Fp::zero()]);
+    }
+}
@@ -0,0 +1,160 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// A two to one hash that constructions like `SpongeChip` are generic over.
+pub trait HashGadget<F: FieldExt> {
+    fn hash_two(
+        &self,
+        layouter: impl Layouter<F>,
+        left: AssignedCell<F, F>,
+        right: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct DummyHashConfig<F: FieldExt> {
+    left: Column<Advice>,
+    right: Column<Advice>,
+    out: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// `h(l, r) = (l + 7)^2 + r` in one row. It is neither one way nor collision
+/// resistant, it only stands in for a real hash when testing constructions
+/// over `HashGadget`.
+#[derive(Clone, Debug)]
+pub struct DummyHashChip<F: FieldExt> {
+    config: DummyHashConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for DummyHashChip<F> {
+    type Config = DummyHashConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> DummyHashChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        left: Column<Advice>,
+        right: Column<Advice>,
+        out: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("dummy hash", |meta| {
+            let s = meta.query_selector(selector);
+            let l = meta.query_advice(left, Rotation::cur());
+            let r = meta.query_advice(right, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let shifted = l + Expression::Constant(F::from(7));
+            vec![s * (out - (shifted.clone() * shifted + r))]
+        });
+
+        DummyHashConfig {
+            left,
+            right,
+            out,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The hash on plain field elements.
+    pub fn hash(left: F, right: F) -> F {
+        let shifted = left + F::from(7);
+        shifted * shifted + right
+    }
+}
+
+impl<F: FieldExt> HashGadget<F> for DummyHashChip<F> {
+    fn hash_two(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left: AssignedCell<F, F>,
+        right: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "dummy hash",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                left.copy_advice(|| "left", &mut region, config.left, 0)?;
+                right.copy_advice(|| "right", &mut region, config.right, 0)?;
+                let out = left
+                    .value()
+                    .zip(right.value())
+                    .map(|(l, r)| Self::hash(*l, *r));
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    struct DummyHashTest;
+
+    impl<F: FieldExt> HarnessChip<F> for DummyHashTest {
+        type Config = DummyHashConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let left = meta.advice_column();
+            let right = meta.advice_column();
+            let out = meta.advice_column();
+
+            for column in [left, right, out] {
+                meta.enable_equality(column);
+            }
+
+            DummyHashChip::configure(meta, left, right, out)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let out = DummyHashChip::construct(config).hash_two(
+                layouter.namespace(|| "hash"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![out])
+        }
+    }
+
+    #[test]
+    fn test_dummy_hash() {
+        let (left, right) = (Fp::from(3), Fp::from(4));
+        assert_eq!(DummyHashChip::hash(left, right), Fp::from(104));
+
+        let harness = TestHarness::auto();
+        harness.expect_pass(DummyHashTest, vec![left, right], vec![Fp::from(104)]);
+        // not symmetric
+        harness.expect_fail(DummyHashTest, vec![right, left], vec![Fp::from(104)]);
+    }
+}
@@ -0,0 +1,237 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+};
+
+use super::hash::HashGadget;
+
+#[derive(Clone, Debug)]
+pub struct IncrementalMerkleConfig<F: FieldExt> {
+    // holds the constant 0 the empty subtrees hash up from
+    advice: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+/// An append-only binary Merkle tree of `2^depth` leaves, all 0 until they
+/// are appended, which only keeps the frontier: for every level the last
+/// left child appended at that level.
+///
+/// Appending the leaf at index `i` walks up from the leaf. At a level where
+/// bit `l` of `i` is 0 the node is a left child, it becomes the frontier of
+/// the level and its sibling is the root of an empty subtree, `zero_l`. At a
+/// level where the bit is 1 its sibling is the frontier:
+///
+/// `node_{l+1} = hash(node_l, zero_l)` or `hash(frontier_l, node_l)`
+///
+/// and `node_depth` is the new root. Every append costs `depth` hashes, the
+/// first one another `depth` for `zero_1, ..., zero_depth`. The frontier is
+/// carried from one append to the next by the copy constraints of the
+/// hashes, the leaf index is fixed by the order of the calls.
+#[derive(Clone, Debug)]
+pub struct IncrementalMerkleChip<F: FieldExt, H: HashGadget<F>> {
+    config: IncrementalMerkleConfig<F>,
+    hash: H,
+    depth: usize,
+    // the roots of empty subtrees, computed by the first append
+    zeros: Vec<AssignedCell<F, F>>,
+    frontier: Vec<Option<AssignedCell<F, F>>>,
+    appended: u64,
+}
+
+impl<F: FieldExt, H: HashGadget<F>> IncrementalMerkleChip<F, H> {
+    pub fn construct(config: IncrementalMerkleConfig<F>, hash: H, depth: usize) -> Self {
+        assert!(depth < 64, "the leaf index is a u64");
+        Self {
+            config,
+            hash,
+            depth,
+            zeros: vec![],
+            frontier: vec![None; depth],
+            appended: 0,
+        }
+    }
+
+    /// `constants` is enabled for constants, it holds the empty leaf.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        constants: Column<Fixed>,
+    ) -> IncrementalMerkleConfig<F> {
+        meta.enable_equality(advice);
+        meta.enable_constant(constants);
+
+        IncrementalMerkleConfig {
+            advice,
+            _marker: PhantomData,
+        }
+    }
+
+    fn load_zeros(&mut self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let advice = self.config.advice;
+        let mut zero = layouter.assign_region(
+            || "empty leaf",
+            |mut region| region.assign_advice_from_constant(|| "zero", advice, 0, F::zero()),
+        )?;
+        for level in 0..self.depth {
+            self.zeros.push(zero.clone());
+            zero = self.hash.hash_two(
+                layouter.namespace(|| format!("empty subtree {}", level + 1)),
+                zero.clone(),
+                zero,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Appends `leaf` and returns the new root. Fails with
+    /// `Error::Synthesis` if the tree is full.
+    pub fn append(
+        &mut self,
+        mut layouter: impl Layouter<F>,
+        leaf: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if self.appended >= 1 << self.depth {
+            return Err(Error::Synthesis);
+        }
+        if self.zeros.len() < self.depth {
+            self.load_zeros(layouter.namespace(|| "empty subtrees"))?;
+        }
+
+        let index = self.appended;
+        let mut node = leaf;
+        for level in 0..self.depth {
+            let mut layouter = layouter.namespace(|| format!("level {level}"));
+            node = if (index >> level) & 1 == 0 {
+                self.frontier[level] = Some(node.clone());
+                let zero = self.zeros[level].clone();
+                self.hash
+                    .hash_two(layouter.namespace(|| "hash"), node, zero)?
+            } else {
+                let left = self.frontier[level]
+                    .clone()
+                    .expect("set by a previous append");
+                self.hash
+                    .hash_two(layouter.namespace(|| "hash"), left, node)?
+            };
+        }
+        self.appended += 1;
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::hash::{DummyHashChip, DummyHashConfig},
+        testing::{HarnessChip, TestHarness},
+    };
+
+    const DEPTH: usize = 4;
+
+    // the witnesses are the leaves, the outputs the root after every append
+    struct AppendTest<const TAMPER: bool>;
+
+    impl<F: FieldExt, const TAMPER: bool> HarnessChip<F> for AppendTest<TAMPER> {
+        type Config = (IncrementalMerkleConfig<F>, DummyHashConfig<F>);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let constants = meta.fixed_column();
+            let [left, right, out] = [(); 3].map(|_| meta.advice_column());
+            for column in [left, right, out] {
+                meta.enable_equality(column);
+            }
+            (
+                IncrementalMerkleChip::<F, DummyHashChip<F>>::configure(meta, advice, constants),
+                DummyHashChip::configure(meta, left, right, out),
+            )
+        }
+
+        fn synthesize(
+            (config, hash_config): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let hash = DummyHashChip::construct(hash_config);
+            let mut tree = IncrementalMerkleChip::construct(config, hash, DEPTH);
+            let mut roots = vec![];
+            for (i, leaf) in witnesses.iter().enumerate() {
+                if TAMPER && i == witnesses.len() - 1 {
+                    // the frontier of level 0 replaced by the leaf itself,
+                    // which the last append then hashes with
+                    tree.frontier[0] = Some(leaf.clone());
+                }
+                roots.push(tree.append(layouter.namespace(|| "append"), leaf.clone())?);
+            }
+            Ok(roots)
+        }
+    }
+
+    // the root of the full tree, the leaves padded with 0
+    fn host_root(leaves: &[Fp]) -> Fp {
+        fn root(leaves: &[Fp]) -> Fp {
+            match leaves {
+                [leaf] => *leaf,
+                _ => {
+                    let (left, right) = leaves.split_at(leaves.len() / 2);
+                    DummyHashChip::hash(root(left), root(right))
+                }
+            }
+        }
+        let mut padded = leaves.to_vec();
+        padded.resize(1 << DEPTH, Fp::zero());
+        root(&padded)
+    }
+
+    fn leaves(count: u64) -> Vec<Fp> {
+        (1..=count).map(|i| Fp::from(i * 11)).collect()
+    }
+
+    fn host_roots(leaves: &[Fp]) -> Vec<Fp> {
+        (1..=leaves.len())
+            .map(|len| host_root(&leaves[..len]))
+            .collect()
+    }
+
+    #[test]
+    fn test_append_matches_host() {
+        let harness = TestHarness::auto();
+        for count in [1, 2, 5] {
+            let leaves = leaves(count);
+            let roots = host_roots(&leaves);
+            harness.expect_pass(AppendTest::<false>, leaves.clone(), roots.clone());
+
+            let mut wrong = roots;
+            *wrong.last_mut().unwrap() += Fp::one();
+            harness.expect_fail(AppendTest::<false>, leaves, wrong);
+        }
+    }
+
+    #[test]
+    fn test_tampered_frontier() {
+        // the sixth leaf is a right child at level 0, its sibling must be
+        // the fifth leaf
+        let leaves = leaves(6);
+        let roots = host_roots(&leaves);
+        let harness = TestHarness::auto();
+        harness.expect_pass(AppendTest::<false>, leaves.clone(), roots.clone());
+        harness.expect_fail(AppendTest::<true>, leaves, roots);
+    }
+
+    #[test]
+    fn test_full_tree() {
+        let harness = TestHarness::auto();
+        let full = leaves(1 << DEPTH);
+        harness.expect_pass(AppendTest::<false>, full.clone(), host_roots(&full));
+
+        let too_many = leaves((1 << DEPTH) + 1);
+        let result = harness.try_run(AppendTest::<false>, too_many, vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
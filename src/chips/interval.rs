@@ -0,0 +1,240 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Fixed},
+};
+
+use super::comparator::{ComparatorChip, ComparatorConfig};
+use crate::error::ChipError;
+
+#[derive(Clone, Debug)]
+pub struct IntervalConfig<F: FieldExt, const N: usize> {
+    comparator: ComparatorConfig<F, N>,
+    // holds the bounds, pinned to constants
+    bound: Column<Advice>,
+}
+
+/// Returns a boolean `lo <= value <= hi` for an N-bit `value` and constant
+/// bounds, as `value >= lo` AND `value <= hi` with the `ComparatorChip`.
+///
+/// An empty interval, `lo > hi`, needs no special case: no value is both at
+/// least `lo` and at most `hi`, so the result is always 0. `value` must
+/// already be known to be N-bit.
+#[derive(Clone, Debug)]
+pub struct IntervalChip<F: FieldExt, const N: usize> {
+    config: IntervalConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for IntervalChip<F, N> {
+    type Config = IntervalConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> IntervalChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `constants` is enabled for constants, it holds the bounds.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        comparator: ComparatorConfig<F, N>,
+        bound: Column<Advice>,
+        constants: Column<Fixed>,
+    ) -> <Self as Chip<F>>::Config {
+        meta.enable_equality(bound);
+        meta.enable_constant(constants);
+
+        IntervalConfig { comparator, bound }
+    }
+
+    /// Fails with `ChipError::ConstantOutOfRange` before anything is
+    /// assigned if `lo` or `hi` doesn't fit in N bits.
+    pub fn in_range(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        lo: u64,
+        hi: u64,
+    ) -> Result<AssignedCell<F, F>, ChipError> {
+        ChipError::check_constant_bits("IntervalChip", lo, N)?;
+        ChipError::check_constant_bits("IntervalChip", hi, N)?;
+        let (lo, hi) = (F::from(lo), F::from(hi));
+        let config = self.config();
+
+        let (lo, hi) = layouter.assign_region(
+            || "interval bounds",
+            |mut region| {
+                Ok((
+                    region.assign_advice_from_constant(|| "lo", config.bound, 0, lo)?,
+                    region.assign_advice_from_constant(|| "hi", config.bound, 1, hi)?,
+                ))
+            },
+        )?;
+
+        let comparator = ComparatorChip::construct(config.comparator.clone());
+        let above_lo = comparator.gte(layouter.namespace(|| "value >= lo"), value.clone(), lo)?;
+        let below_hi = comparator.lte(layouter.namespace(|| "value <= hi"), value, hi)?;
+        Ok(comparator.and(layouter.namespace(|| "in range"), above_lo, below_hi)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Error},
+    };
+
+    use super::*;
+    use crate::{
+        chips::range_check::RangeCheckChip,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    struct IntervalTest<const LO: u64, const HI: u64>;
+
+    impl<F: FieldExt, const LO: u64, const HI: u64> HarnessChip<F> for IntervalTest<LO, HI> {
+        type Config = IntervalConfig<F, 8>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let [a, b, diff, out, bit, acc, bound] = [(); 7].map(|_| meta.advice_column());
+            for column in [a, b, diff, out, acc] {
+                meta.enable_equality(column);
+            }
+            let constants = meta.fixed_column();
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            let comparator = ComparatorChip::configure(meta, a, b, diff, out, range_check);
+            IntervalChip::configure(meta, comparator, bound, constants)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let in_range = IntervalChip::construct(config).in_range(
+                layouter.namespace(|| "in range"),
+                witnesses[0].clone(),
+                LO,
+                HI,
+            )?;
+            Ok(vec![in_range])
+        }
+    }
+
+    fn check<const LO: u64, const HI: u64>(value: u64) {
+        let expected = LO <= value && value <= HI;
+        let harness = TestHarness::auto();
+        let witnesses = vec![Fp::from(value)];
+        harness.expect_pass(
+            IntervalTest::<LO, HI>,
+            witnesses.clone(),
+            vec![Fp::from(expected)],
+        );
+        harness.expect_fail(IntervalTest::<LO, HI>, witnesses, vec![Fp::from(!expected)]);
+    }
+
+    #[test]
+    fn test_inside() {
+        for value in [10, 11, 100, 200] {
+            check::<10, 200>(value);
+        }
+        check::<0, 255>(0);
+        check::<0, 255>(255);
+        check::<42, 42>(42);
+    }
+
+    #[test]
+    fn test_below() {
+        for value in [0, 1, 9] {
+            check::<10, 200>(value);
+        }
+        check::<42, 42>(41);
+    }
+
+    #[test]
+    fn test_above() {
+        for value in [201, 202, 255] {
+            check::<10, 200>(value);
+        }
+        check::<42, 42>(43);
+    }
+
+    #[test]
+    fn test_empty_interval() {
+        for value in [0, 3, 5, 9, 255] {
+            check::<9, 3>(value);
+        }
+        check::<1, 0>(0);
+    }
+
+    // keeps the error of `in_range` instead of turning it into a plonk one
+    #[derive(Default)]
+    struct BoundCircuit<const LO: u64, const HI: u64> {
+        error: RefCell<Option<ChipError>>,
+    }
+
+    impl<F: FieldExt, const LO: u64, const HI: u64> Circuit<F> for BoundCircuit<LO, HI> {
+        type Config = IntervalConfig<F, 8>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            <IntervalTest<LO, HI> as HarnessChip<F>>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let value = layouter.assign_region(
+                || "value",
+                |mut region| {
+                    region.assign_advice(|| "value", config.bound, 0, || Value::known(F::from(3)))
+                },
+            )?;
+            let chip = IntervalChip::construct(config);
+            if let Err(err) = chip.in_range(layouter.namespace(|| "in range"), value, LO, HI) {
+                *self.error.borrow_mut() = Some(err);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_bound_out_of_range() {
+        let circuit = BoundCircuit::<0, 256>::default();
+        MockProver::<Fp>::run(6, &circuit, vec![]).unwrap();
+        assert!(matches!(
+            circuit.error.into_inner(),
+            Some(ChipError::ConstantOutOfRange {
+                chip: "IntervalChip",
+                value: 256,
+                max: 255
+            })
+        ));
+
+        // and through the harness, as the plonk error it converts into
+        let harness = TestHarness::auto();
+        let result = harness.try_run(IntervalTest::<256, 300>, vec![Fp::from(3)], vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
@@ -0,0 +1,145 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct InverseConfig<F: FieldExt> {
+    value: Column<Advice>,
+    inverse: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Witnesses `a^-1` and constrains `a * a^-1 == 1`, the nonzero half of the
+/// is zero gate. The constraint has no solution for `a == 0`, so `invert`
+/// refuses a zero input with `Error::Synthesis` instead of laying out a row
+/// that can't verify.
+#[derive(Clone, Debug)]
+pub struct InverseChip<F: FieldExt> {
+    config: InverseConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for InverseChip<F> {
+    type Config = InverseConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> InverseChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        inverse: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("inverse", |meta| {
+            let s = meta.query_selector(selector);
+            let v = meta.query_advice(value, Rotation::cur());
+            let v_inv = meta.query_advice(inverse, Rotation::cur());
+            vec![s * (v * v_inv - Expression::Constant(F::one()))]
+        });
+
+        InverseConfig {
+            value,
+            inverse,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn invert(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "invert",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "value", &mut region, config.value, 0)?;
+
+                let mut invertible = true;
+                let inverse = a.value().map(|a| {
+                    let inverse = a.invert();
+                    invertible = bool::from(inverse.is_some());
+                    inverse.unwrap_or(F::zero())
+                });
+                if !invertible {
+                    return Err(Error::Synthesis);
+                }
+
+                region.assign_advice(|| "inverse", config.inverse, 0, || inverse)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{arithmetic::Field, halo2curves::pasta::Fp};
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    struct InverseTest;
+
+    impl<F: FieldExt> HarnessChip<F> for InverseTest {
+        type Config = InverseConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let inverse = meta.advice_column();
+
+            meta.enable_equality(value);
+            meta.enable_equality(inverse);
+
+            InverseChip::configure(meta, value, inverse)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = InverseChip::construct(config);
+            let inverse = chip.invert(layouter.namespace(|| "invert"), witnesses[0].clone())?;
+            Ok(vec![inverse])
+        }
+    }
+
+    #[test]
+    fn test_invert_nonzero() {
+        let a = Fp::from(7);
+        let inverse = a.invert().unwrap();
+        assert_eq!(a * inverse, Fp::one());
+
+        let harness = TestHarness::auto();
+        harness.expect_pass(InverseTest, vec![a], vec![inverse]);
+        harness.expect_fail(InverseTest, vec![a], vec![inverse + Fp::one()]);
+    }
+
+    #[test]
+    fn test_invert_zero_is_error() {
+        let result = TestHarness::auto().try_run(InverseTest, vec![Fp::zero()], vec![Fp::zero()]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
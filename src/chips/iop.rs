@@ -0,0 +1,247 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::linear_combination::{LinearCombinationChip, LinearCombinationConfig};
+
+#[derive(Clone, Debug)]
+pub struct FoldingConfig<F: FieldExt, const N: usize> {
+    left: Column<Advice>,
+    right: Column<Advice>,
+    challenge: Column<Advice>,
+    folded: Column<Advice>,
+    q_fold: Selector,
+    relation: LinearCombinationConfig<F, N>,
+}
+
+/// The verifier side of folding for the linear relation `x == <a, w>`, with
+/// the coefficients `a` of the `LinearCombinationChip` it is configured
+/// with. Two pairs `(x_1, w_1)` and `(x_2, w_2)` fold with a challenge `r`
+/// into `x = x_1 + r * x_2` and `w = w_1 + r * w_2`, which satisfies the
+/// relation if both pairs do and, for a random `r`, only then.
+///
+/// This is the API a recursive verifier will build on, not such a
+/// verifier: the relation is linear so folding needs no cross term, and
+/// the challenge is a cell the caller provides where a real verifier
+/// derives it from a transcript of the two instances.
+///
+/// One fold is one row:
+///
+/// | left | right | challenge | folded             | q_fold |
+/// |------|-------|-----------|--------------------|--------|
+/// | x_1  | x_2   | r         | x_1 + r * x_2      | 1      |
+#[derive(Clone, Debug)]
+pub struct FoldingChip<F: FieldExt, const N: usize> {
+    config: FoldingConfig<F, N>,
+}
+
+/// `FoldingChip` under the name of the interactive oracle proof it
+/// compiles.
+pub type InteractiveOracleChip<F, const N: usize> = FoldingChip<F, N>;
+
+impl<F: FieldExt, const N: usize> Chip<F> for FoldingChip<F, N> {
+    type Config = FoldingConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> FoldingChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `relation` checks folded witnesses, its value and result columns
+    /// need equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: [Column<Advice>; 4],
+        relation: LinearCombinationConfig<F, N>,
+    ) -> <Self as Chip<F>>::Config {
+        for column in columns {
+            meta.enable_equality(column);
+        }
+        let [left, right, challenge, folded] = columns;
+        let q_fold = meta.selector();
+
+        meta.create_gate("fold", |meta| {
+            let q = meta.query_selector(q_fold);
+            let left = meta.query_advice(left, Rotation::cur());
+            let right = meta.query_advice(right, Rotation::cur());
+            let challenge = meta.query_advice(challenge, Rotation::cur());
+            let folded = meta.query_advice(folded, Rotation::cur());
+            vec![q * (left + challenge * right - folded)]
+        });
+
+        FoldingConfig {
+            left,
+            right,
+            challenge,
+            folded,
+            q_fold,
+            relation,
+        }
+    }
+
+    fn fold(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left: &AssignedCell<F, F>,
+        right: &AssignedCell<F, F>,
+        challenge: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "fold",
+            |mut region| {
+                config.q_fold.enable(&mut region, 0)?;
+                left.copy_advice(|| "left", &mut region, config.left, 0)?;
+                right.copy_advice(|| "right", &mut region, config.right, 0)?;
+                challenge.copy_advice(|| "challenge", &mut region, config.challenge, 0)?;
+
+                let folded =
+                    left.value().copied() + challenge.value().copied() * right.value().copied();
+                region.assign_advice(|| "folded", config.folded, 0, || folded)
+            },
+        )
+    }
+
+    /// `x_1 + r * x_2`.
+    pub fn fold_instance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        first: &AssignedCell<F, F>,
+        second: &AssignedCell<F, F>,
+        challenge: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.fold(layouter.namespace(|| "instance"), first, second, challenge)
+    }
+
+    /// `w_1 + r * w_2`, word by word.
+    pub fn fold_witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        first: &[AssignedCell<F, F>; N],
+        second: &[AssignedCell<F, F>; N],
+        challenge: &AssignedCell<F, F>,
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        let folded = first
+            .iter()
+            .zip(second)
+            .map(|(first, second)| {
+                self.fold(layouter.namespace(|| "witness"), first, second, challenge)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(folded.try_into().expect("N words"))
+    }
+
+    /// Constrains the folded pair to satisfy `x == <a, w>`.
+    pub fn verify_fold(
+        &self,
+        mut layouter: impl Layouter<F>,
+        instance: &AssignedCell<F, F>,
+        witness: &[AssignedCell<F, F>; N],
+    ) -> Result<(), Error> {
+        let relation = LinearCombinationChip::construct(self.config().relation.clone());
+        let combination = relation.combine(layouter.namespace(|| "relation"), witness)?;
+        layouter.assign_region(
+            || "verify fold",
+            |mut region| region.constrain_equal(combination.cell(), instance.cell()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    const COEFFS: [u64; 3] = [1, 2, 3];
+
+    // the witnesses are w_1, x_1, w_2, x_2 and the challenge, the output
+    // is the folded instance
+    struct FoldTest;
+
+    impl<F: FieldExt> HarnessChip<F> for FoldTest {
+        type Config = FoldingConfig<F, 3>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let columns = [(); 4].map(|_| meta.advice_column());
+            let values = [(); 3].map(|_| meta.advice_column());
+            let result = meta.advice_column();
+            for column in values.into_iter().chain([result]) {
+                meta.enable_equality(column);
+            }
+            let relation =
+                LinearCombinationChip::configure(meta, values, result, COEFFS.map(F::from));
+            FoldingChip::configure(meta, columns, relation)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = FoldingChip::construct(config);
+            let first: [_; 3] = witnesses[0..3].to_vec().try_into().unwrap();
+            let second: [_; 3] = witnesses[4..7].to_vec().try_into().unwrap();
+            let (x1, x2, r) = (&witnesses[3], &witnesses[7], &witnesses[8]);
+
+            let instance = chip.fold_instance(layouter.namespace(|| "instance"), x1, x2, r)?;
+            let witness =
+                chip.fold_witness(layouter.namespace(|| "witness"), &first, &second, r)?;
+            chip.verify_fold(layouter.namespace(|| "verify"), &instance, &witness)?;
+            Ok(vec![instance])
+        }
+    }
+
+    fn relation(witness: [u64; 3]) -> u64 {
+        witness.iter().zip(COEFFS).map(|(w, a)| w * a).sum()
+    }
+
+    fn inputs(first: [u64; 3], x1: u64, second: [u64; 3], x2: u64, r: u64) -> Vec<Fp> {
+        first
+            .into_iter()
+            .chain([x1])
+            .chain(second)
+            .chain([x2, r])
+            .map(Fp::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_fold_and_verify() {
+        let (first, second) = ([4, 5, 6], [7, 8, 9]);
+        let (x1, x2) = (relation(first), relation(second));
+        assert_eq!((x1, x2), (32, 50));
+
+        // 32 + 5 * 50, and <a, w_1 + 5 * w_2> = <a, [39, 45, 51]> = 282
+        let folded = Fp::from(282);
+        TestHarness::auto().expect_pass(FoldTest, inputs(first, x1, second, x2, 5), vec![folded]);
+    }
+
+    #[test]
+    fn test_unsatisfied_instance() {
+        let harness = TestHarness::auto();
+        let (first, second) = ([4, 5, 6], [7, 8, 9]);
+        let (x1, x2) = (relation(first), relation(second));
+
+        // either pair off by one, the folded instance is computed honestly
+        // but no longer matches the folded witness
+        for (x1, x2) in [(x1 + 1, x2), (x1, x2 + 1)] {
+            let folded = Fp::from(x1 + 5 * x2);
+            harness.expect_fail(FoldTest, inputs(first, x1, second, x2, 5), vec![folded]);
+        }
+    }
+}
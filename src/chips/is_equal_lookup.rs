@@ -0,0 +1,276 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, TableColumn},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct IsEqualLookupConfig<F: FieldExt, const BITS: usize> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    eq: Column<Advice>,
+    q_lookup: Selector,
+    table_a: TableColumn,
+    table_b: TableColumn,
+    table_eq: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+/// Equality of BITS-bit values by a lookup into the `2^(2 * BITS)` row table
+/// of `(a, b, a == b)` triples, in place of inverting `a - b`.
+///
+/// One row and no gates per comparison, for a table that is only practical
+/// for small BITS. The lookup also range checks both operands. Disabled rows
+/// look up `(0, 0, 1)`, which is in the table.
+#[derive(Clone, Debug)]
+pub struct IsEqualLookupChip<F: FieldExt, const BITS: usize> {
+    config: IsEqualLookupConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for IsEqualLookupChip<F, BITS> {
+    type Config = IsEqualLookupConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> IsEqualLookupChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        eq: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 16, "the equality table has 2^(2 * BITS) rows");
+        let q_lookup = meta.complex_selector();
+        let table_a = meta.lookup_table_column();
+        let table_b = meta.lookup_table_column();
+        let table_eq = meta.lookup_table_column();
+
+        meta.lookup("is equal", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let not_q = Expression::Constant(F::one()) - q.clone();
+            vec![
+                (q.clone() * a, table_a),
+                (q.clone() * b, table_b),
+                (q * eq + not_q, table_eq),
+            ]
+        });
+
+        IsEqualLookupConfig {
+            a,
+            b,
+            eq,
+            q_lookup,
+            table_a,
+            table_b,
+            table_eq,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_table(
+            || "is equal table",
+            |mut table| {
+                let mut offset = 0;
+                for a in 0..(1u64 << BITS) {
+                    for b in 0..(1u64 << BITS) {
+                        table.assign_cell(
+                            || "a",
+                            config.table_a,
+                            offset,
+                            || Value::known(F::from(a)),
+                        )?;
+                        table.assign_cell(
+                            || "b",
+                            config.table_b,
+                            offset,
+                            || Value::known(F::from(b)),
+                        )?;
+                        table.assign_cell(
+                            || "eq",
+                            config.table_eq,
+                            offset,
+                            || Value::known(F::from(a == b)),
+                        )?;
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns 1 if `a == b` and 0 otherwise.
+    pub fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "is equal",
+            |mut region| {
+                config.q_lookup.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "b", &mut region, config.b, 0)?;
+                let eq = a.value().zip(b.value()).map(|(a, b)| F::from(a == b));
+                region.assign_advice(|| "eq", config.eq, 0, || eq)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::{
+            is_zero::{IsZeroChip, IsZeroConfig},
+            linear_combination::{LinearCombinationChip, LinearCombinationConfig},
+        },
+        report::ConstraintReport,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    // `a - b` and the is zero gate on it
+    fn configure_gate<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+    ) -> (LinearCombinationConfig<F, 2>, IsZeroConfig<F>) {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let diff = meta.advice_column();
+        let diff_inverse = meta.advice_column();
+        let eq = meta.advice_column();
+
+        for column in [a, b, diff, diff_inverse, eq] {
+            meta.enable_equality(column);
+        }
+
+        (
+            LinearCombinationChip::configure(meta, [a, b], diff, [F::one(), -F::one()]),
+            IsZeroChip::configure(meta, diff, diff_inverse, eq),
+        )
+    }
+
+    fn configure_lookup<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> IsEqualLookupConfig<F, 3> {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let eq = meta.advice_column();
+
+        for column in [a, b, eq] {
+            meta.enable_equality(column);
+        }
+
+        IsEqualLookupChip::configure(meta, a, b, eq)
+    }
+
+    // outputs the lookup and the gate result
+    struct IsEqualTest;
+
+    impl<F: FieldExt> HarnessChip<F> for IsEqualTest {
+        type Config = (
+            IsEqualLookupConfig<F, 3>,
+            (LinearCombinationConfig<F, 2>, IsZeroConfig<F>),
+        );
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            (configure_lookup(meta), configure_gate(meta))
+        }
+
+        fn synthesize(
+            (lookup_config, (diff_config, is_zero_config)): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (a, b) = (witnesses[0].clone(), witnesses[1].clone());
+
+            let lookup_chip = IsEqualLookupChip::construct(lookup_config);
+            lookup_chip.load_table(&mut layouter.namespace(|| "is equal table"))?;
+            let lookup_eq =
+                lookup_chip.is_equal(layouter.namespace(|| "lookup"), a.clone(), b.clone())?;
+
+            let diff = LinearCombinationChip::construct(diff_config)
+                .combine(layouter.namespace(|| "a - b"), &[a, b])?;
+            let is_zero_chip = IsZeroChip::construct(is_zero_config);
+            let diff = is_zero_chip.load_cell(layouter.namespace(|| "load diff"), diff)?;
+            let gate_eq = is_zero_chip.is_zero(layouter.namespace(|| "gate"), diff)?;
+
+            Ok(vec![lookup_eq, gate_eq])
+        }
+    }
+
+    #[test]
+    fn test_matches_gate() {
+        let harness = TestHarness::auto();
+        for a in 0..8 {
+            for b in 0..8 {
+                let eq = Fp::from(a == b);
+                harness.expect_pass(IsEqualTest, vec![Fp::from(a), Fp::from(b)], vec![eq, eq]);
+            }
+        }
+        harness.expect_fail(
+            IsEqualTest,
+            vec![Fp::from(5), Fp::from(5)],
+            vec![Fp::zero(), Fp::one()],
+        );
+        harness.expect_fail(
+            IsEqualTest,
+            vec![Fp::from(2), Fp::from(5)],
+            vec![Fp::one(), Fp::zero()],
+        );
+    }
+
+    #[test]
+    fn test_wide_operand_fail() {
+        // 8 doesn't fit in 3 bits, the gate version doesn't care
+        TestHarness::auto().expect_fail(
+            IsEqualTest,
+            vec![Fp::from(8), Fp::from(8)],
+            vec![Fp::one(), Fp::one()],
+        );
+    }
+
+    #[test]
+    fn test_cost() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        configure_lookup(&mut meta);
+        let lookup = ConstraintReport::new(&meta);
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        configure_gate(&mut meta);
+        let gate = ConstraintReport::new(&meta);
+
+        // no polynomials and fewer advice columns, for a 64 row table
+        assert_eq!(lookup.num_polynomials(), 0);
+        assert_eq!(lookup.lookups.len(), 1);
+        assert_eq!(lookup.advice_columns, 3);
+        assert_eq!(lookup.fixed_columns, 3);
+
+        assert_eq!(gate.num_polynomials(), 4);
+        assert!(gate.lookups.is_empty());
+        assert_eq!(gate.advice_columns, 5);
+        assert_eq!(gate.fixed_columns, 0);
+    }
+}
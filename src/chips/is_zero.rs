@@ -7,15 +7,37 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+use super::range_check::RangeCheckChip;
+use crate::{dev::labeled, testing::mutation_hook, witness::WitnessPlan};
+
 #[derive(Clone, Debug)]
 pub struct IsZeroConfig<F: FieldExt> {
     value: Column<Advice>,
     value_inverse: Column<Advice>,
     result: Column<Advice>,
     selector: Selector,
+    // set by `configure_nonzero`
+    nonzero_selector: Option<Selector>,
     _marker: PhantomData<F>,
 }
 
+/// The polynomials of the is zero gate without a selector, see
+/// `IsZeroChip::configure` for what each of them enforces.
+pub(crate) fn is_zero_constraints<F: FieldExt>(
+    v: Expression<F>,
+    v_inv: Expression<F>,
+    is_zero: Expression<F>,
+) -> [Expression<F>; 3] {
+    let one = Expression::Constant(F::from(1));
+    [
+        is_zero.clone() * (is_zero.clone() - one.clone()), // ensure is_zero is 0 or 1
+        // ensure v_inv is calculated correctly
+        (one.clone() - is_zero.clone()) * (v.clone() * v_inv.clone() - one) // v * v_inv == 1
+            + is_zero.clone() * (v.clone() - v_inv), // v == v_inv == 0
+        v * is_zero, // ensure v is 0 if is_zero
+    ]
+}
+
 #[derive(Clone, Debug)]
 pub struct IsZeroChip<F: FieldExt> {
     is_zero_config: IsZeroConfig<F>,
@@ -41,6 +63,16 @@ impl<F: FieldExt> IsZeroChip<F> {
         }
     }
 
+    /// The gate enforces `is_zero == 1 <=> value == 0`:
+    /// - `is_zero * (is_zero - 1)` restricts `is_zero` to a boolean.
+    /// - `value != 0 => is_zero == 0` comes from `value * is_zero`, which
+    ///   cannot vanish for a nonzero value unless `is_zero` is 0.
+    /// - `value == 0 => is_zero == 1` comes from the `(1 - is_zero) *
+    ///   (value * value_inverse - 1)` term, with `is_zero == 0` it requires
+    ///   `value * value_inverse == 1` which has no solution for a zero value.
+    ///
+    /// The `is_zero * (value - value_inverse)` term only pins the inverse
+    /// witness to 0 in the zero case so the row is fully determined.
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         value: Column<Advice>,
@@ -48,21 +80,27 @@ impl<F: FieldExt> IsZeroChip<F> {
         result: Column<Advice>,
     ) -> <IsZeroChip<F> as Chip<F>>::Config {
         let selector = meta.selector();
+        Self::configure_with_selector(meta, value, value_inverse, result, selector)
+    }
 
+    /// `configure` with a selector owned by the caller, so several is zero
+    /// gates that always fire together can share one selector.
+    pub fn configure_with_selector(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        value_inverse: Column<Advice>,
+        result: Column<Advice>,
+        selector: Selector,
+    ) -> <IsZeroChip<F> as Chip<F>>::Config {
         meta.create_gate("is zero gate", |meta| {
             let s = meta.query_selector(selector);
             let v = meta.query_advice(value, Rotation::cur());
             let v_inv = meta.query_advice(value_inverse, Rotation::cur());
             let is_zero = meta.query_advice(result, Rotation::cur());
-            let one = Expression::Constant(F::from(1));
-            vec![
-                s.clone() * is_zero.clone() * (is_zero.clone() - one.clone()), // ensure is_zero is 0 or 1
-                // ensure v_inv is calculated correctly
-                s.clone()
-                    * ((one.clone() - is_zero.clone()) * (v.clone() * v_inv.clone() - one) // v * v_inv == 1
-                        + is_zero.clone() * (v.clone() - v_inv)), // v == v_inv == 0
-                s * v * is_zero, // ensure v is 0 if is_zero
-            ]
+            is_zero_constraints(v, v_inv, is_zero)
+                .into_iter()
+                .map(|constraint| s.clone() * constraint)
+                .collect::<Vec<_>>()
         });
 
         IsZeroConfig {
@@ -70,13 +108,70 @@ impl<F: FieldExt> IsZeroChip<F> {
             value_inverse,
             result,
             selector,
+            nonzero_selector: None,
             _marker: PhantomData,
         }
     }
+
+    /// Adds a second gate on its own selector to `config`, `value *
+    /// value_inverse - 1` alone, for `assert_nonzero`. Opt-in so configs
+    /// that never assert nonzero don't pay for the selector.
+    pub fn configure_nonzero(
+        meta: &mut ConstraintSystem<F>,
+        config: IsZeroConfig<F>,
+    ) -> <IsZeroChip<F> as Chip<F>>::Config {
+        let nonzero_selector = meta.selector();
+        meta.create_gate("is nonzero gate", |meta| {
+            let s = meta.query_selector(nonzero_selector);
+            let v = meta.query_advice(config.value, Rotation::cur());
+            let v_inv = meta.query_advice(config.value_inverse, Rotation::cur());
+            vec![s * (v * v_inv - Expression::Constant(F::one()))]
+        });
+
+        IsZeroConfig {
+            nonzero_selector: Some(nonzero_selector),
+            ..config
+        }
+    }
 }
 
+/// A value with its inverse witness, as loaded by `load_value` or
+/// `load_cell`. Clones refer to the same cells, so checking a value more
+/// than once with `is_zero` of a clone loads the inverse once and all the
+/// results agree.
+#[derive(Clone, Debug)]
 pub struct ValueIZ<F: FieldExt>(AssignedCell<F, F>, AssignedCell<F, F>);
 
+fn is_zero_result<F: FieldExt>(value: &ValueIZ<F>) -> Value<F> {
+    let mul = value.0.value().copied() * value.1.value();
+    Value::known(F::from(1)) - mul
+}
+
+/// The witnesses of one is zero check, see `IsZeroWitness`.
+#[derive(Clone, Copy, Debug)]
+pub struct IsZeroRow<F: FieldExt> {
+    pub inverse: Value<F>,
+    pub is_zero: Value<F>,
+}
+
+/// Computes the inverse and the result of `load_cell` and `is_zero` for
+/// many values at once, for `load_cell_precomputed` and
+/// `is_zero_precomputed`.
+pub struct IsZeroWitness<F: FieldExt>(PhantomData<F>);
+
+impl<F: FieldExt> WitnessPlan for IsZeroWitness<F> {
+    type Input = Value<F>;
+    type Row = IsZeroRow<F>;
+
+    fn row(value: &Value<F>) -> IsZeroRow<F> {
+        let inverse = value.map(|v| v.invert().unwrap_or(F::zero()));
+        IsZeroRow {
+            inverse,
+            is_zero: Value::known(F::one()) - *value * inverse,
+        }
+    }
+}
+
 impl<F: FieldExt> IsZeroChip<F> {
     pub fn load_value(
         &self,
@@ -96,7 +191,55 @@ impl<F: FieldExt> IsZeroChip<F> {
                     || "value inverse",
                     config.value,
                     0,
-                    || value.map(|v| v.invert().unwrap_or(F::zero())),
+                    || {
+                        mutation_hook(
+                            "IsZeroChip inverse",
+                            value.map(|v| v.invert().unwrap_or(F::zero())),
+                        )
+                    },
+                )
+            },
+        )?;
+        Ok(ValueIZ::<F>(value_cell, value_inverse_cell))
+    }
+
+    // same as load_value but for a value that is already assigned elsewhere in the circuit
+    pub fn load_cell(
+        &self,
+        layouter: impl Layouter<F>,
+        value_cell: AssignedCell<F, F>,
+    ) -> Result<ValueIZ<F>, Error> {
+        let inverse = value_cell.value().map(|v| v.invert().unwrap_or(F::zero()));
+        self.assign_inverse(layouter, value_cell, inverse)
+    }
+
+    /// `load_cell` with the inverse of `row`, computed by `IsZeroWitness`
+    /// for the value of `value_cell`.
+    pub fn load_cell_precomputed(
+        &self,
+        layouter: impl Layouter<F>,
+        value_cell: AssignedCell<F, F>,
+        row: &IsZeroRow<F>,
+    ) -> Result<ValueIZ<F>, Error> {
+        self.assign_inverse(layouter, value_cell, row.inverse)
+    }
+
+    fn assign_inverse(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value_cell: AssignedCell<F, F>,
+        inverse: Value<F>,
+    ) -> Result<ValueIZ<F>, Error> {
+        let config = self.config();
+
+        let value_inverse_cell = layouter.assign_region(
+            || "load private",
+            |mut region| {
+                region.assign_advice(
+                    || "value inverse",
+                    config.value_inverse,
+                    0,
+                    || mutation_hook("IsZeroChip inverse", inverse),
                 )
             },
         )?;
@@ -104,13 +247,64 @@ impl<F: FieldExt> IsZeroChip<F> {
     }
 
     pub fn is_zero(
+        &self,
+        layouter: impl Layouter<F>,
+        value: ValueIZ<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let result = is_zero_result(&value);
+        self.assign_is_zero(layouter, &value, result, "region".to_string())
+    }
+
+    /// `is_zero` with the result of `row`, see `load_cell_precomputed`.
+    pub fn is_zero_precomputed(
+        &self,
+        layouter: impl Layouter<F>,
+        value: ValueIZ<F>,
+        row: &IsZeroRow<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_is_zero(layouter, &value, row.is_zero, "region".to_string())
+    }
+
+    /// `is_zero` of a value that has to be an N bit integer. `is_zero` is
+    /// about field zero: the field modulus, or any multiple of it, is zero
+    /// in the field. Code treating the value as an N bit word may instead
+    /// mean "zero in N bits", where `2^N` would also count as zero. Both
+    /// agree once the value is range checked to `[0, 2^N)`, so the value is
+    /// checked with `range_check` first and a wider one fails the circuit
+    /// rather than getting either answer.
+    pub fn is_zero_nbits<const N: usize>(
         &self,
         mut layouter: impl Layouter<F>,
+        range_check: &RangeCheckChip<F, N>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        range_check.range_check(layouter.namespace(|| "range check"), value.clone())?;
+        let value = self.load_cell(layouter.namespace(|| "load cell"), value)?;
+        self.is_zero(layouter.namespace(|| "is zero"), value)
+    }
+
+    /// `is_zero` with `label` in the region name, so `dev::explain_failures`
+    /// can tell which call a failure comes from.
+    pub fn is_zero_labeled(
+        &self,
+        layouter: impl Layouter<F>,
         value: ValueIZ<F>,
+        label: &str,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let result = is_zero_result(&value);
+        self.assign_is_zero(layouter, &value, result, labeled("is zero", label))
+    }
+
+    fn assign_is_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &ValueIZ<F>,
+        result: Value<F>,
+        region_name: String,
     ) -> Result<AssignedCell<F, F>, Error> {
         let config = self.config();
         layouter.assign_region(
-            || "region",
+            || region_name.clone(),
             |mut region| {
                 config.selector.enable(&mut region, 0)?;
                 value
@@ -123,11 +317,45 @@ impl<F: FieldExt> IsZeroChip<F> {
                     0,
                 )?;
 
-                let mul = value.0.value().copied() * value.1.value();
-
-                let result = Value::known(F::from(1)) - mul;
+                region.assign_advice(
+                    || "result",
+                    config.result,
+                    0,
+                    || mutation_hook("IsZeroChip result", result),
+                )
+            },
+        )
+    }
 
-                region.assign_advice(|| "result", config.result, 0, || result)
+    /// Constrains `value * inverse == 1`, which no inverse satisfies for a
+    /// zero value. Cheaper than `is_zero` and constraining the result, one
+    /// polynomial and no result cell. `value` is copied into the value
+    /// column, which needs equality.
+    ///
+    /// Fails with `Error::Synthesis` unless the config came from
+    /// `configure_nonzero`.
+    pub fn assert_nonzero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+        let nonzero_selector = match config.nonzero_selector {
+            Some(selector) => selector,
+            None => return Err(Error::Synthesis),
+        };
+        layouter.assign_region(
+            || "assert nonzero",
+            |mut region| {
+                nonzero_selector.enable(&mut region, 0)?;
+                value.copy_advice(|| "copy value", &mut region, config.value, 0)?;
+                region.assign_advice(
+                    || "value inverse",
+                    config.value_inverse,
+                    0,
+                    || value.value().map(|v| v.invert().unwrap_or(F::zero())),
+                )?;
+                Ok(())
             },
         )
     }
@@ -136,29 +364,327 @@ impl<F: FieldExt> IsZeroChip<F> {
 #[cfg(test)]
 mod tests {
     use halo2_proofs::{
+        arithmetic::Field,
         circuit::SimpleFloorPlanner,
         dev::MockProver,
-        halo2curves::pasta::Fp,
-        plonk::{Circuit, Instance},
+        halo2curves::{group::ff::PrimeField, pasta::Fp},
+        plonk::{Any, Circuit, Instance},
     };
 
     use super::*;
+    use crate::{
+        chips::range_check::RangeCheckConfig,
+        dev::{record, AssignmentEvent},
+        testing::{HarnessChip, TestCircuit, TestHarness, TestRng},
+    };
 
     const K: u32 = 4;
 
-    #[derive(Default)]
-    struct TestCircuit<F: FieldExt> {
-        number: Value<F>,
+    struct IsZeroTest;
+
+    impl<F: FieldExt> HarnessChip<F> for IsZeroTest {
+        type Config = IsZeroConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let value_inverse = meta.advice_column();
+            let result = meta.advice_column();
+
+            meta.enable_equality(value);
+            meta.enable_equality(value_inverse);
+            meta.enable_equality(result);
+
+            IsZeroChip::<F>::configure(meta, value, value_inverse, result)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = IsZeroChip::<F>::construct(config);
+            let number = witnesses[0].value().copied();
+            let value = chip.load_value(layouter.namespace(|| "load value"), number)?;
+            let result_cell = chip.is_zero(layouter.namespace(|| "is zero"), value)?;
+            Ok(vec![result_cell])
+        }
+    }
+
+    #[test]
+    fn test_circuit_0_pass() {
+        // Number is 0, hence is_zero should be true or 1.
+        TestHarness::auto().expect_pass(IsZeroTest, vec![Fp::from(0)], vec![Fp::from(1)]);
+    }
+
+    #[test]
+    fn test_circuit_0_fail() {
+        // Number is 0, hence is_zero should be true or 1. But is_zero = 0 should fail.
+        TestHarness::auto().expect_fail(IsZeroTest, vec![Fp::from(0)], vec![Fp::from(0)]);
+    }
+
+    #[test]
+    fn test_circuit_123_pass() {
+        // Number is 123, hence is_zero should be false or 0.
+        TestHarness::auto().expect_pass(IsZeroTest, vec![Fp::from(9)], vec![Fp::from(0)]);
+    }
+
+    #[test]
+    fn test_circuit_123_fail() {
+        // Number is 123, hence is_zero should be false or 0. But is_zero = 1 should fail.
+        TestHarness::auto().expect_fail(IsZeroTest, vec![Fp::from(123)], vec![Fp::from(1)]);
+    }
+
+    // checks one loaded value twice
+    struct CachedTest;
+
+    impl<F: FieldExt> HarnessChip<F> for CachedTest {
+        type Config = IsZeroConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            <IsZeroTest as HarnessChip<F>>::configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = IsZeroChip::<F>::construct(config);
+            let value = chip.load_cell(layouter.namespace(|| "load cell"), witnesses[0].clone())?;
+            let first = chip.is_zero(layouter.namespace(|| "first check"), value.clone())?;
+            let second = chip.is_zero(layouter.namespace(|| "second check"), value)?;
+            Ok(vec![first, second])
+        }
+    }
+
+    #[test]
+    fn test_cached() {
+        let harness = TestHarness::auto();
+        for value in [0, 9] {
+            let is_zero = Fp::from(value == 0);
+            let value = vec![Fp::from(value)];
+            harness.expect_pass(CachedTest, value.clone(), vec![is_zero, is_zero]);
+            harness.expect_fail(
+                CachedTest,
+                value.clone(),
+                vec![is_zero, Fp::one() - is_zero],
+            );
+            harness.expect_fail(CachedTest, value, vec![Fp::one() - is_zero; 2]);
+        }
+
+        // the inverse is assigned once, both checks copy the witness and
+        // that inverse
+        let circuit = TestCircuit::<Fp, CachedTest>::new(vec![Fp::from(9)]);
+        let trace = record(K, &circuit).unwrap();
+        let inverses: Vec<_> = trace
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                AssignmentEvent::Advice {
+                    region: Some(region),
+                    column,
+                    row,
+                    ..
+                } if region == "load private" => Some((Column::<Any>::from(*column), *row)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(inverses.len(), 1);
+        let copied: Vec<_> = trace
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                AssignmentEvent::Copy { left, .. } => Some(*left),
+                _ => None,
+            })
+            .collect();
+        // the witness, the inverse and the two results to the instance
+        assert_eq!(copied.len(), 6);
+        assert_eq!(copied[0], copied[2]);
+        assert_eq!(copied[1], inverses[0]);
+        assert_eq!(copied[3], inverses[0]);
+    }
+
+    // checks every witness, with the witnesses computed up front if PLAN
+    struct ManyTest<const PLAN: bool>;
+
+    impl<F: FieldExt, const PLAN: bool> HarnessChip<F> for ManyTest<PLAN> {
+        type Config = IsZeroConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            <IsZeroTest as HarnessChip<F>>::configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = IsZeroChip::<F>::construct(config);
+            if !PLAN {
+                return witnesses
+                    .into_iter()
+                    .map(|cell| {
+                        let value = chip.load_cell(layouter.namespace(|| "load cell"), cell)?;
+                        chip.is_zero(layouter.namespace(|| "is zero"), value)
+                    })
+                    .collect();
+            }
+
+            let values: Vec<_> = witnesses.iter().map(|cell| cell.value().copied()).collect();
+            let rows = IsZeroWitness::compute(&values);
+            witnesses
+                .into_iter()
+                .zip(&rows)
+                .map(|(cell, row)| {
+                    let value =
+                        chip.load_cell_precomputed(layouter.namespace(|| "load cell"), cell, row)?;
+                    chip.is_zero_precomputed(layouter.namespace(|| "is zero"), value, row)
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_precomputed() {
+        let mut rng = TestRng::new(0x91a5);
+        let values: Vec<_> = (0..16).map(|_| Fp::from(rng.below(3))).collect();
+        let results: Vec<_> = values.iter().map(|v| Fp::from(*v == Fp::zero())).collect();
+
+        let harness = TestHarness::auto();
+        harness.expect_pass(ManyTest::<true>, values.clone(), results.clone());
+        let mut wrong = results;
+        wrong[3] = Fp::one() - wrong[3];
+        harness.expect_fail(ManyTest::<true>, values.clone(), wrong);
+
+        // the same assignments in the same order, only computed elsewhere
+        let computed = record(6, &TestCircuit::<Fp, ManyTest<false>>::new(values.clone())).unwrap();
+        let planned = record(6, &TestCircuit::<Fp, ManyTest<true>>::new(values)).unwrap();
+        assert!(computed.diff(&planned).is_empty());
+    }
+
+    struct AssertNonzeroTest<const CONFIGURED: bool>;
+
+    impl<F: FieldExt, const CONFIGURED: bool> HarnessChip<F> for AssertNonzeroTest<CONFIGURED> {
+        type Config = IsZeroConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let config = <IsZeroTest as HarnessChip<F>>::configure(meta);
+            if CONFIGURED {
+                IsZeroChip::configure_nonzero(meta, config)
+            } else {
+                config
+            }
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            IsZeroChip::construct(config).assert_nonzero(
+                layouter.namespace(|| "assert nonzero"),
+                witnesses[0].clone(),
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_assert_nonzero() {
+        let harness = TestHarness::auto();
+        for value in [Fp::one(), Fp::from(9), -Fp::one()] {
+            harness.expect_pass(AssertNonzeroTest::<true>, vec![value], vec![]);
+        }
+        harness.expect_fail(AssertNonzeroTest::<true>, vec![Fp::zero()], vec![]);
+
+        // without `configure_nonzero` there is no gate to enable
+        let result = harness.try_run(AssertNonzeroTest::<false>, vec![Fp::one()], vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+
+    #[test]
+    fn test_random_values() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0x15_2e80);
+        for _ in 0..16 {
+            // small values so zero comes up often
+            let value = rng.below(4);
+            let is_zero = Fp::from(value == 0);
+            harness.expect_pass(IsZeroTest, vec![Fp::from(value)], vec![is_zero]);
+            harness.expect_fail(IsZeroTest, vec![Fp::from(value)], vec![Fp::one() - is_zero]);
+        }
+    }
+
+    struct NbitsTest;
+
+    impl<F: FieldExt> HarnessChip<F> for NbitsTest {
+        type Config = (IsZeroConfig<F>, RangeCheckConfig<F, 8>);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            meta.enable_equality(acc);
+            (
+                <IsZeroTest as HarnessChip<F>>::configure(meta),
+                RangeCheckChip::configure(meta, bit, acc),
+            )
+        }
+
+        fn synthesize(
+            (is_zero_config, range_check_config): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let result = IsZeroChip::construct(is_zero_config).is_zero_nbits(
+                layouter.namespace(|| "is zero nbits"),
+                &RangeCheckChip::construct(range_check_config),
+                witnesses[0].clone(),
+            )?;
+            Ok(vec![result])
+        }
+    }
+
+    #[test]
+    fn test_is_zero_nbits() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(NbitsTest, vec![Fp::zero()], vec![Fp::one()]);
+        harness.expect_pass(NbitsTest, vec![Fp::from(255)], vec![Fp::zero()]);
+
+        // the modulus is field zero and so also zero in 8 bits
+        let modulus = Fp::from_str_vartime(
+            "28948022309329048855892746252171976963363056481941560715954676764349967630337",
+        )
+        .unwrap();
+        assert_eq!(modulus, Fp::zero());
+        harness.expect_pass(NbitsTest, vec![modulus], vec![Fp::one()]);
+
+        // 2^8 is zero in 8 bits but not in the field, and p - 1 is neither,
+        // no answer is right for a value out of range
+        for value in [Fp::from(256), -Fp::one()] {
+            for result in [Fp::zero(), Fp::one()] {
+                harness.expect_fail(NbitsTest, vec![value], vec![result]);
+            }
+        }
     }
 
     #[derive(Clone, Debug)]
-    struct TestCircuitConfig<F: FieldExt> {
+    struct ForgedCircuitConfig<F: FieldExt> {
         is_zero_config: IsZeroConfig<F>,
         instance: Column<Instance>,
     }
 
-    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
-        type Config = TestCircuitConfig<F>;
+    // Assigns the is zero region directly so that every witness can be forged,
+    // bypassing the honest computation in `IsZeroChip::is_zero`.
+    #[derive(Default)]
+    struct ForgedCircuit<F: FieldExt> {
+        value: Value<F>,
+        value_inverse: Value<F>,
+        result: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for ForgedCircuit<F> {
+        type Config = ForgedCircuitConfig<F>;
 
         type FloorPlanner = SimpleFloorPlanner;
 
@@ -167,18 +693,11 @@ mod tests {
         }
 
         fn configure(meta: &mut halo2_proofs::plonk::ConstraintSystem<F>) -> Self::Config {
-            let value = meta.advice_column();
-            let value_inverse = meta.advice_column();
-            let result = meta.advice_column();
             let instance = meta.instance_column();
-
-            meta.enable_equality(value);
-            meta.enable_equality(value_inverse);
-            meta.enable_equality(result);
             meta.enable_equality(instance);
 
-            TestCircuitConfig::<F> {
-                is_zero_config: IsZeroChip::<F>::configure(meta, value, value_inverse, result),
+            ForgedCircuitConfig {
+                is_zero_config: <IsZeroTest as HarnessChip<F>>::configure(meta),
                 instance,
             }
         }
@@ -188,9 +707,21 @@ mod tests {
             config: Self::Config,
             mut layouter: impl halo2_proofs::circuit::Layouter<F>,
         ) -> Result<(), halo2_proofs::plonk::Error> {
-            let chip = IsZeroChip::<F>::construct(config.is_zero_config);
-            let value = chip.load_value(layouter.namespace(|| "load value"), self.number)?;
-            let result_cell = chip.is_zero(layouter.namespace(|| "load value"), value)?;
+            let is_zero_config = config.is_zero_config;
+            let result_cell = layouter.assign_region(
+                || "forged region",
+                |mut region| {
+                    is_zero_config.selector.enable(&mut region, 0)?;
+                    region.assign_advice(|| "value", is_zero_config.value, 0, || self.value)?;
+                    region.assign_advice(
+                        || "value inverse",
+                        is_zero_config.value_inverse,
+                        0,
+                        || self.value_inverse,
+                    )?;
+                    region.assign_advice(|| "result", is_zero_config.result, 0, || self.result)
+                },
+            )?;
 
             layouter.constrain_instance(result_cell.cell(), config.instance, 0)?;
 
@@ -198,67 +729,162 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_circuit_0_pass() {
-        // Number is 0, hence is_zero should be true or 1.
-        let prover = MockProver::run(
+    fn run_forged(value: Fp, value_inverse: Fp, result: Fp) -> MockProver<Fp> {
+        MockProver::run(
             K,
-            &TestCircuit::<Fp> {
-                number: Value::known(Fp::from(0)), // private input number
+            &ForgedCircuit::<Fp> {
+                value: Value::known(value),
+                value_inverse: Value::known(value_inverse),
+                result: Value::known(result),
             },
-            vec![vec![Fp::from(1)]], // public input is_zero
+            vec![vec![result]],
         )
-        .unwrap();
+        .unwrap()
+    }
 
-        // Should success.
-        assert_eq!(prover.verify(), Ok(()));
+    #[test]
+    fn test_forged_honest_rows_pass() {
+        // Sanity check that the forged circuit accepts correct witnesses.
+        let nine = Fp::from(9);
+        assert_eq!(
+            run_forged(nine, nine.invert().unwrap(), Fp::zero()).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            run_forged(Fp::zero(), Fp::zero(), Fp::one()).verify(),
+            Ok(())
+        );
     }
 
     #[test]
-    fn test_circuit_0_fail() {
-        // Number is 0, hence is_zero should be true or 1. But is_zero = 0 should fail.
-        let prover = MockProver::run(
-            K,
-            &TestCircuit::<Fp> {
-                number: Value::known(Fp::from(0)), // private input number
-            },
-            vec![vec![Fp::from(0)]], // public input is_zero
-        )
-        .unwrap();
+    fn test_forged_nonzero_claimed_zero_fail() {
+        // Nonzero value with is_zero = 1 is rejected by `value * is_zero`,
+        // whatever the inverse witness is.
+        let nine = Fp::from(9);
+        for value_inverse in [Fp::zero(), nine, nine.invert().unwrap()] {
+            assert!(run_forged(nine, value_inverse, Fp::one()).verify().is_err());
+        }
+    }
 
-        // Should fail since is_zero should be true or 1 but it is passed as 0.
-        assert!(prover.verify().is_err());
+    #[test]
+    fn test_forged_zero_claimed_nonzero_fail() {
+        // Zero value with is_zero = 0 needs `value * value_inverse == 1`,
+        // which no inverse witness satisfies.
+        for value_inverse in [Fp::zero(), Fp::one(), Fp::from(9)] {
+            assert!(run_forged(Fp::zero(), value_inverse, Fp::zero())
+                .verify()
+                .is_err());
+        }
     }
 
     #[test]
-    fn test_circuit_123_pass() {
-        // Number is 123, hence is_zero should be false or 0.
-        let prover = MockProver::run(
-            K,
-            &TestCircuit::<Fp> {
-                number: Value::known(Fp::from(9)), // private input number
-            },
-            vec![vec![Fp::from(0)]], // public input is_zero
-        )
-        .unwrap();
+    fn test_forged_non_boolean_result_fail() {
+        let nine = Fp::from(9);
+        assert!(run_forged(nine, nine.invert().unwrap(), Fp::from(2))
+            .verify()
+            .is_err());
+    }
+
+    #[derive(Clone, Debug)]
+    struct SharedSelectorConfig<F: FieldExt> {
+        selector: Selector,
+        left: IsZeroConfig<F>,
+        right: IsZeroConfig<F>,
+    }
+
+    // Two is zero gates on one selector, laid out side by side in one row
+    // with forgeable results.
+    #[derive(Default)]
+    struct SharedSelectorCircuit<F: FieldExt> {
+        values: [Value<F>; 2],
+        results: [Value<F>; 2],
+    }
+
+    impl<F: FieldExt> Circuit<F> for SharedSelectorCircuit<F> {
+        type Config = SharedSelectorConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let selector = meta.selector();
+            let mut is_zero = || {
+                let value = meta.advice_column();
+                let value_inverse = meta.advice_column();
+                let result = meta.advice_column();
+                IsZeroChip::configure_with_selector(meta, value, value_inverse, result, selector)
+            };
+            let left = is_zero();
+            let right = is_zero();
+
+            SharedSelectorConfig {
+                selector,
+                left,
+                right,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "shared selector",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    for ((is_zero_config, value), result) in [&config.left, &config.right]
+                        .into_iter()
+                        .zip(self.values)
+                        .zip(self.results)
+                    {
+                        region.assign_advice(|| "value", is_zero_config.value, 0, || value)?;
+                        region.assign_advice(
+                            || "value inverse",
+                            is_zero_config.value_inverse,
+                            0,
+                            || value.map(|v| v.invert().unwrap_or(F::zero())),
+                        )?;
+                        region.assign_advice(|| "result", is_zero_config.result, 0, || result)?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
 
-        // Should success.
-        assert_eq!(prover.verify(), Ok(()));
+    fn run_shared(values: [u64; 2], results: [u64; 2]) -> MockProver<Fp> {
+        let circuit = SharedSelectorCircuit::<Fp> {
+            values: values.map(|v| Value::known(Fp::from(v))),
+            results: results.map(|v| Value::known(Fp::from(v))),
+        };
+        MockProver::run(K, &circuit, vec![]).unwrap()
     }
 
     #[test]
-    fn test_circuit_123_fail() {
-        // Number is 123, hence is_zero should be false or 0. But is_zero = 1 should fail.
-        let prover = MockProver::run(
-            K,
-            &TestCircuit::<Fp> {
-                number: Value::known(Fp::from(123)), // private input number
-            },
-            vec![vec![Fp::from(1)]], // public input is_zero
-        )
-        .unwrap();
+    fn test_shared_selector() {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        SharedSelectorCircuit::<Fp>::configure(&mut cs);
+        assert_eq!(cs.num_selectors(), 1);
+
+        assert_eq!(run_shared([0, 9], [1, 0]).verify(), Ok(()));
+        // enabling the one selector checks both gates, a wrong result on
+        // either side fails
+        assert!(run_shared([0, 9], [0, 0]).verify().is_err());
+        assert!(run_shared([0, 9], [1, 1]).verify().is_err());
+    }
 
-        // Should fail since is_zero should be false or 0 but it is passed as 1.
-        assert!(prover.verify().is_err());
+    #[test]
+    fn test_mutations() {
+        // the inverse of 0 is pinned to 0, so both cases catch every mutation
+        let harness = TestHarness::auto();
+        for (value, is_zero) in [(9, 0), (0, 1)] {
+            harness
+                .mutation_test(IsZeroTest, vec![Fp::from(value)], vec![Fp::from(is_zero)])
+                .assert_all_caught();
+        }
     }
 }
@@ -0,0 +1,209 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+
+#[derive(Clone, Debug)]
+pub struct IsLtEqConfig<F: FieldExt, const BITS: usize> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    diff: Column<Advice>,
+    is_lte: Column<Advice>,
+    selector: Selector,
+    range_check: RangeCheckConfig<F, BITS>,
+}
+
+/// Returns a boolean `is_lte = a <= b` for BITS-bit `a` and `b`.
+///
+/// The gate ties `diff = b - a + (1 - is_lte) * 2^BITS` and `diff` is range
+/// checked to BITS bits. With `is_lte = 1` that needs `b - a` in `[0, 2^BITS)`
+/// and with `is_lte = 0` it needs `b - a` in `[-2^BITS, 0)`. The two ranges
+/// are disjoint so only the honest `is_lte` can be witnessed. The inputs
+/// must already be known to be BITS-bit.
+#[derive(Clone, Debug)]
+pub struct IsLtEqChip<F: FieldExt, const BITS: usize> {
+    config: IsLtEqConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for IsLtEqChip<F, BITS> {
+    type Config = IsLtEqConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> IsLtEqChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        diff: Column<Advice>,
+        is_lte: Column<Advice>,
+        range_check: RangeCheckConfig<F, BITS>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 64, "operands must be at most 64 bits");
+        let selector = meta.selector();
+
+        meta.create_gate("is lte", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+            let is_lte = meta.query_advice(is_lte, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            let base = Expression::Constant(F::from_u128(1 << BITS));
+            vec![
+                s.clone() * is_lte.clone() * (one.clone() - is_lte.clone()), // is_lte is 0 or 1
+                s * (diff - (b - a) - (one - is_lte) * base),
+            ]
+        });
+
+        IsLtEqConfig {
+            a,
+            b,
+            diff,
+            is_lte,
+            selector,
+            range_check,
+        }
+    }
+
+    pub fn is_lte(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let (diff, is_lte) = layouter.assign_region(
+            || "is lte",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "copy a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "copy b", &mut region, config.b, 0)?;
+
+                let values = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| (a.get_lower_128(), b.get_lower_128()));
+                // wraps for operands wider than BITS, the range check on
+                // diff then fails
+                let diff = values.map(|(a, b)| {
+                    if a <= b {
+                        F::from_u128(b - a)
+                    } else {
+                        F::from_u128(b.wrapping_add(1 << BITS).wrapping_sub(a))
+                    }
+                });
+                let is_lte = values.map(|(a, b)| F::from(a <= b));
+
+                let diff = region.assign_advice(|| "diff", config.diff, 0, || diff)?;
+                let is_lte = region.assign_advice(|| "is lte", config.is_lte, 0, || is_lte)?;
+                Ok((diff, is_lte))
+            },
+        )?;
+
+        RangeCheckChip::construct(config.range_check.clone())
+            .range_check(layouter.namespace(|| "range check diff"), diff)?;
+
+        Ok(is_lte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        dev::without_witness_checks,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    struct IsLtEqTest;
+
+    impl<F: FieldExt> HarnessChip<F> for IsLtEqTest {
+        type Config = IsLtEqConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let diff = meta.advice_column();
+            let is_lte = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+
+            for column in [a, b, diff, is_lte, acc] {
+                meta.enable_equality(column);
+            }
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            IsLtEqChip::configure(meta, a, b, diff, is_lte, range_check)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = IsLtEqChip::construct(config);
+            let is_lte = chip.is_lte(
+                layouter.namespace(|| "is lte"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![is_lte])
+        }
+    }
+
+    fn expect(a: u64, b: u64, is_lte: bool) {
+        TestHarness::auto().expect_pass(
+            IsLtEqTest,
+            vec![Fp::from(a), Fp::from(b)],
+            vec![Fp::from(is_lte)],
+        );
+    }
+
+    #[test]
+    fn test_is_lte() {
+        expect(5, 5, true);
+        expect(4, 5, true);
+        expect(6, 5, false);
+        expect(0, 15, true);
+        expect(15, 0, false);
+    }
+
+    #[test]
+    fn test_is_lte_wrong_result_fail() {
+        let harness = TestHarness::auto();
+        harness.expect_fail(IsLtEqTest, vec![Fp::from(4), Fp::from(5)], vec![Fp::zero()]);
+        harness.expect_fail(IsLtEqTest, vec![Fp::from(6), Fp::from(5)], vec![Fp::one()]);
+    }
+
+    #[test]
+    fn test_wide_operand_fail() {
+        // b + 2^BITS - a underflows for a = p - 1, the diff then fails the
+        // range check rather than the witness computation panicking
+        let harness = TestHarness::auto();
+        without_witness_checks(|| {
+            for is_lte in [Fp::zero(), Fp::one()] {
+                harness.expect_fail(IsLtEqTest, vec![-Fp::one(), Fp::from(5)], vec![is_lte]);
+            }
+        });
+    }
+}
@@ -0,0 +1,201 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::expr::linear_combination;
+
+#[derive(Clone, Debug)]
+pub struct LinearCombinationConfig<F: FieldExt, const N: usize> {
+    values: [Column<Advice>; N],
+    result: Column<Advice>,
+    coeffs: [F; N],
+    selector: Selector,
+}
+
+/// Computes `coeff_0 * value_0 + ... + coeff_{N-1} * value_{N-1}` in a single
+/// row, where the coefficients are fixed when the chip is configured.
+#[derive(Clone, Debug)]
+pub struct LinearCombinationChip<F: FieldExt, const N: usize> {
+    config: LinearCombinationConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for LinearCombinationChip<F, N> {
+    type Config = LinearCombinationConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> LinearCombinationChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        values: [Column<Advice>; N],
+        result: Column<Advice>,
+        coeffs: [F; N],
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("linear combination", |meta| {
+            let s = meta.query_selector(selector);
+            let terms: Vec<_> = values
+                .iter()
+                .zip(coeffs)
+                .map(|(column, coeff)| (meta.query_advice(*column, Rotation::cur()), coeff))
+                .collect();
+            let sum = linear_combination(&terms);
+            let result = meta.query_advice(result, Rotation::cur());
+            vec![s * (sum - result)]
+        });
+
+        LinearCombinationConfig {
+            values,
+            result,
+            coeffs,
+            selector,
+        }
+    }
+
+    pub fn combine(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>; N],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "linear combination",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                let mut result = Value::known(F::zero());
+                for ((value, column), coeff) in values
+                    .iter()
+                    .zip(config.values.iter())
+                    .zip(config.coeffs.iter())
+                {
+                    value.copy_advice(|| "copy value", &mut region, *column, 0)?;
+                    result = result + value.value().map(|v| *v * coeff);
+                }
+
+                region.assign_advice(|| "result", config.result, 0, || result)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+
+    const K: u32 = 4;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        values: [Value<F>; 3],
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        linear_combination_config: LinearCombinationConfig<F, 3>,
+        instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let values = [(); 3].map(|_| meta.advice_column());
+            let result = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            for column in values {
+                meta.enable_equality(column);
+            }
+            meta.enable_equality(result);
+            meta.enable_equality(instance);
+
+            TestCircuitConfig {
+                advice,
+                linear_combination_config: LinearCombinationChip::configure(
+                    meta,
+                    values,
+                    result,
+                    [F::from(2), F::from(3), F::from(5)],
+                ),
+                instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = LinearCombinationChip::construct(config.linear_combination_config);
+
+            let values = layouter.assign_region(
+                || "load values",
+                |mut region| {
+                    let a = region.assign_advice(|| "a", config.advice, 0, || self.values[0])?;
+                    let b = region.assign_advice(|| "b", config.advice, 1, || self.values[1])?;
+                    let c = region.assign_advice(|| "c", config.advice, 2, || self.values[2])?;
+                    Ok([a, b, c])
+                },
+            )?;
+
+            let result = chip.combine(layouter.namespace(|| "2a + 3b + 5c"), &values)?;
+            layouter.constrain_instance(result.cell(), config.instance, 0)
+        }
+    }
+
+    fn circuit(a: u64, b: u64, c: u64) -> TestCircuit<Fp> {
+        TestCircuit {
+            values: [a, b, c].map(|v| Value::known(Fp::from(v))),
+        }
+    }
+
+    #[test]
+    fn test_combine_pass() {
+        // 2 * 4 + 3 * 5 + 5 * 6 = 53
+        let prover = MockProver::run(K, &circuit(4, 5, 6), vec![vec![Fp::from(53)]]).unwrap();
+
+        // Should success.
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_combine_fail() {
+        let prover = MockProver::run(K, &circuit(4, 5, 6), vec![vec![Fp::from(54)]]).unwrap();
+
+        // Should fail since the result is 53.
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::is_zero::{IsZeroChip, IsZeroConfig};
+
+mod table;
+pub use table::Log2TableConfig;
+
+// floor(log2(value)) of a value below 2^128, 0 for 0, which the table
+// rejects whatever the witness
+fn floor_log2<F: FieldExt>(value: &F) -> F {
+    F::from(value.get_lower_128().checked_ilog2().unwrap_or(0) as u64)
+}
+
+#[derive(Clone, Debug)]
+pub struct Log2Config<F: FieldExt, const BITS: usize> {
+    value: Column<Advice>,
+    log2: Column<Advice>,
+    is_zero: Column<Advice>,
+    bit_length: Column<Advice>,
+    q_log2: Selector,
+    q_bit_length: Selector,
+    table: Log2TableConfig<F, BITS>,
+    is_zero_config: IsZeroConfig<F>,
+}
+
+/// `floor(log2(value))` of a value in `1..2^BITS` by a lookup into the
+/// `2^BITS - 1` row table of `(value, floor(log2(value)))` pairs, and the
+/// bit length of a value in `0..2^BITS` on top of it.
+///
+/// 0 is deliberately not in the table, it has no logarithm, so
+/// `floor_log2` of 0 fails to verify, as does any value of BITS bits or
+/// more. `bit_length` handles 0 with an `IsZeroChip`: with `z = (value ==
+/// 0)` it looks up `value + z`, which is 1 for 0, and a gate selects
+/// `(1 - z) * (log2 + 1)`:
+///
+/// | value | log2 | is_zero | bit_length              | q_log2 | q_bit_length |
+/// |-------|------|---------|-------------------------|--------|--------------|
+/// | v     | l    |         |                         | 1      | 0            |
+/// | v     | l    | z       | (1 - z) * (l + 1)       | 0      | 1            |
+///
+/// Disabled rows look up `(1, 0)`, which is in the table.
+#[derive(Clone, Debug)]
+pub struct Log2Chip<F: FieldExt, const BITS: usize> {
+    config: Log2Config<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for Log2Chip<F, BITS> {
+    type Config = Log2Config<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> Log2Chip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `value`, `is_zero` and `value_inverse` are also the `IsZeroChip`'s.
+    /// Every column needs equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        log2: Column<Advice>,
+        is_zero: Column<Advice>,
+        value_inverse: Column<Advice>,
+        bit_length: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 24, "the log2 table has 2^BITS rows");
+        let q_log2 = meta.complex_selector();
+        let q_bit_length = meta.complex_selector();
+        let table = Log2TableConfig::configure(meta);
+
+        meta.lookup("log2", |meta| {
+            let q_log2 = meta.query_selector(q_log2);
+            let q_bit_length = meta.query_selector(q_bit_length);
+            let value = meta.query_advice(value, Rotation::cur());
+            let log2 = meta.query_advice(log2, Rotation::cur());
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+            let one = Expression::Constant(F::one());
+
+            let not_q = one - q_log2.clone() - q_bit_length.clone();
+            let input = q_log2.clone() * value.clone() + q_bit_length.clone() * (value + is_zero);
+            vec![
+                (input + not_q, table.value),
+                ((q_log2 + q_bit_length) * log2, table.log2),
+            ]
+        });
+
+        meta.create_gate("bit length", |meta| {
+            let q = meta.query_selector(q_bit_length);
+            let log2 = meta.query_advice(log2, Rotation::cur());
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+            let bit_length = meta.query_advice(bit_length, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![q * (bit_length - (one.clone() - is_zero) * (log2 + one))]
+        });
+
+        Log2Config {
+            value,
+            log2,
+            is_zero,
+            bit_length,
+            q_log2,
+            q_bit_length,
+            table,
+            is_zero_config: IsZeroChip::configure(meta, value, value_inverse, is_zero),
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config().table.load(layouter)
+    }
+
+    /// `floor(log2(value))`, fails to verify unless `value` is in
+    /// `1..2^BITS`.
+    pub fn floor_log2(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "floor log2",
+            |mut region| {
+                config.q_log2.enable(&mut region, 0)?;
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+                let log2 = value.value().map(floor_log2);
+                region.assign_advice(|| "log2", config.log2, 0, || log2)
+            },
+        )
+    }
+
+    /// The number of bits `value` needs, `floor(log2(value)) + 1` and 0 for
+    /// 0. Fails to verify unless `value` is in `0..2^BITS`.
+    pub fn bit_length(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let is_zero_chip = IsZeroChip::construct(config.is_zero_config.clone());
+        let loaded = is_zero_chip.load_cell(layouter.namespace(|| "load cell"), value.clone())?;
+        let is_zero = is_zero_chip.is_zero(layouter.namespace(|| "is zero"), loaded)?;
+
+        layouter.assign_region(
+            || "bit length",
+            |mut region| {
+                config.q_bit_length.enable(&mut region, 0)?;
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+                is_zero.copy_advice(|| "is zero", &mut region, config.is_zero, 0)?;
+
+                let adjusted = value.value().copied() + is_zero.value().copied();
+                let log2 = adjusted.map(|adjusted| floor_log2(&adjusted));
+                region.assign_advice(|| "log2", config.log2, 0, || log2)?;
+
+                let bit_length = is_zero
+                    .value()
+                    .zip(log2)
+                    .map(|(is_zero, log2)| (F::one() - *is_zero) * (log2 + F::one()));
+                region.assign_advice(|| "bit length", config.bit_length, 0, || bit_length)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    const BITS: usize = 4;
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Log2Config<F, BITS> {
+        let [value, log2, is_zero, value_inverse, bit_length] =
+            [(); 5].map(|_| meta.advice_column());
+        for column in [value, log2, is_zero, value_inverse, bit_length] {
+            meta.enable_equality(column);
+        }
+        Log2Chip::configure(meta, value, log2, is_zero, value_inverse, bit_length)
+    }
+
+    struct FloorLog2Test;
+
+    impl<F: FieldExt> HarnessChip<F> for FloorLog2Test {
+        type Config = Log2Config<F, BITS>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = Log2Chip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "log2 table"))?;
+            let log2 =
+                chip.floor_log2(layouter.namespace(|| "floor log2"), witnesses[0].clone())?;
+            Ok(vec![log2])
+        }
+    }
+
+    struct BitLengthTest;
+
+    impl<F: FieldExt> HarnessChip<F> for BitLengthTest {
+        type Config = Log2Config<F, BITS>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = Log2Chip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "log2 table"))?;
+            let bit_length =
+                chip.bit_length(layouter.namespace(|| "bit length"), witnesses[0].clone())?;
+            Ok(vec![bit_length])
+        }
+    }
+
+    #[test]
+    fn test_powers_of_two() {
+        let harness = TestHarness::auto();
+        for i in 0..BITS as u64 {
+            let value = vec![Fp::from(1 << i)];
+            harness.expect_pass(FloorLog2Test, value.clone(), vec![Fp::from(i)]);
+            harness.expect_pass(BitLengthTest, value, vec![Fp::from(i + 1)]);
+        }
+    }
+
+    #[test]
+    fn test_below_powers_of_two() {
+        let harness = TestHarness::auto();
+        for i in 2..=BITS as u64 {
+            let value = vec![Fp::from((1 << i) - 1)];
+            harness.expect_pass(FloorLog2Test, value.clone(), vec![Fp::from(i - 1)]);
+            harness.expect_pass(BitLengthTest, value.clone(), vec![Fp::from(i)]);
+            harness.expect_fail(FloorLog2Test, value.clone(), vec![Fp::from(i)]);
+            harness.expect_fail(BitLengthTest, value, vec![Fp::from(i + 1)]);
+        }
+    }
+
+    #[test]
+    fn test_largest_value() {
+        // 2^BITS - 1 is the last row of the table, 2^BITS isn't in it
+        let harness = TestHarness::auto();
+        let largest = vec![Fp::from((1 << BITS) - 1)];
+        harness.expect_pass(
+            FloorLog2Test,
+            largest.clone(),
+            vec![Fp::from(BITS as u64 - 1)],
+        );
+        harness.expect_pass(BitLengthTest, largest, vec![Fp::from(BITS as u64)]);
+
+        let too_wide = vec![Fp::from(1 << BITS)];
+        harness.expect_fail(FloorLog2Test, too_wide.clone(), vec![Fp::from(BITS as u64)]);
+        harness.expect_fail(BitLengthTest, too_wide, vec![Fp::from(BITS as u64 + 1)]);
+    }
+
+    #[test]
+    fn test_zero() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(BitLengthTest, vec![Fp::zero()], vec![Fp::zero()]);
+        harness.expect_fail(BitLengthTest, vec![Fp::zero()], vec![Fp::one()]);
+
+        // 0 isn't in the table, whatever the claimed logarithm
+        for log2 in [Fp::zero(), -Fp::one()] {
+            harness.expect_fail(FloorLog2Test, vec![Fp::zero()], vec![log2]);
+        }
+    }
+}
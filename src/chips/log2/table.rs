@@ -0,0 +1,58 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{ConstraintSystem, Error, TableColumn},
+};
+
+// Table size is 2**BITS - 1
+// 0 has no logarithm and is left out, so looking it up fails
+
+#[derive(Debug, Clone)]
+pub struct Log2TableConfig<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    pub value: TableColumn,
+    pub log2: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> Log2TableConfig<F, BITS> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let value = meta.lookup_table_column();
+        let log2 = meta.lookup_table_column();
+
+        Self {
+            value,
+            log2,
+            _marker: PhantomData,
+        }
+    }
+
+    // fill floor(log2(value)) for every value in 1..2**BITS
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load log2 table",
+            |mut table| {
+                for (offset, value) in (1..(1u64 << BITS)).enumerate() {
+                    table.assign_cell(
+                        || "value",
+                        self.value,
+                        offset,
+                        || Value::known(F::from(value)),
+                    )?;
+                    table.assign_cell(
+                        || "log2",
+                        self.log2,
+                        offset,
+                        || Value::known(F::from(value.ilog2() as u64)),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
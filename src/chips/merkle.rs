@@ -0,0 +1,155 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::Error,
+};
+
+use super::hash::HashGadget;
+
+/// The root of a binary Merkle tree over `2^DEPTH` leaves. Each of the
+/// DEPTH layers hashes adjacent pairs of the layer below with `hash_two`,
+/// `2^DEPTH - 1` hashes in total. The tree has no configuration of its own,
+/// all the constraints are the hash's.
+#[derive(Clone, Debug)]
+pub struct MerkleTreeRootChip<F: FieldExt, H: HashGadget<F>, const DEPTH: usize> {
+    hash: H,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, H: HashGadget<F>, const DEPTH: usize> MerkleTreeRootChip<F, H, DEPTH> {
+    pub fn construct(hash: H) -> Self {
+        Self {
+            hash,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Fails with `Error::Synthesis` unless there are exactly `2^DEPTH`
+    /// leaves.
+    pub fn compute_root(
+        &self,
+        mut layouter: impl Layouter<F>,
+        leaves: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if leaves.len() != 1 << DEPTH {
+            return Err(Error::Synthesis);
+        }
+
+        let mut layer = leaves.to_vec();
+        for depth in (0..DEPTH).rev() {
+            layer = layer
+                .chunks(2)
+                .map(|pair| {
+                    self.hash.hash_two(
+                        layouter.namespace(|| format!("layer {depth}")),
+                        pair[0].clone(),
+                        pair[1].clone(),
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+        }
+        Ok(layer.remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{halo2curves::pasta::Fp, plonk::ConstraintSystem};
+
+    use super::*;
+    use crate::{
+        chips::hash::{DummyHashChip, DummyHashConfig},
+        testing::{HarnessChip, TestHarness, TestRng},
+    };
+
+    // the witnesses are the leaves
+    struct MerkleTest<const DEPTH: usize>;
+
+    impl<F: FieldExt, const DEPTH: usize> HarnessChip<F> for MerkleTest<DEPTH> {
+        type Config = DummyHashConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let [left, right, out] = [(); 3].map(|_| meta.advice_column());
+            for column in [left, right, out] {
+                meta.enable_equality(column);
+            }
+            DummyHashChip::configure(meta, left, right, out)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let tree =
+                MerkleTreeRootChip::<_, _, DEPTH>::construct(DummyHashChip::construct(config));
+            let root = tree.compute_root(layouter.namespace(|| "root"), &witnesses)?;
+            Ok(vec![root])
+        }
+    }
+
+    fn host_root(leaves: &[Fp]) -> Fp {
+        match leaves {
+            [leaf] => *leaf,
+            _ => {
+                let (left, right) = leaves.split_at(leaves.len() / 2);
+                DummyHashChip::hash(host_root(left), host_root(right))
+            }
+        }
+    }
+
+    fn leaves(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_four_leaves() {
+        let leaves = leaves(&[1, 2, 3, 4]);
+        // h(h(1, 2), h(3, 4)) with h(l, r) = (l + 7)^2 + r
+        let root = DummyHashChip::hash(Fp::from(66), Fp::from(104));
+        assert_eq!(root, Fp::from(5433));
+        assert_eq!(host_root(&leaves), root);
+
+        TestHarness::auto().expect_pass(MerkleTest::<2>, leaves, vec![root]);
+    }
+
+    #[test]
+    fn test_tampered_leaf() {
+        let harness = TestHarness::auto();
+        let leaves = leaves(&[1, 2, 3, 4]);
+        let root = host_root(&leaves);
+        for i in 0..4 {
+            let mut tampered = leaves.clone();
+            tampered[i] += Fp::one();
+            assert_ne!(host_root(&tampered), root);
+            harness.expect_fail(MerkleTest::<2>, tampered, vec![root]);
+        }
+        // the same leaves in another order
+        harness.expect_fail(
+            MerkleTest::<2>,
+            vec![leaves[1], leaves[0], leaves[2], leaves[3]],
+            vec![root],
+        );
+    }
+
+    #[test]
+    fn test_matches_host() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0x3e7);
+        for _ in 0..4 {
+            let leaves: Vec<_> = (0..8).map(|_| Fp::from(rng.next_u64())).collect();
+            harness.expect_pass(MerkleTest::<3>, leaves.clone(), vec![host_root(&leaves)]);
+        }
+        // a single leaf is its own root
+        harness.expect_pass(MerkleTest::<0>, vec![Fp::from(9)], vec![Fp::from(9)]);
+    }
+
+    #[test]
+    fn test_wrong_leaf_count() {
+        let result =
+            TestHarness::auto().try_run(MerkleTest::<2>, leaves(&[1, 2, 3]), vec![Fp::one()]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
@@ -0,0 +1,411 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Extremum {
+    Max,
+    Min,
+}
+
+#[derive(Clone, Debug)]
+pub struct MinMaxConfig<F: FieldExt, const BITS: usize> {
+    value: Column<Advice>,
+    flag: Column<Advice>,
+    diff: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_max: Selector,
+    q_min: Selector,
+    range_check: RangeCheckConfig<F, BITS>,
+}
+
+/// Maximum and minimum of BITS-bit values, over a pair or accumulated over a
+/// slice in one region:
+///
+/// | value | flag | diff | acc                            | q_first | q_max |
+/// |-------|------|------|--------------------------------|---------|-------|
+/// | v_0   |      |      | v_0                            | 1       | 0     |
+/// | v_1   | f_1  | d_1  | acc_0 + f_1 * (v_1 - acc_0)    | 0       | 1     |
+/// | v_2   | f_2  | d_2  | acc_1 + f_2 * (v_2 - acc_1)    | 0       | 1     |
+///
+/// For the maximum `f_i = acc_{i-1} < v_i`, through the comparator row
+/// `d_i = v_i - acc_{i-1} - 1 + (1 - f_i) * 2^BITS` with `d_i` range checked to
+/// BITS bits. The minimum uses `q_min` and `f_i = v_i < acc_{i-1}`, with the
+/// operands of the difference swapped. The inputs must already be known to be
+/// BITS-bit.
+#[derive(Clone, Debug)]
+pub struct MinMaxChip<F: FieldExt, const BITS: usize> {
+    config: MinMaxConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for MinMaxChip<F, BITS> {
+    type Config = MinMaxConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> MinMaxChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        flag: Column<Advice>,
+        diff: Column<Advice>,
+        acc: Column<Advice>,
+        range_check: RangeCheckConfig<F, BITS>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 64, "operands must be at most 64 bits");
+        let q_first = meta.selector();
+        let q_max = meta.selector();
+        let q_min = meta.selector();
+
+        meta.create_gate("min max first", |meta| {
+            let q = meta.query_selector(q_first);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - value)]
+        });
+
+        meta.create_gate("min max step", |meta| {
+            let q_max = meta.query_selector(q_max);
+            let q_min = meta.query_selector(q_min);
+            let value = meta.query_advice(value, Rotation::cur());
+            let flag = meta.query_advice(flag, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let one = Expression::Constant(F::one());
+            let base = Expression::Constant(F::from_u128(1 << BITS));
+            let wrap = (one.clone() - flag.clone()) * base;
+            let q = q_max.clone() + q_min.clone();
+            vec![
+                q.clone() * flag.clone() * (one.clone() - flag.clone()),
+                q_max
+                    * (diff.clone()
+                        - (value.clone() - acc_prev.clone() - one.clone())
+                        - wrap.clone()),
+                q_min * (diff - (acc_prev.clone() - value.clone() - one) - wrap),
+                q * (acc - acc_prev.clone() - flag * (value - acc_prev)),
+            ]
+        });
+
+        MinMaxConfig {
+            value,
+            flag,
+            diff,
+            acc,
+            q_first,
+            q_max,
+            q_min,
+            range_check,
+        }
+    }
+
+    pub fn max(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        Ok(self.running_max(layouter, &[a, b])?.pop().unwrap())
+    }
+
+    pub fn min(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        Ok(self.running_min(layouter, &[a, b])?.pop().unwrap())
+    }
+
+    /// Returns the maximum of every prefix of `cells`, which must not be
+    /// empty, the accumulators of the rows. The last one is the maximum of
+    /// all of them.
+    pub fn running_max(
+        &self,
+        layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.running(layouter, cells, Extremum::Max)
+    }
+
+    /// Returns the minimum of every prefix of `cells`, which must not be
+    /// empty.
+    pub fn running_min(
+        &self,
+        layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.running(layouter, cells, Extremum::Min)
+    }
+
+    fn running(
+        &self,
+        layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+        extremum: Extremum,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(!cells.is_empty(), "no cells to accumulate");
+        let mut accs = vec![cells[0].value().copied()];
+        for cell in &cells[1..] {
+            let acc = accs.last().unwrap().zip(cell.value()).map(|(acc, value)| {
+                let keep = match extremum {
+                    Extremum::Max => acc.get_lower_128() >= value.get_lower_128(),
+                    Extremum::Min => acc.get_lower_128() <= value.get_lower_128(),
+                };
+                if keep {
+                    acc
+                } else {
+                    *value
+                }
+            });
+            accs.push(acc);
+        }
+        self.assign_running(layouter, cells, extremum, accs)
+    }
+
+    // lays out the rows with the given accumulator witnesses, flags and
+    // differences are derived from them
+    fn assign_running(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+        extremum: Extremum,
+        accs: Vec<Value<F>>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let config = self.config();
+
+        let (acc_cells, diffs) = layouter.assign_region(
+            || "running min max",
+            |mut region| {
+                config.q_first.enable(&mut region, 0)?;
+                cells[0].copy_advice(|| "value", &mut region, config.value, 0)?;
+                let mut acc = region.assign_advice(|| "acc", config.acc, 0, || accs[0])?;
+
+                let mut acc_cells = vec![acc.clone()];
+                let mut diffs = vec![];
+                for (offset, cell) in cells.iter().enumerate().skip(1) {
+                    match extremum {
+                        Extremum::Max => config.q_max.enable(&mut region, offset)?,
+                        Extremum::Min => config.q_min.enable(&mut region, offset)?,
+                    }
+                    cell.copy_advice(|| "value", &mut region, config.value, offset)?;
+
+                    let values = acc
+                        .value()
+                        .zip(cell.value())
+                        .map(|(acc, value)| match extremum {
+                            Extremum::Max => (acc.get_lower_128(), value.get_lower_128()),
+                            Extremum::Min => (value.get_lower_128(), acc.get_lower_128()),
+                        });
+                    // lo < hi through diff = hi - lo - 1, wrapped when it isn't
+                    let flag = values.map(|(lo, hi)| lo < hi);
+                    let diff = values.zip(flag).map(|((lo, hi), flag)| {
+                        let wrap = if flag { 0 } else { 1 << BITS };
+                        F::from_u128(hi + wrap - lo - 1)
+                    });
+
+                    region.assign_advice(|| "flag", config.flag, offset, || flag.map(F::from))?;
+                    diffs.push(region.assign_advice(|| "diff", config.diff, offset, || diff)?);
+                    acc = region.assign_advice(|| "acc", config.acc, offset, || accs[offset])?;
+                    acc_cells.push(acc.clone());
+                }
+                Ok((acc_cells, diffs))
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::construct(config.range_check.clone());
+        for (i, diff) in diffs.into_iter().enumerate() {
+            range_check_chip
+                .range_check(layouter.namespace(|| format!("range check {i}")), diff)?;
+        }
+
+        Ok(acc_cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness, TestRng};
+
+    fn configure<F: FieldExt, const BITS: usize>(
+        meta: &mut ConstraintSystem<F>,
+    ) -> MinMaxConfig<F, BITS> {
+        let value = meta.advice_column();
+        let flag = meta.advice_column();
+        let diff = meta.advice_column();
+        let acc = meta.advice_column();
+        let bit = meta.advice_column();
+        let range_acc = meta.advice_column();
+
+        for column in [value, diff, acc, range_acc] {
+            meta.enable_equality(column);
+        }
+
+        let range_check = RangeCheckChip::configure(meta, bit, range_acc);
+        MinMaxChip::configure(meta, value, flag, diff, acc, range_check)
+    }
+
+    // the outputs are the extremum of every prefix
+    struct RunningTest<const MAX: bool, const BITS: usize>;
+
+    impl<F: FieldExt, const MAX: bool, const BITS: usize> HarnessChip<F> for RunningTest<MAX, BITS> {
+        type Config = MinMaxConfig<F, BITS>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = MinMaxChip::construct(config);
+            if MAX {
+                chip.running_max(layouter.namespace(|| "running max"), &witnesses)
+            } else {
+                chip.running_min(layouter.namespace(|| "running min"), &witnesses)
+            }
+        }
+    }
+
+    // running max whose last accumulator is replaced by the witness after the
+    // values
+    struct TamperedTest;
+
+    impl<F: FieldExt> HarnessChip<F> for TamperedTest {
+        type Config = MinMaxConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (tampered, cells) = witnesses.split_last().unwrap();
+            let mut accs = vec![];
+            let mut acc = 0;
+            for cell in cells {
+                cell.value().map(|v| acc = acc.max(v.get_lower_128()));
+                accs.push(Value::known(F::from_u128(acc)));
+            }
+            *accs.last_mut().unwrap() = tampered.value().copied();
+
+            let accs = MinMaxChip::construct(config).assign_running(
+                layouter.namespace(|| "tampered running max"),
+                cells,
+                Extremum::Max,
+                accs,
+            )?;
+            Ok(vec![accs.last().unwrap().clone()])
+        }
+    }
+
+    fn values(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    fn prefix_extrema(input: &[u64], extremum: fn(u64, u64) -> u64) -> Vec<Fp> {
+        let prefixes: Vec<u64> = input
+            .iter()
+            .scan(input[0], |acc, v| {
+                *acc = extremum(*acc, *v);
+                Some(*acc)
+            })
+            .collect();
+        values(&prefixes)
+    }
+
+    fn expect_extrema(input: &[u64]) {
+        let harness = TestHarness::auto();
+        let max = prefix_extrema(input, u64::max);
+        let min = prefix_extrema(input, u64::min);
+        harness.expect_pass(RunningTest::<true, 4>, values(input), max);
+        harness.expect_pass(RunningTest::<false, 4>, values(input), min);
+    }
+
+    #[test]
+    fn test_running_random() {
+        let mut rng = TestRng::new(0xb1d);
+        for n in [2, 5, 8] {
+            let input: Vec<u64> = (0..n).map(|_| rng.below(16)).collect();
+            expect_extrema(&input);
+        }
+    }
+
+    #[test]
+    fn test_running_edge_cases() {
+        expect_extrema(&[9]);
+        expect_extrema(&[6, 6, 6, 6]);
+        // the accumulator stays at the first element
+        expect_extrema(&[15, 11, 7, 3, 0]);
+        expect_extrema(&[0, 3, 7, 11, 15]);
+    }
+
+    #[test]
+    fn test_pairwise() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(RunningTest::<true, 4>, values(&[3, 12]), values(&[3, 12]));
+        harness.expect_pass(RunningTest::<false, 4>, values(&[3, 12]), values(&[3, 3]));
+        harness.expect_fail(RunningTest::<true, 4>, values(&[3, 12]), values(&[3, 3]));
+        harness.expect_fail(RunningTest::<false, 4>, values(&[3, 12]), values(&[3, 12]));
+    }
+
+    #[test]
+    fn test_prefix_maxima() {
+        let harness = TestHarness::auto();
+        let input = values(&[3, 1, 4, 1, 5]);
+        harness.expect_pass(
+            RunningTest::<true, 8>,
+            input.clone(),
+            values(&[3, 3, 4, 4, 5]),
+        );
+        // the running value instead of the maximum so far
+        harness.expect_fail(
+            RunningTest::<true, 8>,
+            input.clone(),
+            values(&[3, 1, 4, 4, 5]),
+        );
+        harness.expect_fail(RunningTest::<true, 8>, input, values(&[3, 3, 4, 4, 4]));
+        // at 8 bits, past the range of 4 bit values
+        harness.expect_pass(
+            RunningTest::<true, 8>,
+            values(&[200, 17, 255, 0]),
+            values(&[200, 200, 255, 255]),
+        );
+    }
+
+    #[test]
+    fn test_tampered_accumulator_fail() {
+        let harness = TestHarness::auto();
+        // the honest accumulator passes through the same layout
+        harness.expect_pass(TamperedTest, values(&[4, 9, 2, 9]), values(&[9]));
+        // a claimed max above every value, or the last value instead
+        harness.expect_fail(TamperedTest, values(&[4, 9, 2, 14]), values(&[14]));
+        harness.expect_fail(TamperedTest, values(&[4, 9, 2, 2]), values(&[2]));
+    }
+}
@@ -0,0 +1,145 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::bit_decomposition::{BitDecompositionChip, BitDecompositionConfig};
+
+#[derive(Clone, Debug)]
+pub struct ParityBitConfig<F: FieldExt, const N: usize> {
+    decomposition: BitDecompositionConfig<F, N>,
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    selector: Selector,
+}
+
+/// Whether an N bit value is even. The value is decomposed into N bits,
+/// which also range checks it, and one more row over the decomposition's
+/// columns turns the least significant bit into `1 - lsb`:
+///
+/// | bit | acc     | selector |
+/// |-----|---------|----------|
+/// | lsb | 1 - lsb | 1        |
+///
+/// The result is boolean because the bit is.
+#[derive(Clone, Debug)]
+pub struct ParityBitChip<F: FieldExt, const N: usize> {
+    config: ParityBitConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for ParityBitChip<F, N> {
+    type Config = ParityBitConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> ParityBitChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// The least significant bit is copied out of the decomposition, `bit`
+    /// and `acc` need equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("parity bit", |meta| {
+            let s = meta.query_selector(selector);
+            let lsb = meta.query_advice(bit, Rotation::cur());
+            let even = meta.query_advice(acc, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![s * (one - lsb - even)]
+        });
+
+        ParityBitConfig {
+            decomposition: BitDecompositionChip::configure(meta, bit, acc),
+            bit,
+            acc,
+            selector,
+        }
+    }
+
+    /// Returns 1 if `value` is even and 0 if it is odd. Fails to verify if
+    /// `value` doesn't fit in N bits.
+    pub fn is_even(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let bits = BitDecompositionChip::construct(config.decomposition.clone())
+            .decompose(layouter.namespace(|| "decompose"), value)?;
+
+        layouter.assign_region(
+            || "parity bit",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                let lsb = bits
+                    .bit(0)
+                    .copy_advice(|| "lsb", &mut region, config.bit, 0)?;
+                let even = lsb.value().map(|lsb| F::one() - lsb);
+                region.assign_advice(|| "is even", config.acc, 0, || even)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    struct ParityTest;
+
+    impl<F: FieldExt> HarnessChip<F> for ParityTest {
+        type Config = ParityBitConfig<F, 8>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            meta.enable_equality(bit);
+            meta.enable_equality(acc);
+            ParityBitChip::configure(meta, bit, acc)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = ParityBitChip::construct(config);
+            let even = chip.is_even(layouter.namespace(|| "is even"), witnesses[0].clone())?;
+            Ok(vec![even])
+        }
+    }
+
+    #[test]
+    fn test_is_even() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(ParityTest, vec![Fp::from(4)], vec![Fp::one()]);
+        harness.expect_pass(ParityTest, vec![Fp::from(7)], vec![Fp::zero()]);
+        harness.expect_pass(ParityTest, vec![Fp::from(255)], vec![Fp::zero()]);
+    }
+
+    #[test]
+    fn test_wrong_parity() {
+        let harness = TestHarness::auto();
+        harness.expect_fail(ParityTest, vec![Fp::from(4)], vec![Fp::zero()]);
+        harness.expect_fail(ParityTest, vec![Fp::from(7)], vec![Fp::one()]);
+    }
+}
@@ -0,0 +1,263 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Challenge, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+/// Host side random linear combination, `v_0 * c^(n-1) + ... + v_(n-1)`.
+pub fn rlc<F: FieldExt>(values: &[F], challenge: F) -> F {
+    values
+        .iter()
+        .fold(F::zero(), |acc, value| acc * challenge + value)
+}
+
+#[derive(Clone, Debug)]
+pub struct PhaseDemoConfig<F: FieldExt> {
+    value: Column<Advice>,
+    acc: Column<Advice>,
+    challenge: Challenge,
+    q_first: Selector,
+    q_step: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Minimal two phase circuit. The values are witnessed in the first phase,
+/// the prover commits to them and only then learns the challenge `c`, which
+/// the second phase accumulator uses:
+///
+/// | value | acc                 | q_first | q_step |
+/// |-------|---------------------|---------|--------|
+/// | v_0   | v_0                 | 1       | 0      |
+/// | v_1   | acc_0 * c + v_1     | 0       | 1      |
+/// | ...   | ...                 | 0       | 1      |
+///
+/// `value` has to be a first phase column and `acc` a second phase column
+/// (`meta.advice_column_in(SecondPhase)`), with the challenge usable after
+/// the first phase (`meta.challenge_usable_after(FirstPhase)`).
+#[derive(Clone, Debug)]
+pub struct PhaseDemoChip<F: FieldExt> {
+    config: PhaseDemoConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for PhaseDemoChip<F> {
+    type Config = PhaseDemoConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> PhaseDemoChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        acc: Column<Advice>,
+        challenge: Challenge,
+    ) -> <Self as Chip<F>>::Config {
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        meta.create_gate("rlc first", |meta| {
+            let q = meta.query_selector(q_first);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - value)]
+        });
+
+        meta.create_gate("rlc step", |meta| {
+            let q = meta.query_selector(q_step);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let c = meta.query_challenge(challenge);
+            vec![q * (acc - (acc_prev * c + value))]
+        });
+
+        PhaseDemoConfig {
+            value,
+            acc,
+            challenge,
+            q_first,
+            q_step,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Witnesses `values` and returns the final accumulator cell. The
+    /// accumulator is unknown until the prover reaches the second phase.
+    pub fn accumulate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!values.is_empty(), "nothing to accumulate");
+        let config = self.config();
+        let challenge = layouter.get_challenge(config.challenge);
+
+        layouter.assign_region(
+            || "rlc",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut acc_cell = None;
+                for (offset, value) in values.iter().enumerate() {
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+
+                    region.assign_advice(|| "value", config.value, offset, || *value)?;
+                    acc = acc * challenge + *value;
+                    acc_cell = Some(region.assign_advice(|| "acc", config.acc, offset, || acc)?);
+                }
+                Ok(acc_cell.unwrap())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, FirstPhase, SecondPhase},
+    };
+
+    use super::*;
+
+    const K: u32 = 4;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        values: Vec<Value<F>>,
+        // (challenge, final accumulator) seen during synthesis
+        seen: Cell<Option<(F, F)>>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = PhaseDemoConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                seen: Cell::new(None),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let acc = meta.advice_column_in(SecondPhase);
+            let challenge = meta.challenge_usable_after(FirstPhase);
+            PhaseDemoChip::configure(meta, value, acc, challenge)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = PhaseDemoChip::construct(config.clone());
+            let challenge = layouter.get_challenge(config.challenge);
+            let acc = chip.accumulate(layouter.namespace(|| "rlc"), &self.values)?;
+
+            challenge
+                .zip(acc.value())
+                .map(|(challenge, acc)| self.seen.set(Some((challenge, *acc))));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_accumulator_matches_host_fold() {
+        let values = [3, 5, 7, 11].map(Fp::from);
+        let circuit = TestCircuit {
+            values: values.iter().copied().map(Value::known).collect(),
+            ..Default::default()
+        };
+
+        let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let (challenge, acc) = circuit.seen.get().expect("second phase was synthesized");
+        assert_eq!(acc, rlc(&values, challenge));
+    }
+
+    #[test]
+    fn test_forged_accumulator_fail() {
+        // Assigns the accumulator of the values with the last one changed,
+        // against the original values column.
+        #[derive(Default)]
+        struct ForgedCircuit<F: FieldExt>(TestCircuit<F>);
+
+        impl<F: FieldExt> Circuit<F> for ForgedCircuit<F> {
+            type Config = PhaseDemoConfig<F>;
+
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self(self.0.without_witnesses())
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                TestCircuit::<F>::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let challenge = layouter.get_challenge(config.challenge);
+                let values = &self.0.values;
+                layouter.assign_region(
+                    || "forged rlc",
+                    |mut region| {
+                        let mut acc = Value::known(F::zero());
+                        for (offset, value) in values.iter().enumerate() {
+                            if offset == 0 {
+                                config.q_first.enable(&mut region, offset)?;
+                            } else {
+                                config.q_step.enable(&mut region, offset)?;
+                            }
+                            region.assign_advice(|| "value", config.value, offset, || *value)?;
+
+                            let forged = if offset == values.len() - 1 {
+                                *value + Value::known(F::one())
+                            } else {
+                                *value
+                            };
+                            acc = acc * challenge + forged;
+                            region.assign_advice(|| "acc", config.acc, offset, || acc)?;
+                        }
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        let circuit = ForgedCircuit(TestCircuit {
+            values: [3, 5, 7, 11].map(|v| Value::known(Fp::from(v))).to_vec(),
+            ..Default::default()
+        });
+        let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,384 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use crate::testing::mutation_hook;
+
+#[derive(Clone, Debug)]
+pub struct PolynomialEvalConfig<F: FieldExt, const DEGREE: usize> {
+    coeff: Column<Advice>,
+    x: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_step: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Evaluates `c_0 + c_1 * x + ... + c_DEGREE * x^DEGREE` with Horner's rule,
+/// one row per coefficient from the highest down:
+///
+/// | coeff     | x | acc                      | q_first | q_step |
+/// |-----------|---|--------------------------|---------|--------|
+/// | c_2       | x | c_2                      | 1       | 0      |
+/// | c_1       | x | acc_0 * x + c_1          | 0       | 1      |
+/// | c_0       | x | acc_1 * x + c_0          | 0       | 1      |
+///
+/// The coefficients and `x` are copied in, the last `acc` is the result.
+/// `x` is copied into every row rather than copied once and carried down by
+/// an `x_cur == x_prev` constraint. The column needs equality for the first
+/// copy anyway, the others only lengthen its permutation cycle, while
+/// carrying it would add a constraint to the step gate.
+///
+/// `evaluate_fixed` evaluates a public polynomial, its coefficients pinned to
+/// constants instead of copied from cells.
+#[derive(Clone, Debug)]
+pub struct PolynomialEvalChip<F: FieldExt, const DEGREE: usize> {
+    config: PolynomialEvalConfig<F, DEGREE>,
+}
+
+impl<F: FieldExt, const DEGREE: usize> Chip<F> for PolynomialEvalChip<F, DEGREE> {
+    type Config = PolynomialEvalConfig<F, DEGREE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const DEGREE: usize> PolynomialEvalChip<F, DEGREE> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        coeff: Column<Advice>,
+        x: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        for column in [coeff, x, acc] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("horner first", |meta| {
+            let q = meta.query_selector(q_first);
+            let coeff = meta.query_advice(coeff, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - coeff)]
+        });
+
+        meta.create_gate("horner step", |meta| {
+            let q = meta.query_selector(q_step);
+            let coeff = meta.query_advice(coeff, Rotation::cur());
+            let x = meta.query_advice(x, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            vec![q * (acc - (acc_prev * x + coeff))]
+        });
+
+        PolynomialEvalConfig {
+            coeff,
+            x,
+            acc,
+            q_first,
+            q_step,
+            _marker: PhantomData,
+        }
+    }
+
+    /// `coefficients[i]` is the coefficient of `x^i`, there must be
+    /// `DEGREE + 1` of them.
+    pub fn evaluate(
+        &self,
+        layouter: impl Layouter<F>,
+        coefficients: &[AssignedCell<F, F>],
+        x: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert_eq!(
+            coefficients.len(),
+            DEGREE + 1,
+            "expected DEGREE + 1 coefficients"
+        );
+        let coeff_column = self.config().coeff;
+        self.assign_horner(layouter, x, |region, offset, i| {
+            coefficients[i].copy_advice(|| "copy coeff", region, coeff_column, offset)
+        })
+    }
+
+    /// Like `evaluate` with the coefficients as constants, the circuit needs
+    /// a column enabled for constants.
+    pub fn evaluate_fixed(
+        &self,
+        layouter: impl Layouter<F>,
+        coefficients: &[F],
+        x: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert_eq!(
+            coefficients.len(),
+            DEGREE + 1,
+            "expected DEGREE + 1 coefficients"
+        );
+        let coeff_column = self.config().coeff;
+        self.assign_horner(layouter, x, |region, offset, i| {
+            region.assign_advice_from_constant(|| "coeff", coeff_column, offset, coefficients[i])
+        })
+    }
+
+    // `assign_coeff(region, offset, i)` assigns the coefficient of `x^i`
+    fn assign_horner(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: AssignedCell<F, F>,
+        assign_coeff: impl Fn(&mut Region<'_, F>, usize, usize) -> Result<AssignedCell<F, F>, Error>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "polynomial eval",
+            |mut region| {
+                let mut acc: Option<AssignedCell<F, F>> = None;
+                for offset in 0..=DEGREE {
+                    let coeff = assign_coeff(&mut region, offset, DEGREE - offset)?;
+                    x.copy_advice(|| "copy x", &mut region, config.x, offset)?;
+
+                    let value = match &acc {
+                        None => {
+                            config.q_first.enable(&mut region, offset)?;
+                            coeff.value().copied()
+                        }
+                        Some(acc) => {
+                            config.q_step.enable(&mut region, offset)?;
+                            acc.value().copied() * x.value() + coeff.value()
+                        }
+                    };
+                    acc = Some(region.assign_advice(
+                        || "acc",
+                        config.acc,
+                        offset,
+                        || mutation_hook("PolynomialEvalChip acc", value),
+                    )?);
+                }
+                Ok(acc.unwrap())
+            },
+        )
+    }
+}
+
+/// One `PolynomialEvalChip` evaluation per point, all over the same
+/// coefficient cells. Each evaluation copies the coefficients into its own
+/// region, so the copies are tied to the caller's cells and a polynomial
+/// can't differ between points.
+#[derive(Clone, Debug)]
+pub struct MultipointEvalChip<F: FieldExt, const DEGREE: usize, const POINTS: usize> {
+    eval_chip: PolynomialEvalChip<F, DEGREE>,
+}
+
+impl<F: FieldExt, const DEGREE: usize, const POINTS: usize> MultipointEvalChip<F, DEGREE, POINTS> {
+    pub fn construct(config: PolynomialEvalConfig<F, DEGREE>) -> Self {
+        Self {
+            eval_chip: PolynomialEvalChip::construct(config),
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        coeff: Column<Advice>,
+        x: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> PolynomialEvalConfig<F, DEGREE> {
+        PolynomialEvalChip::configure(meta, coeff, x, acc)
+    }
+
+    /// Returns the evaluation at each of `evaluation_points`, in order.
+    pub fn evaluate_at_all(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coefficients: &[AssignedCell<F, F>],
+        evaluation_points: [AssignedCell<F, F>; POINTS],
+    ) -> Result<[AssignedCell<F, F>; POINTS], Error> {
+        let evaluations = evaluation_points
+            .into_iter()
+            .map(|x| {
+                self.eval_chip
+                    .evaluate(layouter.namespace(|| "evaluate"), coefficients, x)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(evaluations.try_into().expect("one evaluation per point"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    // witnesses are the 3 coefficients of a quadratic, then the 3 points
+    struct MultipointEvalTest;
+
+    impl<F: FieldExt> HarnessChip<F> for MultipointEvalTest {
+        type Config = PolynomialEvalConfig<F, 2>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let coeff = meta.advice_column();
+            let x = meta.advice_column();
+            let acc = meta.advice_column();
+            MultipointEvalChip::<F, 2, 3>::configure(meta, coeff, x, acc)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (coefficients, points) = witnesses.split_at(3);
+            let evaluations = MultipointEvalChip::<F, 2, 3>::construct(config).evaluate_at_all(
+                layouter.namespace(|| "evaluate at all"),
+                coefficients,
+                points.to_vec().try_into().expect("3 points"),
+            )?;
+            Ok(evaluations.to_vec())
+        }
+    }
+
+    fn witnesses(coefficients: [u64; 3]) -> Vec<Fp> {
+        coefficients
+            .iter()
+            .chain([2, 3, 4].iter())
+            .map(|v| Fp::from(*v))
+            .collect()
+    }
+
+    fn outputs(values: [u64; 3]) -> Vec<Fp> {
+        values.map(Fp::from).to_vec()
+    }
+
+    // witnesses are the DEGREE + 1 coefficients then x, or with FIXED only x
+    struct EvalTest<const DEGREE: usize, const FIXED: bool>;
+
+    // the public polynomial of the FIXED tests
+    fn fixed_coefficients<F: FieldExt>(degree: usize) -> Vec<F> {
+        (0..=degree as u64).map(|i| F::from(3 * i + 1)).collect()
+    }
+
+    impl<F: FieldExt, const DEGREE: usize, const FIXED: bool> HarnessChip<F>
+        for EvalTest<DEGREE, FIXED>
+    {
+        type Config = PolynomialEvalConfig<F, DEGREE>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let [coeff, x, acc] = [(); 3].map(|_| meta.advice_column());
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+            PolynomialEvalChip::configure(meta, coeff, x, acc)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = PolynomialEvalChip::construct(config);
+            let (x, coefficients) = witnesses.split_last().expect("x is the last witness");
+            let layouter = layouter.namespace(|| "evaluate");
+            let eval = if FIXED {
+                chip.evaluate_fixed(layouter, &fixed_coefficients(DEGREE), x.clone())?
+            } else {
+                chip.evaluate(layouter, coefficients, x.clone())?
+            };
+            Ok(vec![eval])
+        }
+    }
+
+    fn host_eval(coefficients: &[Fp], x: Fp) -> Fp {
+        coefficients
+            .iter()
+            .rev()
+            .fold(Fp::zero(), |acc, coeff| acc * x + coeff)
+    }
+
+    fn check_eval<const DEGREE: usize>(coefficients: &[u64], x: u64) {
+        let coefficients: Vec<_> = coefficients.iter().map(|c| Fp::from(*c)).collect();
+        let x = Fp::from(x);
+        let eval = host_eval(&coefficients, x);
+        let witnesses = [coefficients, vec![x]].concat();
+
+        let harness = TestHarness::auto();
+        harness.expect_pass(EvalTest::<DEGREE, false>, witnesses.clone(), vec![eval]);
+        harness.expect_fail(EvalTest::<DEGREE, false>, witnesses, vec![eval + Fp::one()]);
+
+        let eval = host_eval(&fixed_coefficients(DEGREE), x);
+        harness.expect_pass(EvalTest::<DEGREE, true>, vec![x], vec![eval]);
+        harness.expect_fail(EvalTest::<DEGREE, true>, vec![x], vec![eval + Fp::one()]);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        check_eval::<0>(&[7], 5);
+        check_eval::<1>(&[3, 2], 5);
+        check_eval::<1>(&[3, 2], 0);
+        check_eval::<7>(&[1, 0, 5, 9, 0, 2, 8, 4], 3);
+        check_eval::<7>(&[1, 0, 5, 9, 0, 2, 8, 4], 1 << 40);
+    }
+
+    #[test]
+    fn test_tampered_accumulator() {
+        let harness = TestHarness::auto();
+        let witnesses = [1, 0, 5, 9, 0, 2, 8, 4, 3].map(Fp::from).to_vec();
+        let eval = host_eval(&witnesses[..8], witnesses[8]);
+        harness
+            .mutation_test(EvalTest::<7, false>, witnesses, vec![eval])
+            .assert_all_caught();
+        let eval = host_eval(&fixed_coefficients(7), Fp::from(3));
+        harness
+            .mutation_test(EvalTest::<7, true>, vec![Fp::from(3)], vec![eval])
+            .assert_all_caught();
+    }
+
+    #[test]
+    fn test_evaluate_at_all() {
+        // x^2 + 1 at 2, 3, 4
+        let harness = TestHarness::auto();
+        harness.expect_pass(
+            MultipointEvalTest,
+            witnesses([1, 0, 1]),
+            outputs([5, 10, 17]),
+        );
+        harness.expect_fail(
+            MultipointEvalTest,
+            witnesses([1, 0, 1]),
+            outputs([5, 10, 18]),
+        );
+    }
+
+    #[test]
+    fn test_coefficient_change_propagates() {
+        // 2x^2 + 1, changing the shared x^2 coefficient changes every point
+        let harness = TestHarness::auto();
+        harness.expect_pass(
+            MultipointEvalTest,
+            witnesses([1, 0, 2]),
+            outputs([9, 19, 33]),
+        );
+        for stale in [
+            outputs([5, 19, 33]),
+            outputs([9, 10, 33]),
+            outputs([9, 19, 17]),
+        ] {
+            harness.expect_fail(MultipointEvalTest, witnesses([1, 0, 2]), stale);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance},
+};
+
+use super::{
+    hash::HashGadget,
+    sponge::{SpongeChip, SpongeConfig},
+};
+
+#[derive(Clone, Debug)]
+pub struct PublicInputHashConfig<F: FieldExt> {
+    sponge: SpongeConfig<F>,
+    advice: Column<Advice>,
+    commitment: Column<Instance>,
+}
+
+/// Hashes N instance values into one commitment with a `SpongeChip`, and
+/// checks the commitment against a row of its own instance column. A
+/// circuit that hands its public inputs on, to another circuit or to a
+/// contract, then only has to hand on the commitment.
+///
+/// The values are loaded into the sponge's advice column, which already
+/// has equality, so the chip adds no columns apart from the commitment
+/// instance column.
+#[derive(Clone, Debug)]
+pub struct PublicInputHashChip<F: FieldExt, H: HashGadget<F> + Clone, const N: usize> {
+    config: PublicInputHashConfig<F>,
+    hash: H,
+}
+
+impl<F: FieldExt, H: HashGadget<F> + Clone, const N: usize> PublicInputHashChip<F, H, N> {
+    pub fn construct(config: PublicInputHashConfig<F>, hash: H) -> Self {
+        Self { config, hash }
+    }
+
+    /// `advice` and `constants` are the sponge's, see
+    /// `SpongeChip::configure`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        constants: Column<Fixed>,
+        commitment: Column<Instance>,
+    ) -> PublicInputHashConfig<F> {
+        meta.enable_equality(commitment);
+        PublicInputHashConfig {
+            sponge: SpongeChip::<F, H>::configure(meta, advice, constants),
+            advice,
+            commitment,
+        }
+    }
+
+    /// Loads the values at `rows` of `instance`, which needs equality, and
+    /// returns their sponge digest.
+    pub fn load_and_hash(
+        &self,
+        mut layouter: impl Layouter<F>,
+        instance: Column<Instance>,
+        rows: [usize; N],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let advice = self.config.advice;
+        let values = layouter.assign_region(
+            || "load public inputs",
+            |mut region| {
+                rows.iter()
+                    .enumerate()
+                    .map(|(offset, row)| {
+                        region.assign_advice_from_instance(
+                            || "public input",
+                            instance,
+                            *row,
+                            advice,
+                            offset,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        let mut sponge = SpongeChip::construct(self.config.sponge.clone(), self.hash.clone());
+        sponge.absorb(layouter.namespace(|| "absorb"), &values)?;
+        sponge.squeeze(layouter.namespace(|| "squeeze"))
+    }
+
+    /// Constrains `commitment` to the value at `row` of the commitment
+    /// column.
+    pub fn constrain_commitment(
+        &self,
+        mut layouter: impl Layouter<F>,
+        commitment: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(commitment.cell(), self.config.commitment, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner, dev::MockProver, halo2curves::pasta::Fp, plonk::Circuit,
+    };
+
+    use super::*;
+    use crate::chips::hash::{DummyHashChip, DummyHashConfig};
+
+    const K: u32 = 5;
+
+    #[derive(Clone, Debug)]
+    struct TestConfig<F: FieldExt> {
+        values: Column<Instance>,
+        hash_config: PublicInputHashConfig<F>,
+        dummy_hash_config: DummyHashConfig<F>,
+    }
+
+    // hashes the rows 0..3 of the first instance column into row 0 of the
+    // second
+    #[derive(Default)]
+    struct TestCircuit;
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit {
+        type Config = TestConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let values = meta.instance_column();
+            let commitment = meta.instance_column();
+            let advice = meta.advice_column();
+            let constants = meta.fixed_column();
+            let [left, right, out] = [(); 3].map(|_| meta.advice_column());
+
+            meta.enable_equality(values);
+            for column in [left, right, out] {
+                meta.enable_equality(column);
+            }
+
+            TestConfig {
+                values,
+                hash_config: PublicInputHashChip::<F, DummyHashChip<F>, 3>::configure(
+                    meta, advice, constants, commitment,
+                ),
+                dummy_hash_config: DummyHashChip::configure(meta, left, right, out),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = PublicInputHashChip::<_, _, 3>::construct(
+                config.hash_config,
+                DummyHashChip::construct(config.dummy_hash_config),
+            );
+            let commitment =
+                chip.load_and_hash(layouter.namespace(|| "hash"), config.values, [0, 1, 2])?;
+            chip.constrain_commitment(layouter.namespace(|| "commitment"), &commitment, 0)
+        }
+    }
+
+    fn run(values: [u64; 3], commitment: Fp) -> MockProver<Fp> {
+        let values = values.map(Fp::from).to_vec();
+        MockProver::run(K, &TestCircuit, vec![values, vec![commitment]]).unwrap()
+    }
+
+    // the sponge digest of 1, 2, 3 with h(l, r) = (l + 7)^2 + r
+    fn commitment() -> Fp {
+        Fp::from_u128(12_694_344_392_612_835_569_803_802_259)
+    }
+
+    #[test]
+    fn test_known_commitment() {
+        // the values, the pad and the length
+        let expected = [1, 2, 3, 1, 3].into_iter().fold(Fp::zero(), |state, x| {
+            DummyHashChip::hash(state, Fp::from(x))
+        });
+        assert_eq!(expected, commitment());
+
+        assert_eq!(run([1, 2, 3], commitment()).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_tampered_input() {
+        for values in [[1, 2, 4], [0, 2, 3], [2, 1, 3]] {
+            assert!(run(values, commitment()).verify().is_err());
+        }
+        assert!(run([1, 2, 3], commitment() + Fp::one()).verify().is_err());
+    }
+}
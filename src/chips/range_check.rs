@@ -0,0 +1,145 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+use super::bit_decomposition::{BitDecompositionChip, BitDecompositionConfig};
+
+#[derive(Clone, Debug)]
+pub struct RangeCheckConfig<F: FieldExt, const BITS: usize> {
+    decomposition: BitDecompositionConfig<F, BITS>,
+}
+
+/// Checks that a value is in `[0, 2^BITS)` by decomposing it into BITS bits.
+/// Costs BITS rows per check but no lookup table, so it works for any width.
+#[derive(Clone, Debug)]
+pub struct RangeCheckChip<F: FieldExt, const BITS: usize> {
+    config: RangeCheckConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for RangeCheckChip<F, BITS> {
+    type Config = RangeCheckConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> RangeCheckChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        RangeCheckConfig {
+            decomposition: BitDecompositionChip::configure(meta, bit, acc),
+        }
+    }
+
+    pub fn range_check(
+        &self,
+        layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        BitDecompositionChip::construct(self.config().decomposition.clone())
+            .decompose(layouter, value)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::Circuit,
+    };
+
+    use super::*;
+
+    const K: u32 = 5;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        value: Value<F>,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        range_check_config: RangeCheckConfig<F, 8>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(acc);
+
+            TestCircuitConfig {
+                advice,
+                range_check_config: RangeCheckChip::configure(meta, bit, acc),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = RangeCheckChip::construct(config.range_check_config);
+            let value = layouter.assign_region(
+                || "load value",
+                |mut region| region.assign_advice(|| "value", config.advice, 0, || self.value),
+            )?;
+            chip.range_check(layouter.namespace(|| "range check"), value)
+        }
+    }
+
+    fn run(value: Fp) -> MockProver<Fp> {
+        MockProver::run(
+            K,
+            &TestCircuit::<Fp> {
+                value: Value::known(value),
+            },
+            vec![],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_in_range_pass() {
+        for value in [0, 1, 128, 255] {
+            assert_eq!(run(Fp::from(value)).verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_fail() {
+        assert!(run(Fp::from(256)).verify().is_err());
+        // -1 is the largest field element.
+        assert!(run(-Fp::one()).verify().is_err());
+    }
+}
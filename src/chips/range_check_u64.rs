@@ -0,0 +1,163 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    expr::pow2_recompose,
+    range_check::{RangeCheckChip, RangeCheckConfig},
+};
+
+#[derive(Clone, Debug)]
+pub struct RangeCheckU64Config<F: FieldExt> {
+    value: Column<Advice>,
+    hi: Column<Advice>,
+    lo: Column<Advice>,
+    selector: Selector,
+    range_check: RangeCheckConfig<F, 32>,
+}
+
+/// Checks that a value is in `[0, 2^64)` by splitting it as
+/// `value = hi * 2^32 + lo` and range checking both limbs to 32 bits with a
+/// `RangeCheckChip`. Any split of a larger value needs a limb that doesn't
+/// fit, since `hi * 2^32 + lo < 2^64` is far below the modulus.
+#[derive(Clone, Debug)]
+pub struct RangeCheckU64Chip<F: FieldExt> {
+    config: RangeCheckU64Config<F>,
+}
+
+impl<F: FieldExt> Chip<F> for RangeCheckU64Chip<F> {
+    type Config = RangeCheckU64Config<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> RangeCheckU64Chip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `value`, `hi` and `lo` need equality, as does the accumulator of
+    /// `range_check`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        hi: Column<Advice>,
+        lo: Column<Advice>,
+        range_check: RangeCheckConfig<F, 32>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("range check u64", |meta| {
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(value, Rotation::cur());
+            let hi = meta.query_advice(hi, Rotation::cur());
+            let lo = meta.query_advice(lo, Rotation::cur());
+            vec![s * (value - pow2_recompose(&[lo, hi], 32))]
+        });
+
+        RangeCheckU64Config {
+            value,
+            hi,
+            lo,
+            selector,
+            range_check,
+        }
+    }
+
+    pub fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        let (hi, lo) = layouter.assign_region(
+            || "range check u64",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                value.copy_advice(|| "copy value", &mut region, config.value, 0)?;
+
+                // the low 64 bits, which don't recompose a larger value
+                let value = value.value().map(|v| v.get_lower_128() as u64);
+                let hi = value.map(|v| F::from(v >> 32));
+                let lo = value.map(|v| F::from(v & 0xffff_ffff));
+
+                let hi = region.assign_advice(|| "hi", config.hi, 0, || hi)?;
+                let lo = region.assign_advice(|| "lo", config.lo, 0, || lo)?;
+                Ok((hi, lo))
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::construct(config.range_check.clone());
+        range_check_chip.range_check(layouter.namespace(|| "range check hi"), hi)?;
+        range_check_chip.range_check(layouter.namespace(|| "range check lo"), lo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness, TestRng};
+
+    struct RangeCheckU64Test;
+
+    impl<F: FieldExt> HarnessChip<F> for RangeCheckU64Test {
+        type Config = RangeCheckU64Config<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let [value, hi, lo, bit, acc] = [(); 5].map(|_| meta.advice_column());
+            for column in [value, hi, lo, acc] {
+                meta.enable_equality(column);
+            }
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            RangeCheckU64Chip::configure(meta, value, hi, lo, range_check)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            RangeCheckU64Chip::construct(config)
+                .range_check(layouter.namespace(|| "range check"), witnesses[0].clone())?;
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let harness = TestHarness::auto();
+        for value in [0, 1, u32::MAX as u64, 1 << 32, u64::MAX] {
+            harness.expect_pass(RangeCheckU64Test, vec![Fp::from(value)], vec![]);
+        }
+        harness.expect_fail(RangeCheckU64Test, vec![Fp::from_u128(1 << 64)], vec![]);
+        harness.expect_fail(RangeCheckU64Test, vec![-Fp::one()], vec![]);
+    }
+
+    #[test]
+    fn test_random_values() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0x64);
+        for _ in 0..8 {
+            let value = rng.next_u64();
+            harness.expect_pass(RangeCheckU64Test, vec![Fp::from(value)], vec![]);
+
+            // at least 2^64
+            let high = Fp::from(rng.next_u64().max(1)) * Fp::from_u128(1 << 64);
+            harness.expect_fail(RangeCheckU64Test, vec![high + Fp::from(value)], vec![]);
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    bit_decomposition::{BitDecompositionChip, BitDecompositionConfig},
+    select::{SelectChip, SelectConfig},
+};
+
+// NOT CRYPTOGRAPHIC. The "points" are pairs (x, y) standing for x + y*i, and the group law is
+// multiplication of such numbers: (x1, y1) + (x2, y2) = (x1*x2 - y1*y2, x1*y2 + x2*y1) with
+// identity (1, 0). It is only a convenient degree 2 law so the double-and-add control flow
+// can be exercised without a real elliptic curve gadget.
+
+pub type ToyPoint<F> = (F, F);
+
+pub type AssignedToyPoint<F> = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+pub fn toy_identity<F: FieldExt>() -> ToyPoint<F> {
+    (F::one(), F::zero())
+}
+
+pub fn toy_add<F: FieldExt>(p: ToyPoint<F>, q: ToyPoint<F>) -> ToyPoint<F> {
+    (p.0 * q.0 - p.1 * q.1, p.0 * q.1 + q.0 * p.1)
+}
+
+// host side double-and-add, most significant bit first like the chip
+pub fn toy_scalar_mul<F: FieldExt>(scalar: u64, base: ToyPoint<F>) -> ToyPoint<F> {
+    (0..64).rev().fold(toy_identity(), |acc, i| {
+        let doubled = toy_add(acc, acc);
+        if (scalar >> i) & 1 == 1 {
+            toy_add(doubled, base)
+        } else {
+            doubled
+        }
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct ScalarMulDemoConfig<F: FieldExt> {
+    // x1, y1, x2, y2, x3, y3
+    advice: [Column<Advice>; 6],
+    q_add: Selector,
+    decomposition: BitDecompositionConfig<F, 64>,
+    select: SelectConfig<F>,
+}
+
+/// Fixed-base double-and-add over the toy group above, driven by the 64 bit
+/// decomposition of the scalar and choosing between the doubled and the
+/// added point with `SelectChip`.
+#[derive(Clone, Debug)]
+pub struct ScalarMulDemoChip<F: FieldExt> {
+    config: ScalarMulDemoConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for ScalarMulDemoChip<F> {
+    type Config = ScalarMulDemoConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> ScalarMulDemoChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 6],
+        constants: Column<Fixed>,
+        decomposition: BitDecompositionConfig<F, 64>,
+        select: SelectConfig<F>,
+    ) -> <Self as Chip<F>>::Config {
+        let q_add = meta.selector();
+        meta.enable_constant(constants);
+
+        meta.create_gate("toy group add", |meta| {
+            let q = meta.query_selector(q_add);
+            let [x1, y1, x2, y2, x3, y3] = advice.map(|c| meta.query_advice(c, Rotation::cur()));
+            vec![
+                q.clone() * (x3 - (x1.clone() * x2.clone() - y1.clone() * y2.clone())),
+                q * (y3 - (x1 * y2 + x2 * y1)),
+            ]
+        });
+
+        ScalarMulDemoConfig {
+            advice,
+            q_add,
+            decomposition,
+            select,
+        }
+    }
+
+    fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        p: &AssignedToyPoint<F>,
+        assign_q: impl Fn(&mut Region<'_, F>) -> Result<AssignedToyPoint<F>, Error>,
+    ) -> Result<AssignedToyPoint<F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "toy group add",
+            |mut region| {
+                config.q_add.enable(&mut region, 0)?;
+                p.0.copy_advice(|| "x1", &mut region, config.advice[0], 0)?;
+                p.1.copy_advice(|| "y1", &mut region, config.advice[1], 0)?;
+                let q = assign_q(&mut region)?;
+
+                let r =
+                    p.0.value()
+                        .zip(p.1.value())
+                        .zip(q.0.value().zip(q.1.value()))
+                        .map(|((x1, y1), (x2, y2))| toy_add((*x1, *y1), (*x2, *y2)));
+                let x3 = region.assign_advice(|| "x3", config.advice[4], 0, || r.map(|r| r.0))?;
+                let y3 = region.assign_advice(|| "y3", config.advice[5], 0, || r.map(|r| r.1))?;
+                Ok((x3, y3))
+            },
+        )
+    }
+
+    pub fn scalar_mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        scalar_cell: AssignedCell<F, F>,
+        base: ToyPoint<F>,
+    ) -> Result<AssignedToyPoint<F>, Error> {
+        let config = self.config();
+        let decomposition_chip = BitDecompositionChip::construct(config.decomposition.clone());
+        let select_chip = SelectChip::construct(config.select.clone());
+
+        let bits =
+            decomposition_chip.decompose(layouter.namespace(|| "scalar bits"), scalar_cell)?;
+
+        let mut acc = layouter.assign_region(
+            || "identity",
+            |mut region| {
+                let (x, y) = toy_identity::<F>();
+                let x = region.assign_advice_from_constant(|| "x", config.advice[4], 0, x)?;
+                let y = region.assign_advice_from_constant(|| "y", config.advice[5], 0, y)?;
+                Ok((x, y))
+            },
+        )?;
+
+        for (i, bit) in bits.bits().iter().enumerate().rev() {
+            let doubled = self.add(
+                layouter.namespace(|| format!("double {i}")),
+                &acc,
+                |region| {
+                    let x2 = acc.0.copy_advice(|| "x2", region, config.advice[2], 0)?;
+                    let y2 = acc.1.copy_advice(|| "y2", region, config.advice[3], 0)?;
+                    Ok((x2, y2))
+                },
+            )?;
+            let added = self.add(
+                layouter.namespace(|| format!("add {i}")),
+                &doubled,
+                |region| {
+                    let x2 =
+                        region.assign_advice_from_constant(|| "x2", config.advice[2], 0, base.0)?;
+                    let y2 =
+                        region.assign_advice_from_constant(|| "y2", config.advice[3], 0, base.1)?;
+                    Ok((x2, y2))
+                },
+            )?;
+
+            acc = (
+                select_chip.select(
+                    layouter.namespace(|| format!("select x {i}")),
+                    bit.clone(),
+                    added.0,
+                    doubled.0,
+                )?,
+                select_chip.select(
+                    layouter.namespace(|| format!("select y {i}")),
+                    bit.clone(),
+                    added.1,
+                    doubled.1,
+                )?,
+            );
+        }
+
+        Ok(acc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+    use crate::testing::mutation_test;
+
+    const K: u32 = 10;
+
+    const BASE: (u64, u64) = (3, 5);
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        scalar: Value<F>,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        scalar_mul_config: ScalarMulDemoConfig<F>,
+        instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let group_advice = [(); 6].map(|_| meta.advice_column());
+            let select_advice = [(); 4].map(|_| meta.advice_column());
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let constants = meta.fixed_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            for column in group_advice.iter().chain(select_advice.iter()) {
+                meta.enable_equality(*column);
+            }
+            meta.enable_equality(bit);
+            meta.enable_equality(acc);
+            meta.enable_equality(instance);
+
+            let decomposition = BitDecompositionChip::configure(meta, bit, acc);
+            let [cond, a, b, out] = select_advice;
+            let select = SelectChip::configure(meta, cond, a, b, out);
+
+            TestCircuitConfig {
+                advice,
+                scalar_mul_config: ScalarMulDemoChip::configure(
+                    meta,
+                    group_advice,
+                    constants,
+                    decomposition,
+                    select,
+                ),
+                instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = ScalarMulDemoChip::construct(config.scalar_mul_config);
+
+            let scalar = layouter.assign_region(
+                || "load scalar",
+                |mut region| region.assign_advice(|| "scalar", config.advice, 0, || self.scalar),
+            )?;
+
+            let (x, y) = chip.scalar_mul(
+                layouter.namespace(|| "scalar mul"),
+                scalar,
+                (F::from(BASE.0), F::from(BASE.1)),
+            )?;
+
+            layouter.constrain_instance(x.cell(), config.instance, 0)?;
+            layouter.constrain_instance(y.cell(), config.instance, 1)
+        }
+    }
+
+    fn circuit(scalar: u64) -> TestCircuit<Fp> {
+        TestCircuit {
+            scalar: Value::known(Fp::from(scalar)),
+        }
+    }
+
+    fn expected(scalar: u64) -> Vec<Fp> {
+        let expected = toy_scalar_mul(scalar, (Fp::from(BASE.0), Fp::from(BASE.1)));
+        vec![expected.0, expected.1]
+    }
+
+    fn run(scalar: u64, expected_scalar: u64) -> MockProver<Fp> {
+        MockProver::run(K, &circuit(scalar), vec![expected(expected_scalar)]).unwrap()
+    }
+
+    #[test]
+    fn test_scalar_zero() {
+        assert_eq!(
+            toy_scalar_mul(0, (Fp::from(BASE.0), Fp::from(BASE.1))),
+            toy_identity()
+        );
+        assert_eq!(run(0, 0).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_scalar_one() {
+        assert_eq!(
+            toy_scalar_mul(1, (Fp::from(BASE.0), Fp::from(BASE.1))),
+            (Fp::from(BASE.0), Fp::from(BASE.1))
+        );
+        assert_eq!(run(1, 1).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_scalar_random() {
+        let scalar = 0x9e37_79b9_7f4a_7c15;
+        assert_eq!(run(scalar, scalar).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_other_scalar_result_fail() {
+        // The result of the scalar with one control bit flipped must not verify.
+        let scalar = 0x9e37_79b9_7f4a_7c15;
+        assert!(run(scalar, scalar ^ (1 << 17)).verify().is_err());
+    }
+
+    #[test]
+    fn test_scalar_flipped_bit_fail() {
+        // every bit of 0 is 0, so each mutation flips one decomposition bit
+        // and the double-and-add then takes the other branch at that step
+        let report = mutation_test(K, || circuit(0), vec![expected(0)]);
+        let bits = report
+            .mutations
+            .iter()
+            .filter(|mutation| mutation.name == "BitDecompositionChip bit")
+            .count();
+        assert_eq!(bits, 64);
+        report.assert_all_caught();
+    }
+}
@@ -0,0 +1,304 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::cond_swap::SwapOutput;
+use crate::debug_assert_witness;
+
+#[derive(Clone, Debug)]
+pub struct SelectConfig<F: FieldExt> {
+    cond: Column<Advice>,
+    a: Column<Advice>,
+    b: Column<Advice>,
+    out: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Returns `a` if `cond` is 1 and `b` if `cond` is 0. The condition is
+/// constrained to be boolean by the same gate.
+#[derive(Clone, Debug)]
+pub struct SelectChip<F: FieldExt> {
+    config: SelectConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for SelectChip<F> {
+    type Config = SelectConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> SelectChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        cond: Column<Advice>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        out: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("select", |meta| {
+            let s = meta.query_selector(selector);
+            let cond = meta.query_advice(cond, Rotation::cur());
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![
+                s.clone() * cond.clone() * (one - cond.clone()), // cond is 0 or 1
+                s * (out - b.clone() - cond * (a - b)),          // out = cond ? a : b
+            ]
+        });
+
+        SelectConfig {
+            cond,
+            a,
+            b,
+            out,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: AssignedCell<F, F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                cond.copy_advice(|| "copy cond", &mut region, config.cond, 0)?;
+                debug_assert_witness!(
+                    "SelectChip",
+                    "select",
+                    cond.value(),
+                    |cond: &F| *cond == F::zero() || *cond == F::one(),
+                    "boolean",
+                );
+                a.copy_advice(|| "copy a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "copy b", &mut region, config.b, 0)?;
+
+                let out = cond
+                    .value()
+                    .zip(a.value().zip(b.value()))
+                    .map(|(cond, (a, b))| if *cond == F::one() { *a } else { *b });
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+
+    /// Returns `(b, a)` if `cond` is 1 and `(a, b)` if it is 0, as two
+    /// selects on the same condition. Both rows constrain `cond` to be
+    /// boolean and the same cells are assigned whichever way it goes.
+    /// `CondSwapChip::swap` does the same in one row of its own gate.
+    pub fn conditional_swap(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: AssignedCell<F, F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<SwapOutput<F>, Error> {
+        let first = self.select(
+            layouter.namespace(|| "first"),
+            cond.clone(),
+            b.clone(),
+            a.clone(),
+        )?;
+        let second = self.select(layouter.namespace(|| "second"), cond, a, b)?;
+        Ok(SwapOutput::new(first, second))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+    use crate::{
+        dev::without_witness_checks,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    const K: u32 = 4;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        cond: Value<F>,
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        select_config: SelectConfig<F>,
+        instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let cond = meta.advice_column();
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let out = meta.advice_column();
+            let instance = meta.instance_column();
+
+            for column in [advice, cond, a, b, out] {
+                meta.enable_equality(column);
+            }
+            meta.enable_equality(instance);
+
+            TestCircuitConfig {
+                advice,
+                select_config: SelectChip::configure(meta, cond, a, b, out),
+                instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = SelectChip::construct(config.select_config);
+
+            let (cond, a, b) = layouter.assign_region(
+                || "load values",
+                |mut region| {
+                    let cond = region.assign_advice(|| "cond", config.advice, 0, || self.cond)?;
+                    let a = region.assign_advice(|| "a", config.advice, 1, || self.a)?;
+                    let b = region.assign_advice(|| "b", config.advice, 2, || self.b)?;
+                    Ok((cond, a, b))
+                },
+            )?;
+
+            let out = chip.select(layouter.namespace(|| "select"), cond, a, b)?;
+            layouter.constrain_instance(out.cell(), config.instance, 0)
+        }
+    }
+
+    fn run(cond: u64, a: u64, b: u64, out: u64) -> MockProver<Fp> {
+        MockProver::run(
+            K,
+            &TestCircuit::<Fp> {
+                cond: Value::known(Fp::from(cond)),
+                a: Value::known(Fp::from(a)),
+                b: Value::known(Fp::from(b)),
+            },
+            vec![vec![Fp::from(out)]],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_select_pass() {
+        assert_eq!(run(1, 3, 5, 3).verify(), Ok(()));
+        assert_eq!(run(0, 3, 5, 5).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_select_fail() {
+        assert!(run(1, 3, 5, 5).verify().is_err());
+        // A non-boolean condition fails whatever the output is.
+        assert!(without_witness_checks(|| run(2, 3, 5, 5)).verify().is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SelectChip")]
+    fn test_non_boolean_condition_panics() {
+        run(2, 3, 5, 5);
+    }
+
+    // witnesses are cond, a and b
+    struct SwapTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SwapTest {
+        type Config = SelectConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let [cond, a, b, out] = [(); 4].map(|_| meta.advice_column());
+            for column in [cond, a, b, out] {
+                meta.enable_equality(column);
+            }
+            SelectChip::configure(meta, cond, a, b, out)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (first, second) = SelectChip::construct(config)
+                .conditional_swap(
+                    layouter.namespace(|| "swap"),
+                    witnesses[0].clone(),
+                    witnesses[1].clone(),
+                    witnesses[2].clone(),
+                )?
+                .into();
+            Ok(vec![first, second])
+        }
+    }
+
+    fn values(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_conditional_swap() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(SwapTest, values(&[0, 3, 5]), values(&[3, 5]));
+        harness.expect_pass(SwapTest, values(&[1, 3, 5]), values(&[5, 3]));
+        harness.expect_fail(SwapTest, values(&[0, 3, 5]), values(&[5, 3]));
+        harness.expect_fail(SwapTest, values(&[1, 3, 5]), values(&[3, 5]));
+    }
+
+    #[test]
+    fn test_conditional_swap_non_boolean_fail() {
+        // both what the chip assigns for a cond of 2 and what the select
+        // formula gives for it, 3 + 2 * (5 - 3) and 5 + 2 * (3 - 5)
+        without_witness_checks(|| {
+            let harness = TestHarness::auto();
+            for outputs in [values(&[3, 5]), values(&[7, 1])] {
+                harness.expect_fail(SwapTest, values(&[2, 3, 5]), outputs);
+            }
+        });
+    }
+}
@@ -0,0 +1,321 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::is_zero::is_zero_constraints;
+
+#[derive(Clone, Debug)]
+pub struct SetMembershipConfig<F: FieldExt> {
+    set: Vec<F>,
+    value: Column<Advice>,
+    element: Column<Fixed>,
+    product: Column<Advice>,
+    product_inverse: Column<Advice>,
+    is_member: Column<Advice>,
+    q_lookup: Selector,
+    q_first: Selector,
+    q_step: Selector,
+    q_result: Selector,
+    table: TableColumn,
+}
+
+/// Membership of a value in a set fixed at keygen.
+///
+/// `assert_member` looks the value up in a one column table of the set.
+/// Disabled rows look up the first element instead of 0, so 0 is only a
+/// member if it is in the set.
+///
+/// `is_member` can't use the lookup since it also has to prove non
+/// membership. It multiplies `value - s_i` over the set and returns whether
+/// the product is zero with the is zero gate:
+///
+/// | value | element | product              | q_first | q_step | q_result |
+/// |-------|---------|----------------------|---------|--------|----------|
+/// | v     | s_0     | v - s_0              | 1       | 0      | 0        |
+/// | v     | s_1     | product_0 * (v - s_1)| 0       | 1      | 0        |
+/// | v     | s_2     | product_1 * (v - s_2)| 0       | 1      | 1        |
+///
+/// `product_inverse` and `is_member` are only assigned on the last row.
+#[derive(Clone, Debug)]
+pub struct SetMembershipChip<F: FieldExt> {
+    config: SetMembershipConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for SetMembershipChip<F> {
+    type Config = SetMembershipConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> SetMembershipChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        element: Column<Fixed>,
+        product: Column<Advice>,
+        product_inverse: Column<Advice>,
+        is_member: Column<Advice>,
+        set: Vec<F>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(!set.is_empty(), "empty set");
+        let q_lookup = meta.complex_selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let q_result = meta.selector();
+        let table = meta.lookup_table_column();
+
+        let first = set[0];
+        meta.lookup("set membership", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let value = meta.query_advice(value, Rotation::cur());
+            let not_q = Expression::Constant(F::one()) - q.clone();
+            vec![(q * value + not_q * Expression::Constant(first), table)]
+        });
+
+        meta.create_gate("set product first", |meta| {
+            let q = meta.query_selector(q_first);
+            let value = meta.query_advice(value, Rotation::cur());
+            let element = meta.query_fixed(element, Rotation::cur());
+            let product = meta.query_advice(product, Rotation::cur());
+            vec![q * (product - (value - element))]
+        });
+
+        meta.create_gate("set product step", |meta| {
+            let q = meta.query_selector(q_step);
+            let value = meta.query_advice(value, Rotation::cur());
+            let value_prev = meta.query_advice(value, Rotation::prev());
+            let element = meta.query_fixed(element, Rotation::cur());
+            let product = meta.query_advice(product, Rotation::cur());
+            let product_prev = meta.query_advice(product, Rotation::prev());
+            vec![
+                q.clone() * (value.clone() - value_prev),
+                q * (product - product_prev * (value - element)),
+            ]
+        });
+
+        meta.create_gate("set is member", |meta| {
+            let q = meta.query_selector(q_result);
+            let product = meta.query_advice(product, Rotation::cur());
+            let product_inverse = meta.query_advice(product_inverse, Rotation::cur());
+            let is_member = meta.query_advice(is_member, Rotation::cur());
+            is_zero_constraints(product, product_inverse, is_member)
+                .into_iter()
+                .map(|constraint| q.clone() * constraint)
+                .collect::<Vec<_>>()
+        });
+
+        SetMembershipConfig {
+            set,
+            value,
+            element,
+            product,
+            product_inverse,
+            is_member,
+            q_lookup,
+            q_first,
+            q_step,
+            q_result,
+            table,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_table(
+            || "set",
+            |mut table| {
+                for (offset, element) in config.set.iter().enumerate() {
+                    table.assign_cell(
+                        || "element",
+                        config.table,
+                        offset,
+                        || Value::known(*element),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Constrains `value` to be in the set.
+    pub fn assert_member(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "assert member",
+            |mut region| {
+                config.q_lookup.enable(&mut region, 0)?;
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns 1 if `value` is in the set and 0 otherwise.
+    pub fn is_member(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "is member",
+            |mut region| {
+                let mut product = Value::known(F::one());
+                let last = config.set.len() - 1;
+                for (offset, element) in config.set.iter().enumerate() {
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                        value.copy_advice(|| "value", &mut region, config.value, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                        region.assign_advice(
+                            || "value",
+                            config.value,
+                            offset,
+                            || value.value().copied(),
+                        )?;
+                    }
+                    region.assign_fixed(
+                        || "element",
+                        config.element,
+                        offset,
+                        || Value::known(*element),
+                    )?;
+
+                    product = product * value.value().map(|v| *v - element);
+                    region.assign_advice(|| "product", config.product, offset, || product)?;
+                }
+
+                config.q_result.enable(&mut region, last)?;
+                region.assign_advice(
+                    || "product inverse",
+                    config.product_inverse,
+                    last,
+                    || product.map(|p| p.invert().unwrap_or(F::zero())),
+                )?;
+                region.assign_advice(
+                    || "is member",
+                    config.is_member,
+                    last,
+                    || product.map(|p| F::from(p == F::zero())),
+                )
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    const SET: [u64; 5] = [2, 3, 5, 7, 11];
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> SetMembershipConfig<F> {
+        let value = meta.advice_column();
+        let element = meta.fixed_column();
+        let product = meta.advice_column();
+        let product_inverse = meta.advice_column();
+        let is_member = meta.advice_column();
+
+        meta.enable_equality(value);
+        meta.enable_equality(is_member);
+
+        let set = SET.map(F::from).to_vec();
+        SetMembershipChip::configure(
+            meta,
+            value,
+            element,
+            product,
+            product_inverse,
+            is_member,
+            set,
+        )
+    }
+
+    struct AssertMemberTest;
+
+    impl<F: FieldExt> HarnessChip<F> for AssertMemberTest {
+        type Config = SetMembershipConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = SetMembershipChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "set"))?;
+            chip.assert_member(layouter.namespace(|| "assert member"), witnesses[0].clone())?;
+            Ok(vec![])
+        }
+    }
+
+    struct IsMemberTest;
+
+    impl<F: FieldExt> HarnessChip<F> for IsMemberTest {
+        type Config = SetMembershipConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = SetMembershipChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "set"))?;
+            let is_member =
+                chip.is_member(layouter.namespace(|| "is member"), witnesses[0].clone())?;
+            Ok(vec![is_member])
+        }
+    }
+
+    #[test]
+    fn test_assert_member() {
+        let harness = TestHarness::auto();
+        for member in SET {
+            harness.expect_pass(AssertMemberTest, vec![Fp::from(member)], vec![]);
+        }
+        harness.expect_fail(AssertMemberTest, vec![Fp::from(4)], vec![]);
+        // 0 is what disabled rows would look up without the first element
+        // default
+        harness.expect_fail(AssertMemberTest, vec![Fp::zero()], vec![]);
+    }
+
+    #[test]
+    fn test_is_member() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(IsMemberTest, vec![Fp::from(7)], vec![Fp::one()]);
+        harness.expect_pass(IsMemberTest, vec![Fp::from(4)], vec![Fp::zero()]);
+        harness.expect_pass(IsMemberTest, vec![Fp::zero()], vec![Fp::zero()]);
+
+        harness.expect_fail(IsMemberTest, vec![Fp::from(4)], vec![Fp::one()]);
+        harness.expect_fail(IsMemberTest, vec![Fp::from(11)], vec![Fp::zero()]);
+    }
+}
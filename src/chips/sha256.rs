@@ -0,0 +1,509 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::{bit_decomposition::field_bit, expr::pow2_recompose};
+
+pub const WORD_BITS: usize = 32;
+
+/// The initial hash values `h0..h7` of SHA-256, FIPS 180-4 section 5.3.3.
+pub const IV: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// The sparse representation of `dense`, bit `i` moved to bit `B * i`.
+pub fn sparse_word<F: FieldExt, const B: usize>(dense: u32) -> F {
+    let digits: Vec<_> = (0..WORD_BITS)
+        .map(|i| F::from(((dense >> i) & 1) as u64))
+        .collect();
+    recompose(&digits, B)
+}
+
+fn recompose<F: FieldExt>(digits: &[F], bits: usize) -> F {
+    let radix = F::from_u128(1 << bits);
+    digits
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, digit| acc * radix + digit)
+}
+
+#[derive(Clone, Debug)]
+pub struct SparseRepConfig<F: FieldExt, const B: usize> {
+    bits: [Column<Advice>; B],
+    dense: Column<Advice>,
+    sparse: Column<Advice>,
+    q_first: Selector,
+    q_step: Selector,
+    q_to_sparse: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Converts 32-bit words to and from their sparse representation, where
+/// bit `i` of the word is the B-bit digit `i`, so each bit `b` maps to
+/// `b * 2^(B * i)`.
+///
+/// Adding sparse words adds digit by digit without carries as long as a
+/// digit stays below `2^B`, which turns bitwise functions into arithmetic:
+/// the low bit of each digit of `a + b` is `a ^ b` and, for `B >= 2`, the
+/// high bit of each digit of `a + b + c` is the majority.
+///
+/// Both directions lay out one digit per row, most significant first, with
+/// the digit's B bits in `bits` and running sums of the dense and the
+/// sparse value:
+///
+/// - `digit = sum(bits_j * 2^j)`, every `bits_j` boolean
+/// - `sparse = 2^B * sparse_prev + digit`
+/// - `dense = 2 * dense_prev + bits_0`
+///
+/// `to_sparse` also zeroes `bits_1..` and copies the word into the last
+/// dense sum, `from_sparse` copies the sparse value into the last sparse
+/// sum and returns the dense value of the low bits of the digits.
+#[derive(Clone, Debug)]
+pub struct SparseRepChip<F: FieldExt, const B: usize> {
+    config: SparseRepConfig<F, B>,
+}
+
+impl<F: FieldExt, const B: usize> Chip<F> for SparseRepChip<F, B> {
+    type Config = SparseRepConfig<F, B>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const B: usize> SparseRepChip<F, B> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bits: [Column<Advice>; B],
+        dense: Column<Advice>,
+        sparse: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        // a sparse value below 2^(32 * B) has one decomposition into digits
+        assert!(
+            B > 0 && WORD_BITS * B < F::NUM_BITS as usize,
+            "sparse words must fit in the field"
+        );
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let q_to_sparse = meta.selector();
+
+        meta.enable_equality(dense);
+        meta.enable_equality(sparse);
+
+        meta.create_gate("sparse rep", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_step = meta.query_selector(q_step);
+            let q_to_sparse = meta.query_selector(q_to_sparse);
+            let one = Expression::Constant(F::one());
+            let bits = bits.map(|bit| meta.query_advice(bit, Rotation::cur()));
+            let dense = meta.query_advice(dense, Rotation::cur());
+            let dense_prev = meta.query_advice(dense, Rotation::prev());
+            let sparse = meta.query_advice(sparse, Rotation::cur());
+            let sparse_prev = meta.query_advice(sparse, Rotation::prev());
+            let digit = pow2_recompose(&bits, 1);
+            let q_digit = q_first.clone() + q_step.clone();
+
+            let mut constraints: Vec<_> = bits
+                .iter()
+                .map(|bit| q_digit.clone() * bit.clone() * (one.clone() - bit.clone()))
+                .collect();
+            constraints.extend([
+                q_first.clone() * (sparse.clone() - digit.clone()),
+                q_first * (dense.clone() - bits[0].clone()),
+                q_step.clone() * (sparse - pow2_recompose(&[digit, sparse_prev], B)),
+                q_step * (dense - pow2_recompose(&[bits[0].clone(), dense_prev], 1)),
+            ]);
+            constraints.extend(
+                bits[1..]
+                    .iter()
+                    .map(|bit| q_to_sparse.clone() * bit.clone()),
+            );
+            constraints
+        });
+
+        SparseRepConfig {
+            bits,
+            dense,
+            sparse,
+            q_first,
+            q_step,
+            q_to_sparse,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the sparse representation of a 32-bit word. Fails for a
+    /// value that doesn't fit in 32 bits.
+    pub fn to_sparse(
+        &self,
+        layouter: impl Layouter<F>,
+        dense: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let digits = dense.value().map(|dense| {
+            (0..WORD_BITS)
+                .map(|i| F::from(field_bit(dense, i)))
+                .collect::<Vec<_>>()
+        });
+        let (_, sparse) = self.assign_digits(layouter, "to sparse", digits, Some(&dense), None)?;
+        Ok(sparse)
+    }
+
+    /// Returns the dense word of the low bits of the digits of a sparse
+    /// value, e.g. `a ^ b` for the sum of two sparse words. Fails for a
+    /// value with more than 32 digits.
+    pub fn from_sparse(
+        &self,
+        layouter: impl Layouter<F>,
+        sparse: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let digits = sparse.value().map(|sparse| {
+            (0..WORD_BITS)
+                .map(|i| {
+                    let bits: Vec<_> = (0..B)
+                        .map(|j| F::from(field_bit(sparse, B * i + j)))
+                        .collect();
+                    recompose(&bits, 1)
+                })
+                .collect::<Vec<_>>()
+        });
+        let (dense, _) =
+            self.assign_digits(layouter, "from sparse", digits, None, Some(&sparse))?;
+        Ok(dense)
+    }
+
+    // lays out the digits, least significant first, and returns the last
+    // dense and sparse sums, the given one copied in
+    fn assign_digits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &'static str,
+        digits: Value<Vec<F>>,
+        dense: Option<&AssignedCell<F, F>>,
+        sparse: Option<&AssignedCell<F, F>>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                let mut dense_acc = Value::known(F::zero());
+                let mut sparse_acc = Value::known(F::zero());
+                let mut last = None;
+                for offset in 0..WORD_BITS {
+                    let i = WORD_BITS - 1 - offset;
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+                    if dense.is_some() {
+                        config.q_to_sparse.enable(&mut region, offset)?;
+                    }
+
+                    let digit = digits.as_ref().map(|digits| digits[i]);
+                    for (j, column) in config.bits.iter().enumerate() {
+                        let bit = digit.map(|digit| F::from(field_bit(&digit, j)));
+                        region.assign_advice(|| "bit", *column, offset, || bit)?;
+                    }
+                    let low_bit = digit.map(|digit| F::from(field_bit(&digit, 0)));
+                    dense_acc = dense_acc * Value::known(F::from(2)) + low_bit;
+                    sparse_acc = sparse_acc * Value::known(F::from_u128(1 << B)) + digit;
+
+                    let is_last = offset == WORD_BITS - 1;
+                    let dense_cell = match dense {
+                        Some(dense) if is_last => {
+                            dense.copy_advice(|| "dense", &mut region, config.dense, offset)?
+                        }
+                        _ => {
+                            region.assign_advice(|| "dense", config.dense, offset, || dense_acc)?
+                        }
+                    };
+                    let sparse_cell = match sparse {
+                        Some(sparse) if is_last => {
+                            sparse.copy_advice(|| "sparse", &mut region, config.sparse, offset)?
+                        }
+                        _ => region.assign_advice(
+                            || "sparse",
+                            config.sparse,
+                            offset,
+                            || sparse_acc,
+                        )?,
+                    };
+                    last = Some((dense_cell, sparse_cell));
+                }
+                Ok(last.expect("a word has bits"))
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IVConfig<F: FieldExt> {
+    advice: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+/// Loads the SHA-256 IV as advice cells fixed to the constants, the state
+/// the first block of a message is compressed into.
+#[derive(Clone, Debug)]
+pub struct IVChip<F: FieldExt> {
+    config: IVConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for IVChip<F> {
+    type Config = IVConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> IVChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// Creates the fixed column the constants are loaded from, `advice` gets
+    /// equality so the IV cells can be copied into the compression.
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: Column<Advice>) -> IVConfig<F> {
+        meta.enable_equality(advice);
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        IVConfig {
+            advice,
+            _marker: PhantomData,
+        }
+    }
+
+    /// `h0..h7`, one per row.
+    pub fn load_iv(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<[AssignedCell<F, F>; 8], Error> {
+        let advice = self.config.advice;
+        let cells = layouter.assign_region(
+            || "sha256 iv",
+            |mut region| {
+                IV.iter()
+                    .enumerate()
+                    .map(|(row, h)| {
+                        region.assign_advice_from_constant(
+                            || format!("h{row}"),
+                            advice,
+                            row,
+                            F::from(*h as u64),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+        Ok(cells.try_into().expect("the IV has 8 words"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::linear_combination::{LinearCombinationChip, LinearCombinationConfig},
+        testing::{HarnessChip, TestHarness},
+    };
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> SparseRepConfig<F, 2> {
+        let bits = [(); 2].map(|_| meta.advice_column());
+        let dense = meta.advice_column();
+        let sparse = meta.advice_column();
+        SparseRepChip::configure(meta, bits, dense, sparse)
+    }
+
+    // outputs the sparse witness and its round trip back to dense
+    struct RoundTripTest;
+
+    impl<F: FieldExt> HarnessChip<F> for RoundTripTest {
+        type Config = SparseRepConfig<F, 2>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = SparseRepChip::construct(config);
+            let sparse =
+                chip.to_sparse(layouter.namespace(|| "to sparse"), witnesses[0].clone())?;
+            let dense = chip.from_sparse(layouter.namespace(|| "from sparse"), sparse.clone())?;
+            Ok(vec![sparse, dense])
+        }
+    }
+
+    // decodes the sum of two sparse words
+    struct SparseXorTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SparseXorTest {
+        type Config = (SparseRepConfig<F, 2>, LinearCombinationConfig<F, 2>);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let config = configure(meta);
+            let [a, b, sum] = [(); 3].map(|_| meta.advice_column());
+            for column in [a, b, sum] {
+                meta.enable_equality(column);
+            }
+            let add = LinearCombinationChip::configure(meta, [a, b], sum, [F::one(); 2]);
+            (config, add)
+        }
+
+        fn synthesize(
+            (config, add): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = SparseRepChip::construct(config);
+            let sparse = witnesses
+                .iter()
+                .map(|word| chip.to_sparse(layouter.namespace(|| "to sparse"), word.clone()))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let sum = LinearCombinationChip::construct(add).combine(
+                layouter.namespace(|| "add"),
+                &[sparse[0].clone(), sparse[1].clone()],
+            )?;
+            let xor = chip.from_sparse(layouter.namespace(|| "from sparse"), sum)?;
+            Ok(vec![xor])
+        }
+    }
+
+    struct IVTest;
+
+    impl<F: FieldExt> HarnessChip<F> for IVTest {
+        type Config = IVConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            IVChip::configure(meta, advice)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            _: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let iv = IVChip::construct(config).load_iv(layouter.namespace(|| "iv"))?;
+            Ok(iv.to_vec())
+        }
+    }
+
+    // the IV word `I` copied to the witness
+    struct IVCopyTest<const I: usize>;
+
+    impl<F: FieldExt, const I: usize> HarnessChip<F> for IVCopyTest<I> {
+        type Config = IVConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            IVChip::configure(meta, advice)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let iv = IVChip::construct(config).load_iv(layouter.namespace(|| "iv"))?;
+            layouter.assign_region(
+                || "copy iv word",
+                |mut region| region.constrain_equal(iv[I].cell(), witnesses[0].cell()),
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_iv() {
+        let iv: Vec<_> = IV.iter().map(|h| Fp::from(*h as u64)).collect();
+        assert_eq!(iv[0], Fp::from(0x6a09_e667));
+        assert_eq!(iv[7], Fp::from(0x5be0_cd19));
+
+        let harness = TestHarness::auto();
+        harness.expect_pass(IVTest, vec![], iv.clone());
+        for i in 0..8 {
+            let mut tampered = iv.clone();
+            tampered[i] += Fp::one();
+            harness.expect_fail(IVTest, vec![], tampered);
+        }
+    }
+
+    #[test]
+    fn test_tampered_iv_copy() {
+        let harness = TestHarness::auto();
+        let h3 = Fp::from(IV[3] as u64);
+        harness.expect_pass(IVCopyTest::<3>, vec![h3], vec![]);
+        harness.expect_fail(IVCopyTest::<3>, vec![h3 + Fp::one()], vec![]);
+        // the value of another IV word
+        harness.expect_fail(IVCopyTest::<3>, vec![Fp::from(IV[4] as u64)], vec![]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let harness = TestHarness::auto();
+        for word in [0, 1, 0x6162_6380, u32::MAX] {
+            let expected = vec![sparse_word::<Fp, 2>(word), Fp::from(word as u64)];
+            harness.expect_pass(RoundTripTest, vec![Fp::from(word as u64)], expected);
+        }
+        // 0b101 -> 0b01_00_01
+        assert_eq!(sparse_word::<Fp, 2>(0b101), Fp::from(0b01_00_01));
+        harness.expect_fail(
+            RoundTripTest,
+            vec![Fp::from(5)],
+            vec![Fp::from(5), Fp::from(5)],
+        );
+    }
+
+    #[test]
+    fn test_wide_word_fail() {
+        TestHarness::auto().expect_fail(
+            RoundTripTest,
+            vec![Fp::from(1 << 32)],
+            vec![Fp::zero(), Fp::zero()],
+        );
+    }
+
+    #[test]
+    fn test_sparse_xor() {
+        let harness = TestHarness::auto();
+        for (a, b) in [(0, 0), (0x6162_6380, 0x5be0_cd19), (u32::MAX, 1)] {
+            let words = vec![Fp::from(a as u64), Fp::from(b as u64)];
+            harness.expect_pass(SparseXorTest, words.clone(), vec![Fp::from((a ^ b) as u64)]);
+            harness.expect_fail(SparseXorTest, words, vec![Fp::from((a ^ b ^ 1) as u64)]);
+        }
+    }
+}
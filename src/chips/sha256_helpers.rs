@@ -0,0 +1,439 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    bit_decomposition::{BitDecompositionChip, BitDecompositionConfig},
+    xor::XorChip,
+};
+
+pub const WORD_BITS: usize = 32;
+
+/// Recomposes LIMB_BITS-bit limbs out of the bits of a 32-bit word, shared
+/// by `RotateRightChip` and `BitShiftRightChip`.
+///
+/// A limb is a running sum over LIMB_BITS rows, most significant bit first,
+/// of bits copied from a decomposition. The fixed `keep` column is 0 where
+/// the copied bit is dropped, so shifted out bits don't contribute:
+///
+/// - first row: `acc = keep * bit`
+/// - other rows: `acc = 2 * acc_prev + keep * bit`
+#[derive(Clone, Debug)]
+pub struct LimbSelectConfig<F: FieldExt, const LIMB_BITS: usize> {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    keep: Column<Fixed>,
+    q_first: Selector,
+    q_step: Selector,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> LimbSelectConfig<F, LIMB_BITS> {
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> Self {
+        assert!(
+            LIMB_BITS > 0 && WORD_BITS % LIMB_BITS == 0,
+            "limbs must split a word evenly"
+        );
+        let keep = meta.fixed_column();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        // the bits are copied in and the limbs copied out
+        meta.enable_equality(bit);
+        meta.enable_equality(acc);
+
+        meta.create_gate("limb select first", |meta| {
+            let q = meta.query_selector(q_first);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let keep = meta.query_fixed(keep, Rotation::cur());
+            vec![q * (acc - keep * bit)]
+        });
+
+        meta.create_gate("limb select step", |meta| {
+            let q = meta.query_selector(q_step);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let keep = meta.query_fixed(keep, Rotation::cur());
+            let two = Expression::Constant(F::from(2));
+            vec![q * (acc - acc_prev * two - keep * bit)]
+        });
+
+        Self {
+            bit,
+            acc,
+            keep,
+            q_first,
+            q_step,
+            _marker: PhantomData,
+        }
+    }
+
+    // the limbs, least significant first, of the word whose bit `i` is
+    // `bits[source(i)]` if that is `Some` and 0 otherwise
+    fn select_limbs(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[AssignedCell<F, F>],
+        source: impl Fn(usize) -> Option<usize>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert_eq!(bits.len(), WORD_BITS, "a word has 32 bits");
+
+        layouter.assign_region(
+            || "limb select",
+            |mut region| {
+                let mut limbs = Vec::with_capacity(WORD_BITS / LIMB_BITS);
+                for limb in 0..WORD_BITS / LIMB_BITS {
+                    let mut acc = Value::known(F::zero());
+                    let mut acc_cell = None;
+                    for j in 0..LIMB_BITS {
+                        let offset = limb * LIMB_BITS + j;
+                        let index = limb * LIMB_BITS + LIMB_BITS - 1 - j;
+                        if j == 0 {
+                            self.q_first.enable(&mut region, offset)?;
+                        } else {
+                            self.q_step.enable(&mut region, offset)?;
+                        }
+
+                        // a dropped bit is still copied from some bit, the
+                        // gate ignores it
+                        let (from, keep) = match source(index) {
+                            Some(from) => (from, F::one()),
+                            None => (index, F::zero()),
+                        };
+                        let bit =
+                            bits[from].copy_advice(|| "bit", &mut region, self.bit, offset)?;
+                        region.assign_fixed(|| "keep", self.keep, offset, || Value::known(keep))?;
+
+                        acc = acc * Value::known(F::from(2)) + bit.value().map(|bit| *bit * keep);
+                        acc_cell =
+                            Some(region.assign_advice(|| "acc", self.acc, offset, || acc)?);
+                    }
+                    limbs.push(acc_cell.expect("at least one bit per limb"));
+                }
+                Ok(limbs)
+            },
+        )
+    }
+}
+
+/// `ROTR^n` of a 32-bit word given as its bits, in LIMB_BITS-bit limbs.
+#[derive(Clone, Debug)]
+pub struct RotateRightChip<F: FieldExt, const LIMB_BITS: usize> {
+    config: LimbSelectConfig<F, LIMB_BITS>,
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> Chip<F> for RotateRightChip<F, LIMB_BITS> {
+    type Config = LimbSelectConfig<F, LIMB_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> RotateRightChip<F, LIMB_BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// Returns the limbs of `ROTR^n(x)`, least significant first, from the
+    /// bits of `x`, least significant first.
+    pub fn rotate_right(
+        &self,
+        layouter: impl Layouter<F>,
+        bits: &[AssignedCell<F, F>],
+        n: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.config
+            .select_limbs(layouter, bits, |i| Some((i + n) % WORD_BITS))
+    }
+}
+
+/// `SHR^n` of a 32-bit word given as its bits, in LIMB_BITS-bit limbs.
+#[derive(Clone, Debug)]
+pub struct BitShiftRightChip<F: FieldExt, const LIMB_BITS: usize> {
+    config: LimbSelectConfig<F, LIMB_BITS>,
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> Chip<F> for BitShiftRightChip<F, LIMB_BITS> {
+    type Config = LimbSelectConfig<F, LIMB_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> BitShiftRightChip<F, LIMB_BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// Returns the limbs of `SHR^n(x)`, least significant first, from the
+    /// bits of `x`, least significant first.
+    pub fn shift_right(
+        &self,
+        layouter: impl Layouter<F>,
+        bits: &[AssignedCell<F, F>],
+        n: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.config
+            .select_limbs(layouter, bits, |i| Some(i + n).filter(|i| *i < WORD_BITS))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MessageSigmaConfig<F: FieldExt, const LIMB_BITS: usize = 8> {
+    decomposition: BitDecompositionConfig<F, WORD_BITS>,
+    limb_select: LimbSelectConfig<F, LIMB_BITS>,
+    pub xor: XorChip<F, LIMB_BITS>,
+    limb: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_step: Selector,
+}
+
+/// The SHA-256 message schedule functions
+///
+/// - `σ0(x) = ROTR^7(x) ^ ROTR^18(x) ^ SHR^3(x)`
+/// - `σ1(x) = ROTR^17(x) ^ ROTR^19(x) ^ SHR^10(x)`
+///
+/// `x` is decomposed into bits once, the three terms are selected from the
+/// bits as LIMB_BITS-bit limbs and xored limb by limb with `XorChip`, two
+/// lookups per limb. The result limbs are recombined into a word with a
+/// running sum, most significant limb first. The decomposition fails for an
+/// `x` that doesn't fit in 32 bits.
+///
+/// Byte limbs need the `2^16` row xor table and `k >= 17`, tests use
+/// `LIMB_BITS = 4`.
+#[derive(Clone, Debug)]
+pub struct MessageSigmaChip<F: FieldExt, const LIMB_BITS: usize = 8> {
+    config: MessageSigmaConfig<F, LIMB_BITS>,
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> Chip<F> for MessageSigmaChip<F, LIMB_BITS> {
+    type Config = MessageSigmaConfig<F, LIMB_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> MessageSigmaChip<F, LIMB_BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// The bit decomposition, the limb selection and the limb recombination
+    /// all share `bit` and `acc`, which need no equality enabled by the
+    /// caller.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let decomposition = BitDecompositionChip::configure(meta, bit, acc);
+        let limb_select = LimbSelectConfig::configure(meta, bit, acc);
+        let xor = XorChip::construct(meta);
+        let (limb, q_first, q_step) = (bit, meta.selector(), meta.selector());
+
+        meta.create_gate("message sigma limbs first", |meta| {
+            let q = meta.query_selector(q_first);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - limb)]
+        });
+
+        meta.create_gate("message sigma limbs step", |meta| {
+            let q = meta.query_selector(q_step);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let radix = Expression::Constant(F::from(1 << LIMB_BITS));
+            vec![q * (acc - acc_prev * radix - limb)]
+        });
+
+        MessageSigmaConfig {
+            decomposition,
+            limb_select,
+            xor,
+            limb,
+            acc,
+            q_first,
+            q_step,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config().xor.xor_table.load(layouter)
+    }
+
+    /// `ROTR^7(x) ^ ROTR^18(x) ^ SHR^3(x)`
+    pub fn sigma0(
+        &self,
+        layouter: impl Layouter<F>,
+        x: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.sigma(layouter, x, [7, 18], 3)
+    }
+
+    /// `ROTR^17(x) ^ ROTR^19(x) ^ SHR^10(x)`
+    pub fn sigma1(
+        &self,
+        layouter: impl Layouter<F>,
+        x: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.sigma(layouter, x, [17, 19], 10)
+    }
+
+    fn sigma(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: AssignedCell<F, F>,
+        [r1, r2]: [usize; 2],
+        s: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let bits: Vec<_> = BitDecompositionChip::construct(config.decomposition.clone())
+            .decompose(layouter.namespace(|| "decompose"), x)?
+            .into();
+
+        let rotate = RotateRightChip::construct(config.limb_select.clone());
+        let shift = BitShiftRightChip::construct(config.limb_select.clone());
+        let first = rotate.rotate_right(layouter.namespace(|| "first rotation"), &bits, r1)?;
+        let second = rotate.rotate_right(layouter.namespace(|| "second rotation"), &bits, r2)?;
+        let third = shift.shift_right(layouter.namespace(|| "shift"), &bits, s)?;
+
+        let limbs = first
+            .into_iter()
+            .zip(second)
+            .zip(third)
+            .map(|((first, second), third)| {
+                let xor = config
+                    .xor
+                    .calculate_xor(layouter.namespace(|| "xor"), first, second)?;
+                config
+                    .xor
+                    .calculate_xor(layouter.namespace(|| "xor"), xor, third)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        layouter.assign_region(
+            || "message sigma limbs",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut acc_cell = None;
+                for (offset, limb) in limbs.iter().rev().enumerate() {
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+                    limb.copy_advice(|| "limb", &mut region, config.limb, offset)?;
+                    acc = acc * Value::known(F::from(1 << LIMB_BITS)) + limb.value();
+                    acc_cell = Some(region.assign_advice(|| "acc", config.acc, offset, || acc)?);
+                }
+                Ok(acc_cell.expect("at least one limb"))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    // the first word of the padded message "abc"
+    const ABC_W0: u64 = 0x6162_6380;
+
+    // outputs σ0 and σ1 of the witness
+    struct SigmaTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SigmaTest {
+        type Config = MessageSigmaConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            MessageSigmaChip::configure(meta, bit, acc)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = MessageSigmaChip::construct(config);
+            chip.load_table(&mut layouter.namespace(|| "xor table"))?;
+            let sigma0 = chip.sigma0(layouter.namespace(|| "sigma0"), witnesses[0].clone())?;
+            let sigma1 = chip.sigma1(layouter.namespace(|| "sigma1"), witnesses[0].clone())?;
+            Ok(vec![sigma0, sigma1])
+        }
+    }
+
+    fn sigmas(x: u32) -> Vec<Fp> {
+        let sigma0 = x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3);
+        let sigma1 = x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10);
+        vec![Fp::from(sigma0 as u64), Fp::from(sigma1 as u64)]
+    }
+
+    #[test]
+    fn test_abc_first_word() {
+        let harness = TestHarness::auto();
+        let expected = vec![Fp::from(0x940e_90ef), Fp::from(0x7da8_6405)];
+        assert_eq!(sigmas(ABC_W0 as u32), expected);
+        harness.expect_pass(SigmaTest, vec![Fp::from(ABC_W0)], expected);
+    }
+
+    #[test]
+    fn test_sigmas() {
+        let harness = TestHarness::auto();
+        for x in [0, 1, 0x18, 0x8000_0000, u32::MAX] {
+            harness.expect_pass(SigmaTest, vec![Fp::from(x as u64)], sigmas(x));
+        }
+    }
+
+    #[test]
+    fn test_wrong_output_fail() {
+        let harness = TestHarness::auto();
+        let [sigma0, sigma1]: [Fp; 2] = sigmas(ABC_W0 as u32).try_into().unwrap();
+        harness.expect_fail(
+            SigmaTest,
+            vec![Fp::from(ABC_W0)],
+            vec![sigma0 + Fp::one(), sigma1],
+        );
+        harness.expect_fail(SigmaTest, vec![Fp::from(ABC_W0)], vec![sigma1, sigma0]);
+    }
+
+    #[test]
+    fn test_wide_word_fail() {
+        TestHarness::auto().expect_fail(SigmaTest, vec![Fp::from(1 << 32)], sigmas(0));
+    }
+}
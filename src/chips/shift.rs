@@ -0,0 +1,231 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+mod table;
+use table::*;
+
+// Computes (value << shift) truncated to BITS bits using a lookup table.
+// Table size is 2**BITS * (BITS + 1), for BITS=4 it is 80
+#[derive(Clone, Debug)]
+pub struct ShiftLeftLookupChip<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    q_lookup: Selector,
+    pub shift_table: ShiftLeftTableConfig<F, BITS>,
+    value_advice: Column<Advice>,
+    shift_advice: Column<Advice>,
+    result_advice: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> ShiftLeftLookupChip<F, BITS> {
+    pub fn construct(meta: &mut ConstraintSystem<F>) -> Self {
+        let q_lookup = meta.complex_selector();
+
+        // creates 3 table columns
+        let shift_table = ShiftLeftTableConfig::configure(meta);
+
+        let value_advice = meta.advice_column();
+        let shift_advice = meta.advice_column();
+        let result_advice = meta.advice_column();
+
+        meta.enable_equality(value_advice);
+        meta.enable_equality(shift_advice);
+        meta.enable_equality(result_advice);
+
+        meta.lookup("shift left lookup", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let value_cur = meta.query_advice(value_advice, Rotation::cur());
+            let shift_cur = meta.query_advice(shift_advice, Rotation::cur());
+            let result_cur = meta.query_advice(result_advice, Rotation::cur());
+
+            vec![
+                (q.clone() * value_cur, shift_table.value),
+                (q.clone() * shift_cur, shift_table.shift),
+                (q * result_cur, shift_table.result),
+            ]
+        });
+
+        Self {
+            q_lookup,
+            shift_table,
+            value_advice,
+            shift_advice,
+            result_advice,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn calculate_shift_left(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value_cell_advice: AssignedCell<F, F>,
+        shift_cell_advice: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "Assign value for lookup shift left check",
+            |mut region| {
+                let offset = 0;
+
+                self.q_lookup.enable(&mut region, offset)?;
+
+                // Copying into the lookup columns range checks value and shift as well
+                let value_cell = value_cell_advice.copy_advice(
+                    || "copy value",
+                    &mut region,
+                    self.value_advice,
+                    offset,
+                )?;
+                let shift_cell = shift_cell_advice.copy_advice(
+                    || "copy shift",
+                    &mut region,
+                    self.shift_advice,
+                    offset,
+                )?;
+
+                let mask = (1u128 << BITS) - 1;
+                let result = value_cell
+                    .value()
+                    .zip(shift_cell.value())
+                    .map(|(value, shift)| {
+                        let shift = shift.get_lower_128();
+                        if shift > BITS as u128 {
+                            // not in the table, the lookup fails regardless of the result
+                            0
+                        } else {
+                            (value.get_lower_128() << shift) & mask
+                        }
+                    })
+                    .map(F::from_u128);
+                region.assign_advice(|| "result", self.result_advice, offset, || result)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+
+    const K: u32 = 7;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt, const BITS: usize> {
+        value: F,
+        shift: F,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt, const BITS: usize> {
+        advice: Column<Advice>,
+        shift_chip: ShiftLeftLookupChip<F, BITS>,
+        result_instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt, const BITS: usize> Circuit<F> for TestCircuit<F, BITS> {
+        type Config = TestCircuitConfig<F, BITS>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let result_instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(result_instance);
+
+            TestCircuitConfig {
+                advice,
+                shift_chip: ShiftLeftLookupChip::construct(meta),
+                result_instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let shift_chip = config.shift_chip.clone();
+
+            shift_chip
+                .shift_table
+                .load(&mut layouter.namespace(|| "shift left table"))?;
+
+            let (value_cell, shift_cell) = layouter.assign_region(
+                || "load advice",
+                |mut region| {
+                    let value = region.assign_advice(
+                        || "value",
+                        config.advice,
+                        0,
+                        || Value::known(self.value),
+                    )?;
+                    let shift = region.assign_advice(
+                        || "shift",
+                        config.advice,
+                        1,
+                        || Value::known(self.shift),
+                    )?;
+                    Ok((value, shift))
+                },
+            )?;
+
+            let result_cell = shift_chip.calculate_shift_left(
+                layouter.namespace(|| "shift left"),
+                value_cell,
+                shift_cell,
+            )?;
+
+            layouter.constrain_instance(result_cell.cell(), config.result_instance, 0)
+        }
+    }
+
+    fn run(value: u64, shift: u64, result: u64) -> MockProver<Fp> {
+        MockProver::run(
+            K,
+            &TestCircuit::<Fp, 4> {
+                value: Fp::from(value),
+                shift: Fp::from(shift),
+            },
+            vec![vec![Fp::from(result)]],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_shift_left_pass() {
+        // Should success.
+        assert_eq!(run(0b0001, 3, 0b1000).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_shift_left_shifted_out() {
+        // All bits are shifted out, so the result is 0.
+        assert_eq!(run(0b1111, 4, 0).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_shift_left_fail() {
+        // Should fail since the result is 0b1000.
+        assert!(run(0b0001, 3, 0b0100).verify().is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{ConstraintSystem, Error, TableColumn},
+};
+
+// Table size is 2**BITS * (BITS + 1)
+// Shift amounts go up to and including BITS so that shifting every bit out is representable
+
+#[derive(Debug, Clone)]
+pub struct ShiftLeftTableConfig<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    pub value: TableColumn,
+    pub shift: TableColumn,
+    pub result: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> ShiftLeftTableConfig<F, BITS> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let value = meta.lookup_table_column();
+        let shift = meta.lookup_table_column();
+        let result = meta.lookup_table_column();
+
+        Self {
+            value,
+            shift,
+            result,
+            _marker: PhantomData,
+        }
+    }
+
+    // fill all possibilities of BITS bit string left shifts, truncated to BITS bits
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load shift left table",
+            |mut table| {
+                let mask = (1u64 << BITS) - 1;
+                let mut offset = 0;
+                for value in 0..(1u64 << BITS) {
+                    for shift in 0..=BITS {
+                        table.assign_cell(
+                            || "value",
+                            self.value,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                        table.assign_cell(
+                            || "shift",
+                            self.shift,
+                            offset,
+                            || Value::known(F::from(shift as u64)),
+                        )?;
+                        table.assign_cell(
+                            || "output",
+                            self.result,
+                            offset,
+                            || Value::known(F::from((value << shift) & mask)),
+                        )?;
+                        offset += 1;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,222 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Debug)]
+pub struct SortedConfig<F: FieldExt, const N: usize> {
+    lo: Column<Advice>,
+    hi: Column<Advice>,
+    diff: Column<Advice>,
+    selector: Selector,
+    range_check: RangeCheckConfig<F, N>,
+}
+
+/// Proves that a slice of N-bit values is sorted, duplicates allowed.
+///
+/// Each adjacent pair is laid out as `(lo, hi)` and `diff = hi - lo` is range
+/// checked to N bits. For N-bit inputs this holds exactly when `lo <= hi`,
+/// otherwise `diff` wraps around to a value close to the modulus. The inputs
+/// must already be known to be N-bit.
+#[derive(Clone, Debug)]
+pub struct SortedChip<F: FieldExt, const N: usize> {
+    config: SortedConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for SortedChip<F, N> {
+    type Config = SortedConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> SortedChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        lo: Column<Advice>,
+        hi: Column<Advice>,
+        diff: Column<Advice>,
+        range_check: RangeCheckConfig<F, N>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("sorted pair", |meta| {
+            let s = meta.query_selector(selector);
+            let lo = meta.query_advice(lo, Rotation::cur());
+            let hi = meta.query_advice(hi, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+            vec![s * (diff - (hi - lo))]
+        });
+
+        SortedConfig {
+            lo,
+            hi,
+            diff,
+            selector,
+            range_check,
+        }
+    }
+
+    /// Constrains `values` to be non-decreasing for `Order::Ascending` and
+    /// non-increasing for `Order::Descending`.
+    pub fn assert_sorted(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+        order: Order,
+    ) -> Result<(), Error> {
+        let config = self.config();
+        let range_check_chip = RangeCheckChip::construct(config.range_check.clone());
+
+        for (i, pair) in values.windows(2).enumerate() {
+            let (lo, hi) = match order {
+                Order::Ascending => (&pair[0], &pair[1]),
+                Order::Descending => (&pair[1], &pair[0]),
+            };
+
+            let diff = layouter.assign_region(
+                || format!("sorted pair {i}"),
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    lo.copy_advice(|| "copy lo", &mut region, config.lo, 0)?;
+                    hi.copy_advice(|| "copy hi", &mut region, config.hi, 0)?;
+
+                    let diff = hi.value().zip(lo.value()).map(|(hi, lo)| *hi - *lo);
+                    region.assign_advice(|| "diff", config.diff, 0, || diff)
+                },
+            )?;
+
+            range_check_chip
+                .range_check(layouter.namespace(|| format!("range check {i}")), diff)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::Circuit,
+    };
+
+    use super::*;
+
+    const K: u32 = 6;
+
+    #[derive(Clone)]
+    struct TestCircuit<F: FieldExt> {
+        values: Vec<Value<F>>,
+        order: Order,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        sorted_config: SortedConfig<F, 8>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                order: self.order,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let lo = meta.advice_column();
+            let hi = meta.advice_column();
+            let diff = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+
+            for column in [advice, lo, hi, diff, acc] {
+                meta.enable_equality(column);
+            }
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            TestCircuitConfig {
+                advice,
+                sorted_config: SortedChip::configure(meta, lo, hi, diff, range_check),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = SortedChip::construct(config.sorted_config);
+
+            let values = layouter.assign_region(
+                || "load values",
+                |mut region| {
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .map(|(row, value)| {
+                            region.assign_advice(|| "value", config.advice, row, || *value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            chip.assert_sorted(layouter.namespace(|| "assert sorted"), &values, self.order)
+        }
+    }
+
+    fn run(values: &[u64], order: Order) -> MockProver<Fp> {
+        let circuit = TestCircuit::<Fp> {
+            values: values.iter().map(|v| Value::known(Fp::from(*v))).collect(),
+            order,
+        };
+        MockProver::run(K, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn test_sorted_pass() {
+        assert_eq!(run(&[0, 3, 17, 255], Order::Ascending).verify(), Ok(()));
+        assert_eq!(run(&[255, 17, 3, 0], Order::Descending).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_unsorted_fail() {
+        assert!(run(&[0, 17, 3, 255], Order::Ascending).verify().is_err());
+        assert!(run(&[0, 3, 17, 255], Order::Descending).verify().is_err());
+    }
+
+    #[test]
+    fn test_duplicates_pass() {
+        assert_eq!(run(&[3, 3, 3, 200], Order::Ascending).verify(), Ok(()));
+        assert_eq!(run(&[200, 200, 3, 3], Order::Descending).verify(), Ok(()));
+    }
+}
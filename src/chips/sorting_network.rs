@@ -0,0 +1,307 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+};
+
+use super::{
+    comparator::{ComparatorChip, ComparatorConfig},
+    cond_swap::{CondSwapChip, CondSwapConfig},
+};
+
+#[derive(Clone, Debug)]
+pub struct SortingNetworkConfig<F: FieldExt, const BITS: usize> {
+    comparator: ComparatorConfig<F, BITS>,
+    cond_swap: CondSwapConfig<F>,
+    // holds the padding constants
+    padding: Column<Advice>,
+}
+
+/// Sorts BITS-bit values ascending with a Batcher odd-even merge network.
+///
+/// Every compare and exchange of wires `i < j` computes `flag = w_j < w_i`
+/// with the `ComparatorChip` and swaps the pair with the `CondSwapChip` under
+/// that flag. The outputs of a swap are a permutation of its inputs and the
+/// network sorts any input, so the result is a sorted permutation of the
+/// input by construction with no separate checks.
+///
+/// Lengths that aren't a power of two are padded with the constant
+/// `2^BITS - 1`, which sorts to the end and is cut off again. The inputs must
+/// already be known to be BITS-bit.
+#[derive(Clone, Debug)]
+pub struct SortingNetworkChip<F: FieldExt, const BITS: usize> {
+    config: SortingNetworkConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for SortingNetworkChip<F, BITS> {
+    type Config = SortingNetworkConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> SortingNetworkChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `constants` is enabled for constants, it holds the padding.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        comparator: ComparatorConfig<F, BITS>,
+        cond_swap: CondSwapConfig<F>,
+        padding: Column<Advice>,
+        constants: Column<Fixed>,
+    ) -> <Self as Chip<F>>::Config {
+        meta.enable_equality(padding);
+        meta.enable_constant(constants);
+
+        SortingNetworkConfig {
+            comparator,
+            cond_swap,
+            padding,
+        }
+    }
+
+    pub fn sort(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if values.len() < 2 {
+            return Ok(values.to_vec());
+        }
+        let config = self.config();
+
+        let mut wires = values.to_vec();
+        let max = F::from_u128((1 << BITS) - 1);
+        while !wires.len().is_power_of_two() {
+            wires.push(layouter.assign_region(
+                || "sort padding",
+                |mut region| {
+                    region.assign_advice_from_constant(|| "padding", config.padding, 0, max)
+                },
+            )?);
+        }
+
+        for (i, j) in odd_even_merge_pairs(wires.len()) {
+            let (lo, hi) = self.compare_exchange(
+                layouter.namespace(|| "compare exchange"),
+                wires[i].clone(),
+                wires[j].clone(),
+            )?;
+            wires[i] = lo;
+            wires[j] = hi;
+        }
+
+        wires.truncate(values.len());
+        Ok(wires)
+    }
+
+    /// Returns `(min, max)` of the pair.
+    fn compare_exchange(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let flag = ComparatorChip::construct(self.config().comparator.clone()).lt(
+            layouter.namespace(|| "b < a"),
+            b.clone(),
+            a.clone(),
+        )?;
+        self.exchange(layouter.namespace(|| "exchange"), a, b, flag)
+    }
+
+    fn exchange(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        flag: AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let swapped =
+            CondSwapChip::construct(self.config().cond_swap.clone()).swap(layouter, a, b, flag)?;
+        Ok(swapped.into())
+    }
+}
+
+// The compare and exchange pairs `(i, j)`, `i < j`, of Batcher's odd-even
+// merge sort on `n` wires, `n` a power of two.
+fn odd_even_merge_pairs(n: usize) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    let mut p = 1;
+    while p < n {
+        let mut k = p;
+        while k >= 1 {
+            for j in (k % p..n - k).step_by(2 * k) {
+                for i in 0..k.min(n - j - k) {
+                    if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                        pairs.push((i + j, i + j + k));
+                    }
+                }
+            }
+            k /= 2;
+        }
+        p *= 2;
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::range_check::RangeCheckChip,
+        testing::{HarnessChip, TestHarness, TestRng},
+    };
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> SortingNetworkConfig<F, 4> {
+        let columns = [(); 8].map(|_| meta.advice_column());
+        let [a, b, diff, out, bit, acc, out1, padding] = columns;
+        for column in [a, b, diff, out, acc, out1] {
+            meta.enable_equality(column);
+        }
+        let constants = meta.fixed_column();
+
+        let range_check = RangeCheckChip::configure(meta, bit, acc);
+        let comparator = ComparatorChip::configure(meta, a, b, diff, out, range_check);
+        // the swap reuses the comparator's columns, flag in `out`
+        let cond_swap = CondSwapChip::configure(meta, a, b, out, diff, out1);
+        SortingNetworkChip::configure(meta, comparator, cond_swap, padding, constants)
+    }
+
+    struct SortTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SortTest {
+        type Config = SortingNetworkConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            SortingNetworkChip::construct(config).sort(layouter.namespace(|| "sort"), &witnesses)
+        }
+    }
+
+    // one compare and exchange whose swap copies a flag witnessed as the
+    // negation of the comparator output
+    struct ForgedFlagTest;
+
+    impl<F: FieldExt> HarnessChip<F> for ForgedFlagTest {
+        type Config = SortingNetworkConfig<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let (a, b) = (witnesses[0].clone(), witnesses[1].clone());
+            let chip = SortingNetworkChip::construct(config.clone());
+            let flag = ComparatorChip::construct(config.comparator).lt(
+                layouter.namespace(|| "b < a"),
+                b.clone(),
+                a.clone(),
+            )?;
+            let forged = layouter.assign_region(
+                || "forged flag",
+                |mut region| {
+                    let forged = flag.value().map(|flag| F::one() - flag);
+                    let forged = region.assign_advice(|| "forged", config.padding, 0, || forged)?;
+                    region.constrain_equal(forged.cell(), flag.cell())?;
+                    Ok(forged)
+                },
+            )?;
+            let (lo, hi) = chip.exchange(layouter.namespace(|| "exchange"), a, b, forged)?;
+            Ok(vec![lo, hi])
+        }
+    }
+
+    fn values(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    fn sorted(values: &[u64]) -> Vec<Fp> {
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        values(&sorted)
+    }
+
+    #[test]
+    fn test_network_sorts_bits() {
+        // 0-1 principle, a network sorting every 0/1 input sorts any input
+        for n in [2, 4, 8, 16] {
+            let pairs = odd_even_merge_pairs(n);
+            for input in 0u32..(1 << n) {
+                let mut wires: Vec<u32> = (0..n).map(|i| (input >> i) & 1).collect();
+                for (i, j) in &pairs {
+                    if wires[*j] < wires[*i] {
+                        wires.swap(*i, *j);
+                    }
+                }
+                assert!(wires.windows(2).all(|w| w[0] <= w[1]), "n = {n}");
+            }
+        }
+        assert_eq!(odd_even_merge_pairs(8).len(), 19);
+    }
+
+    #[test]
+    fn test_sort_random() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0x50f7);
+        for n in [2, 4, 8] {
+            let input: Vec<u64> = (0..n).map(|_| rng.below(16)).collect();
+            harness.expect_pass(SortTest, values(&input), sorted(&input));
+            // the input order, unless it happened to be sorted already
+            if values(&input) != sorted(&input) {
+                harness.expect_fail(SortTest, values(&input), values(&input));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_duplicates() {
+        let harness = TestHarness::auto();
+        let input = [5, 1, 5, 0, 1, 15, 15, 0];
+        harness.expect_pass(SortTest, values(&input), sorted(&input));
+        harness.expect_pass(SortTest, values(&[7; 4]), values(&[7; 4]));
+    }
+
+    #[test]
+    fn test_sort_padded() {
+        let harness = TestHarness::auto();
+        // padded to 4 and 8 with 15, which ties with the real 15s
+        harness.expect_pass(SortTest, values(&[3, 1, 2]), values(&[1, 2, 3]));
+        harness.expect_pass(
+            SortTest,
+            values(&[15, 4, 0, 15, 9]),
+            values(&[0, 4, 9, 15, 15]),
+        );
+        harness.expect_pass(SortTest, values(&[6]), values(&[6]));
+    }
+
+    #[test]
+    fn test_forged_swap_flag_fail() {
+        let harness = TestHarness::auto();
+        // the forged flag swaps the already ordered pair
+        harness.expect_fail(ForgedFlagTest, values(&[2, 9]), values(&[9, 2]));
+        harness.expect_fail(ForgedFlagTest, values(&[9, 2]), values(&[9, 2]));
+    }
+}
@@ -0,0 +1,288 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector, TableColumn},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct SparseDotConfig {
+    index: Column<Advice>,
+    x: Column<Advice>,
+    weight: Column<Advice>,
+    acc: Column<Advice>,
+    q_lookup: Selector,
+    q_first: Selector,
+    q_step: Selector,
+    // (tag, index, weight), tag is 1 on the weight rows and 0 on the default row
+    table_tag: TableColumn,
+    table_index: TableColumn,
+    table_weight: TableColumn,
+}
+
+/// Computes `sum(w[index_i] * x_i)` over witnessed `(index, x)` pairs against
+/// a weight table fixed at keygen.
+///
+/// | index | x   | weight    | acc                      | q_first | q_step |
+/// |-------|-----|-----------|--------------------------|---------|--------|
+/// | i_0   | x_0 | w[i_0]    | w[i_0] * x_0             | 1       | 0      |
+/// | i_1   | x_1 | w[i_1]    | acc_0 + w[i_1] * x_1     | 0       | 1      |
+///
+/// `(index, weight)` is looked up on every row, so an index outside the table
+/// fails. The lookup carries a tag so the `(0, 0, 0)` row that disabled rows
+/// look up doesn't collide with the real weight of index 0.
+#[derive(Clone, Debug)]
+pub struct SparseDotChip<F: FieldExt> {
+    config: SparseDotConfig,
+    weights: Vec<F>,
+}
+
+impl<F: FieldExt> Chip<F> for SparseDotChip<F> {
+    type Config = SparseDotConfig;
+    type Loaded = Vec<F>;
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &self.weights
+    }
+}
+
+impl<F: FieldExt> SparseDotChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, weights: Vec<F>) -> Self {
+        Self { config, weights }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        index: Column<Advice>,
+        x: Column<Advice>,
+        weight: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let q_lookup = meta.complex_selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let table_tag = meta.lookup_table_column();
+        let table_index = meta.lookup_table_column();
+        let table_weight = meta.lookup_table_column();
+
+        meta.lookup("sparse dot weight", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let index = meta.query_advice(index, Rotation::cur());
+            let weight = meta.query_advice(weight, Rotation::cur());
+            vec![
+                (q.clone(), table_tag),
+                (q.clone() * index, table_index),
+                (q * weight, table_weight),
+            ]
+        });
+
+        meta.create_gate("sparse dot first", |meta| {
+            let q = meta.query_selector(q_first);
+            let x = meta.query_advice(x, Rotation::cur());
+            let weight = meta.query_advice(weight, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - weight * x)]
+        });
+
+        meta.create_gate("sparse dot step", |meta| {
+            let q = meta.query_selector(q_step);
+            let x = meta.query_advice(x, Rotation::cur());
+            let weight = meta.query_advice(weight, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            vec![q * (acc - acc_prev - weight * x)]
+        });
+
+        SparseDotConfig {
+            index,
+            x,
+            weight,
+            acc,
+            q_lookup,
+            q_first,
+            q_step,
+            table_tag,
+            table_index,
+            table_weight,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_table(
+            || "sparse dot weights",
+            |mut table| {
+                // default row for the disabled rows
+                table.assign_cell(|| "tag", config.table_tag, 0, || Value::known(F::zero()))?;
+                table.assign_cell(
+                    || "index",
+                    config.table_index,
+                    0,
+                    || Value::known(F::zero()),
+                )?;
+                table.assign_cell(
+                    || "weight",
+                    config.table_weight,
+                    0,
+                    || Value::known(F::zero()),
+                )?;
+
+                for (i, weight) in self.loaded().iter().enumerate() {
+                    let offset = i + 1;
+                    table.assign_cell(
+                        || "tag",
+                        config.table_tag,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    table.assign_cell(
+                        || "index",
+                        config.table_index,
+                        offset,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                    table.assign_cell(
+                        || "weight",
+                        config.table_weight,
+                        offset,
+                        || Value::known(*weight),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns `sum(w[index] * x)` over `terms`. Indices may repeat.
+    pub fn dot(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!terms.is_empty(), "empty dot product");
+        let config = self.config();
+        let weights = self.loaded();
+
+        layouter.assign_region(
+            || "sparse dot",
+            |mut region| {
+                let mut acc = Value::known(F::zero());
+                let mut acc_cell = None;
+                for (offset, (index, x)) in terms.iter().enumerate() {
+                    config.q_lookup.enable(&mut region, offset)?;
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+
+                    index.copy_advice(|| "index", &mut region, config.index, offset)?;
+                    x.copy_advice(|| "x", &mut region, config.x, offset)?;
+
+                    // an index outside the table gets a zero weight and fails the lookup
+                    let weight = index.value().map(|index| {
+                        weights
+                            .get(index.get_lower_128() as usize)
+                            .copied()
+                            .unwrap_or(F::zero())
+                    });
+                    region.assign_advice(|| "weight", config.weight, offset, || weight)?;
+
+                    acc = acc + weight * x.value();
+                    acc_cell = Some(region.assign_advice(|| "acc", config.acc, offset, || acc)?);
+                }
+                Ok(acc_cell.unwrap())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    const WEIGHTS: [u64; 16] = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3];
+
+    // witnesses are (index, x) pairs flattened
+    struct SparseDotTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SparseDotTest {
+        type Config = SparseDotConfig;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let index = meta.advice_column();
+            let x = meta.advice_column();
+            let weight = meta.advice_column();
+            let acc = meta.advice_column();
+
+            for column in [index, x, acc] {
+                meta.enable_equality(column);
+            }
+
+            SparseDotChip::configure(meta, index, x, weight, acc)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = SparseDotChip::construct(config, WEIGHTS.map(F::from).to_vec());
+            chip.load_table(&mut layouter.namespace(|| "weights"))?;
+
+            let terms: Vec<_> = witnesses
+                .chunks(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect();
+            let sum = chip.dot(layouter.namespace(|| "dot"), &terms)?;
+            Ok(vec![sum])
+        }
+    }
+
+    fn run(terms: &[(u64, u64)], sum: u64) -> bool {
+        let witnesses = terms
+            .iter()
+            .flat_map(|(index, x)| [Fp::from(*index), Fp::from(*x)])
+            .collect();
+        TestHarness::auto()
+            .run(SparseDotTest, witnesses, vec![Fp::from(sum)])
+            .verify()
+            .is_ok()
+    }
+
+    fn host_dot(terms: &[(u64, u64)]) -> u64 {
+        terms
+            .iter()
+            .map(|(index, x)| WEIGHTS[*index as usize] * x)
+            .sum()
+    }
+
+    #[test]
+    fn test_sparse_dot_pass() {
+        let terms = [(0, 7), (5, 2), (11, 10), (15, 1)];
+        assert_eq!(host_dot(&terms), 3 * 7 + 9 * 2 + 8 * 10 + 3);
+        assert!(run(&terms, host_dot(&terms)));
+        assert!(!run(&terms, host_dot(&terms) + 1));
+    }
+
+    #[test]
+    fn test_sparse_dot_bad_index_fail() {
+        // Index 16 is past the end of the table, the zero weight the chip
+        // witnesses for it doesn't match any tagged row.
+        assert!(!run(&[(3, 1), (16, 1)], 1));
+    }
+
+    #[test]
+    fn test_sparse_dot_duplicate_indices_pass() {
+        let terms = [(5, 2), (5, 3), (5, 4), (1, 0)];
+        assert_eq!(host_dot(&terms), 9 * 9);
+        assert!(run(&terms, host_dot(&terms)));
+    }
+}
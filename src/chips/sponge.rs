@@ -0,0 +1,217 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+};
+
+use super::hash::HashGadget;
+use crate::utils::fold_cells;
+
+#[derive(Clone, Debug)]
+pub struct SpongeConfig<F: FieldExt> {
+    // holds the initial state and the padding constants
+    advice: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+/// A rate 1 duplex over a two to one hash. The state starts at 0 and every
+/// absorbed cell updates it to `hash(state, cell)`.
+///
+/// `squeeze` pads what was absorbed since the previous squeeze with a 1 and
+/// the number of absorbed cells, both fixed constants, and returns the state
+/// after that:
+///
+/// `squeeze(x_0, ..., x_{n-1}) = hash(hash(hash(...hash(0, x_0)..., x_{n-1}), 1), n)`
+///
+/// The pad alone already makes the padded stream determine the input, the
+/// length also binds the digest to how many cells were absorbed.
+#[derive(Clone, Debug)]
+pub struct SpongeChip<F: FieldExt, H: HashGadget<F>> {
+    config: SpongeConfig<F>,
+    hash: H,
+    state: Option<AssignedCell<F, F>>,
+    absorbed: u64,
+}
+
+impl<F: FieldExt, H: HashGadget<F>> SpongeChip<F, H> {
+    pub fn construct(config: SpongeConfig<F>, hash: H) -> Self {
+        Self {
+            config,
+            hash,
+            state: None,
+            absorbed: 0,
+        }
+    }
+
+    /// `constants` is enabled for constants, it holds the initial state and
+    /// the padding.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        constants: Column<Fixed>,
+    ) -> SpongeConfig<F> {
+        meta.enable_equality(advice);
+        meta.enable_constant(constants);
+
+        SpongeConfig {
+            advice,
+            _marker: PhantomData,
+        }
+    }
+
+    fn constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let advice = self.config.advice;
+        layouter.assign_region(
+            || "sponge constant",
+            |mut region| region.assign_advice_from_constant(|| "constant", advice, 0, value),
+        )
+    }
+
+    // the current state, the constant 0 before the first absorb
+    fn take_state(&mut self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        match self.state.take() {
+            Some(state) => Ok(state),
+            None => self.constant(layouter.namespace(|| "initial state"), F::zero()),
+        }
+    }
+
+    fn duplex(
+        &mut self,
+        mut layouter: impl Layouter<F>,
+        cell: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let state = self.take_state(layouter.namespace(|| "state"))?;
+        self.state = Some(
+            self.hash
+                .hash_two(layouter.namespace(|| "duplex"), state, cell)?,
+        );
+        Ok(())
+    }
+
+    pub fn absorb(
+        &mut self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        if cells.is_empty() {
+            return Ok(());
+        }
+        let state = self.take_state(layouter.namespace(|| "state"))?;
+
+        let hash = &self.hash;
+        let state = fold_cells(&mut layouter, state, cells, |layouter, state, cell| {
+            hash.hash_two(layouter.namespace(|| "absorb"), state, cell)
+        })?;
+        self.state = Some(state);
+        self.absorbed += cells.len() as u64;
+        Ok(())
+    }
+
+    /// Pads the cells absorbed since the previous squeeze and returns the
+    /// state. Absorbing can continue afterwards.
+    pub fn squeeze(&mut self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        let one = self.constant(layouter.namespace(|| "pad"), F::one())?;
+        self.duplex(layouter.namespace(|| "absorb pad"), one)?;
+        let length = self.constant(layouter.namespace(|| "length"), F::from(self.absorbed))?;
+        self.duplex(layouter.namespace(|| "absorb length"), length)?;
+        self.absorbed = 0;
+
+        Ok(self.state.clone().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::hash::{DummyHashChip, DummyHashConfig},
+        testing::{HarnessChip, TestHarness},
+    };
+
+    // absorbs the witnesses in two calls and squeezes once
+    struct SpongeTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SpongeTest {
+        type Config = (SpongeConfig<F>, DummyHashConfig<F>);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let left = meta.advice_column();
+            let right = meta.advice_column();
+            let out = meta.advice_column();
+            let constants = meta.fixed_column();
+
+            for column in [left, right, out] {
+                meta.enable_equality(column);
+            }
+
+            (
+                SpongeChip::<F, DummyHashChip<F>>::configure(meta, advice, constants),
+                DummyHashChip::configure(meta, left, right, out),
+            )
+        }
+
+        fn synthesize(
+            (sponge_config, hash_config): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let mut sponge =
+                SpongeChip::construct(sponge_config, DummyHashChip::construct(hash_config));
+            let (first, second) = witnesses.split_at(witnesses.len() / 2);
+            sponge.absorb(layouter.namespace(|| "absorb first"), first)?;
+            sponge.absorb(layouter.namespace(|| "absorb second"), second)?;
+            let digest = sponge.squeeze(layouter.namespace(|| "squeeze"))?;
+            Ok(vec![digest])
+        }
+    }
+
+    fn host_sponge(inputs: &[Fp]) -> Fp {
+        let state = inputs
+            .iter()
+            .fold(Fp::zero(), |state, x| DummyHashChip::hash(state, *x));
+        let state = DummyHashChip::hash(state, Fp::one());
+        DummyHashChip::hash(state, Fp::from(inputs.len() as u64))
+    }
+
+    fn inputs(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_sponge_matches_host() {
+        let harness = TestHarness::auto();
+        let cases: [&[u64]; 3] = [&[], &[5], &[1, 2, 3, 4, 5]];
+        for values in cases {
+            let inputs = inputs(values);
+            let digest = host_sponge(&inputs);
+            harness.expect_pass(SpongeTest, inputs.clone(), vec![digest]);
+            harness.expect_fail(SpongeTest, inputs, vec![digest + Fp::one()]);
+        }
+    }
+
+    #[test]
+    fn test_sponge_domain_separation() {
+        // inputs that differ by a trailing 0 or 1, the pad value
+        for (a, b) in [
+            (inputs(&[3]), inputs(&[3, 1])),
+            (inputs(&[]), inputs(&[0])),
+            (inputs(&[1, 2]), inputs(&[1, 2, 0])),
+        ] {
+            let digest = host_sponge(&a);
+            assert_ne!(digest, host_sponge(&b));
+
+            let harness = TestHarness::auto();
+            harness.expect_pass(SpongeTest, a, vec![digest]);
+            harness.expect_fail(SpongeTest, b, vec![digest]);
+        }
+    }
+}
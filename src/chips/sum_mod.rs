@@ -0,0 +1,187 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    expr::pow2_recompose,
+    linear_combination::{LinearCombinationChip, LinearCombinationConfig},
+    range_check::{RangeCheckChip, RangeCheckConfig},
+};
+
+#[derive(Clone, Debug)]
+pub struct SumModConfig<F: FieldExt, const BITS: usize, const N: usize> {
+    sum: LinearCombinationConfig<F, N>,
+    total: Column<Advice>,
+    carry: Column<Advice>,
+    result: Column<Advice>,
+    selector: Selector,
+    range_check: RangeCheckConfig<F, BITS>,
+}
+
+/// `(v_0 + ... + v_{N-1}) mod 2^BITS` for BITS-bit values.
+///
+/// The raw sum comes from a `LinearCombinationChip` with unit coefficients
+/// and is split as `sum = carry * 2^BITS + result` with both `carry` and
+/// `result` range checked to BITS bits. The sum is below `N * 2^BITS`, so the
+/// honest carry fits as long as `N <= 2^BITS`, and the split is unique. The
+/// inputs must already be known to be BITS-bit.
+#[derive(Clone, Debug)]
+pub struct SumModChip<F: FieldExt, const BITS: usize, const N: usize> {
+    config: SumModConfig<F, BITS, N>,
+}
+
+impl<F: FieldExt, const BITS: usize, const N: usize> Chip<F> for SumModChip<F, BITS, N> {
+    type Config = SumModConfig<F, BITS, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize, const N: usize> SumModChip<F, BITS, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `values` and `total` are the `LinearCombinationChip` columns, `total`
+    /// also holds the copy of the sum in the carry row.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        values: [Column<Advice>; N],
+        total: Column<Advice>,
+        carry: Column<Advice>,
+        result: Column<Advice>,
+        range_check: RangeCheckConfig<F, BITS>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 64, "operands must be at most 64 bits");
+        assert!(
+            N as u128 <= 1 << BITS,
+            "the carry of N values must fit in BITS bits"
+        );
+        let selector = meta.selector();
+
+        let sum = LinearCombinationChip::configure(meta, values, total, [F::one(); N]);
+
+        meta.create_gate("sum mod", |meta| {
+            let s = meta.query_selector(selector);
+            let total = meta.query_advice(total, Rotation::cur());
+            let carry = meta.query_advice(carry, Rotation::cur());
+            let result = meta.query_advice(result, Rotation::cur());
+            vec![s * (total - pow2_recompose(&[result, carry], BITS))]
+        });
+
+        SumModConfig {
+            sum,
+            total,
+            carry,
+            result,
+            selector,
+            range_check,
+        }
+    }
+
+    pub fn sum_mod(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>; N],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let total = LinearCombinationChip::construct(config.sum.clone())
+            .combine(layouter.namespace(|| "sum"), values)?;
+
+        let (carry, result) = layouter.assign_region(
+            || "sum mod",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                total.copy_advice(|| "copy total", &mut region, config.total, 0)?;
+
+                let total = total.value().map(|total| total.get_lower_128());
+                let carry = total.map(|total| F::from_u128(total >> BITS));
+                let result = total.map(|total| F::from_u128(total & ((1 << BITS) - 1)));
+
+                let carry = region.assign_advice(|| "carry", config.carry, 0, || carry)?;
+                let result = region.assign_advice(|| "result", config.result, 0, || result)?;
+                Ok((carry, result))
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::construct(config.range_check.clone());
+        range_check_chip.range_check(layouter.namespace(|| "range check carry"), carry)?;
+        range_check_chip
+            .range_check(layouter.namespace(|| "range check result"), result.clone())?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    struct SumModTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SumModTest {
+        type Config = SumModConfig<F, 8, 3>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let values = [(); 3].map(|_| meta.advice_column());
+            let total = meta.advice_column();
+            let carry = meta.advice_column();
+            let result = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+
+            for column in values.into_iter().chain([total, carry, result, acc]) {
+                meta.enable_equality(column);
+            }
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            SumModChip::configure(meta, values, total, carry, result, range_check)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let values = witnesses.try_into().expect("3 values");
+            let result =
+                SumModChip::construct(config).sum_mod(layouter.namespace(|| "sum mod"), &values)?;
+            Ok(vec![result])
+        }
+    }
+
+    fn values(values: [u64; 3]) -> Vec<Fp> {
+        values.map(Fp::from).to_vec()
+    }
+
+    #[test]
+    fn test_sum_mod() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(SumModTest, values([250, 10, 5]), vec![Fp::from(9)]);
+        harness.expect_pass(SumModTest, values([0, 0, 0]), vec![Fp::zero()]);
+        // no overflow
+        harness.expect_pass(SumModTest, values([1, 2, 3]), vec![Fp::from(6)]);
+        harness.expect_pass(SumModTest, values([255, 255, 255]), vec![Fp::from(253)]);
+    }
+
+    #[test]
+    fn test_sum_mod_wrong_result_fail() {
+        let harness = TestHarness::auto();
+        // the raw sum and an off by one
+        harness.expect_fail(SumModTest, values([250, 10, 5]), vec![Fp::from(265)]);
+        harness.expect_fail(SumModTest, values([250, 10, 5]), vec![Fp::from(10)]);
+    }
+}
@@ -0,0 +1,344 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    comparator::{ComparatorChip, ComparatorConfig},
+    cond_swap::CondSwapConfig,
+    sorting_network::{SortingNetworkChip, SortingNetworkConfig},
+};
+
+#[derive(Clone, Debug)]
+pub struct UniquenessConfig<F: FieldExt, const BITS: usize> {
+    comparator: ComparatorConfig<F, BITS>,
+    sorting_network: SortingNetworkConfig<F, BITS>,
+    value: Column<Advice>,
+    table: Column<Fixed>,
+    occurs: Column<Advice>,
+    count: Column<Advice>,
+    q_value: Selector,
+    q_table: Selector,
+    q_first: Selector,
+    q_step: Selector,
+}
+
+/// Proves that a batch of BITS-bit values is pairwise distinct, for
+/// instance that no nullifier is spent twice within the batch.
+///
+/// `assert_all_distinct` sorts the values with the `SortingNetworkChip` and
+/// constrains `sorted_i < sorted_{i+1}` with the `ComparatorChip` for every
+/// adjacent pair, two equal values end up next to each other. It costs
+/// `O(N log^2 N)` comparisons and the inputs must already be known to be
+/// BITS-bit.
+///
+/// `assert_all_distinct_small` is linear in `2^BITS` instead, it flags every
+/// value `t` of the domain that occurs in the batch and counts the flags:
+///
+/// | table | occurs | count             | q_table | q_first | q_step |
+/// |-------|--------|-------------------|---------|---------|--------|
+/// | 0     | o_0    | o_0               | 1       | 1       | 0      |
+/// | 1     | o_1    | count_0 + o_1     | 1       | 0       | 1      |
+/// | ...   | ...    | ...               | 1       | 0       | 1      |
+///
+/// Every batch value is looked up among the flagged `t`, every flagged `t`
+/// among the batch values, and the last count is constrained to N. The N
+/// values then cover exactly N flagged values, which they can only do if
+/// they are distinct. The lookup also range checks the values.
+#[derive(Clone, Debug)]
+pub struct UniquenessChip<F: FieldExt, const BITS: usize> {
+    config: UniquenessConfig<F, BITS>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for UniquenessChip<F, BITS> {
+    type Config = UniquenessConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> UniquenessChip<F, BITS> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `comparator`, `cond_swap`, `padding` and `constants` configure the
+    /// sorting network, `constants` also holds the expected counts. `value`
+    /// and `count` need equality.
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        comparator: ComparatorConfig<F, BITS>,
+        cond_swap: CondSwapConfig<F>,
+        padding: Column<Advice>,
+        constants: Column<Fixed>,
+        value: Column<Advice>,
+        occurs: Column<Advice>,
+        count: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(BITS <= 24, "the occurrence table has 2^BITS rows");
+        let sorting_network =
+            SortingNetworkChip::configure(meta, comparator.clone(), cond_swap, padding, constants);
+
+        let table = meta.fixed_column();
+        let q_value = meta.complex_selector();
+        let q_table = meta.complex_selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        // disabled rows on either side are `(0, 0)`, which rows outside the
+        // other side's region have too
+        meta.lookup_any("batch value flagged", |meta| {
+            let q_value = meta.query_selector(q_value);
+            let value = meta.query_advice(value, Rotation::cur());
+            let q_table = meta.query_selector(q_table);
+            let occurs = meta.query_advice(occurs, Rotation::cur());
+            let table = meta.query_fixed(table, Rotation::cur());
+            vec![
+                (q_value.clone(), q_table.clone() * occurs),
+                (q_value * value, q_table * table),
+            ]
+        });
+
+        meta.lookup_any("flagged value in batch", |meta| {
+            let q_table = meta.query_selector(q_table);
+            let occurs = meta.query_advice(occurs, Rotation::cur());
+            let table = meta.query_fixed(table, Rotation::cur());
+            let q_value = meta.query_selector(q_value);
+            let value = meta.query_advice(value, Rotation::cur());
+            let flagged = q_table * occurs;
+            vec![
+                (flagged.clone(), q_value.clone()),
+                (flagged * table, q_value * value),
+            ]
+        });
+
+        meta.create_gate("occurrence flag", |meta| {
+            let q = meta.query_selector(q_table);
+            let occurs = meta.query_advice(occurs, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![q * occurs.clone() * (one - occurs)]
+        });
+
+        meta.create_gate("occurrence count first", |meta| {
+            let q = meta.query_selector(q_first);
+            let occurs = meta.query_advice(occurs, Rotation::cur());
+            let count = meta.query_advice(count, Rotation::cur());
+            vec![q * (count - occurs)]
+        });
+
+        meta.create_gate("occurrence count step", |meta| {
+            let q = meta.query_selector(q_step);
+            let occurs = meta.query_advice(occurs, Rotation::cur());
+            let count = meta.query_advice(count, Rotation::cur());
+            let count_prev = meta.query_advice(count, Rotation::prev());
+            vec![q * (count - count_prev - occurs)]
+        });
+
+        UniquenessConfig {
+            comparator,
+            sorting_network,
+            value,
+            table,
+            occurs,
+            count,
+            q_value,
+            q_table,
+            q_first,
+            q_step,
+        }
+    }
+
+    /// Fails to verify if two of `values` are equal. The values must already
+    /// be known to be BITS-bit.
+    pub fn assert_all_distinct(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        let config = self.config();
+        let sorted = SortingNetworkChip::construct(config.sorting_network.clone())
+            .sort(layouter.namespace(|| "sort"), values)?;
+
+        let comparator = ComparatorChip::construct(config.comparator.clone());
+        for pair in sorted.windows(2) {
+            let lt = comparator.lt(
+                layouter.namespace(|| "adjacent lt"),
+                pair[0].clone(),
+                pair[1].clone(),
+            )?;
+            layouter.assign_region(
+                || "strictly increasing",
+                |mut region| region.constrain_constant(lt.cell(), F::one()),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Fails to verify if two of `values` are equal or one of them doesn't
+    /// fit in BITS bits. The lookups span the whole circuit, so a second
+    /// call could borrow flags from the first call's table: call it at most
+    /// once per circuit.
+    pub fn assert_all_distinct_small(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "batch values",
+            |mut region| {
+                for (offset, value) in values.iter().enumerate() {
+                    config.q_value.enable(&mut region, offset)?;
+                    value.copy_advice(|| "value", &mut region, config.value, offset)?;
+                }
+                Ok(())
+            },
+        )?;
+
+        let batch: Value<Vec<F>> = values.iter().map(|value| value.value().copied()).collect();
+        let count = layouter.assign_region(
+            || "occurrence table",
+            |mut region| {
+                let mut count = Value::known(F::zero());
+                let mut count_cell = None;
+                for t in 0..1u64 << BITS {
+                    let offset = t as usize;
+                    config.q_table.enable(&mut region, offset)?;
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+                    region.assign_fixed(
+                        || "t",
+                        config.table,
+                        offset,
+                        || Value::known(F::from(t)),
+                    )?;
+
+                    let occurs = batch
+                        .as_ref()
+                        .map(|batch| F::from(batch.contains(&F::from(t)) as u64));
+                    region.assign_advice(|| "occurs", config.occurs, offset, || occurs)?;
+                    count = count + occurs;
+                    count_cell =
+                        Some(region.assign_advice(|| "count", config.count, offset, || count)?);
+                }
+                Ok(count_cell.unwrap())
+            },
+        )?;
+
+        layouter.assign_region(
+            || "occurrence count",
+            |mut region| region.constrain_constant(count.cell(), F::from(values.len() as u64)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::{cond_swap::CondSwapChip, range_check::RangeCheckChip},
+        testing::{HarnessChip, TestHarness},
+    };
+
+    const BITS: usize = 4;
+
+    struct DistinctTest<const SMALL: bool>;
+
+    impl<F: FieldExt, const SMALL: bool> HarnessChip<F> for DistinctTest<SMALL> {
+        type Config = UniquenessConfig<F, BITS>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let columns = [(); 11].map(|_| meta.advice_column());
+            let [a, b, diff, out, bit, acc, out1, padding, value, occurs, count] = columns;
+            for column in [a, b, diff, out, acc, out1, value, count] {
+                meta.enable_equality(column);
+            }
+            let constants = meta.fixed_column();
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            let comparator = ComparatorChip::configure(meta, a, b, diff, out, range_check);
+            let cond_swap = CondSwapChip::configure(meta, a, b, out, diff, out1);
+            UniquenessChip::configure(
+                meta, comparator, cond_swap, padding, constants, value, occurs, count,
+            )
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = UniquenessChip::construct(config);
+            if SMALL {
+                chip.assert_all_distinct_small(layouter.namespace(|| "distinct"), &witnesses)?;
+            } else {
+                chip.assert_all_distinct(layouter.namespace(|| "distinct"), &witnesses)?;
+            }
+            Ok(vec![])
+        }
+    }
+
+    fn batch(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_distinct() {
+        let harness = TestHarness::auto();
+        let distinct = batch(&[9, 3, 12, 0, 5]);
+        harness.expect_pass(DistinctTest::<false>, distinct.clone(), vec![]);
+        harness.expect_pass(DistinctTest::<true>, distinct, vec![]);
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let harness = TestHarness::auto();
+        let duplicate = batch(&[9, 3, 12, 3, 5]);
+        harness.expect_fail(DistinctTest::<false>, duplicate.clone(), vec![]);
+        harness.expect_fail(DistinctTest::<true>, duplicate, vec![]);
+    }
+
+    #[test]
+    fn test_trivial_batches() {
+        let harness = TestHarness::auto();
+        for values in [batch(&[7]), vec![]] {
+            harness.expect_pass(DistinctTest::<false>, values.clone(), vec![]);
+            harness.expect_pass(DistinctTest::<true>, values, vec![]);
+        }
+    }
+
+    #[test]
+    fn test_range_boundary() {
+        // 2^BITS - 1 is also the sorting network's padding, 3 values pad to 4
+        let harness = TestHarness::auto();
+        let max = (1 << BITS) - 1;
+        let boundary = batch(&[max, 0, max - 1]);
+        harness.expect_pass(DistinctTest::<false>, boundary.clone(), vec![]);
+        harness.expect_pass(DistinctTest::<true>, boundary, vec![]);
+
+        let twice = batch(&[max, 0, max]);
+        harness.expect_fail(DistinctTest::<false>, twice.clone(), vec![]);
+        harness.expect_fail(DistinctTest::<true>, twice, vec![]);
+
+        // only the small variant range checks, 2^BITS isn't in its table
+        harness.expect_fail(DistinctTest::<true>, batch(&[1 << BITS, 0]), vec![]);
+    }
+}
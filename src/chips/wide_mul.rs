@@ -0,0 +1,385 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    expr::pow2_recompose,
+    range_check::{RangeCheckChip, RangeCheckConfig},
+};
+use crate::{
+    debug_assert_witness,
+    utils::{constrain_instances, fits_in_bits},
+};
+
+#[derive(Clone, Debug)]
+pub struct WideMulConfig<F: FieldExt, const N: usize> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    lo: Column<Advice>,
+    hi: Column<Advice>,
+    selector: Selector,
+    range_check: RangeCheckConfig<F, N>,
+}
+
+/// The limbs of a product, `a * b == hi * 2^N + lo`, from `WideMulChip::mul`
+/// or `ByteMulChip::mul_bytes`.
+#[derive(Clone, Debug)]
+pub struct WideMulOutput<F: FieldExt> {
+    lo: AssignedCell<F, F>,
+    hi: AssignedCell<F, F>,
+}
+
+impl<F: FieldExt> WideMulOutput<F> {
+    pub(super) fn new(lo: AssignedCell<F, F>, hi: AssignedCell<F, F>) -> Self {
+        Self { lo, hi }
+    }
+
+    pub fn lo(&self) -> &AssignedCell<F, F> {
+        &self.lo
+    }
+
+    pub fn hi(&self) -> &AssignedCell<F, F> {
+        &self.hi
+    }
+
+    /// Constrains `lo` and `hi` to the rows `start_row` and `start_row + 1`
+    /// of `instance`.
+    pub fn expose_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        instance: Column<Instance>,
+        start_row: usize,
+    ) -> Result<(), Error> {
+        constrain_instances(
+            layouter,
+            &[self.lo.clone(), self.hi.clone()],
+            instance,
+            start_row,
+        )
+    }
+}
+
+impl<F: FieldExt> From<WideMulOutput<F>> for (AssignedCell<F, F>, AssignedCell<F, F>) {
+    fn from(output: WideMulOutput<F>) -> Self {
+        (output.lo, output.hi)
+    }
+}
+
+/// Multiplies two N-bit values into a 2N-bit product split into N-bit limbs,
+/// `a * b == hi * 2^N + lo`. Both limbs are range checked so the split is
+/// unique. The operands are expected to be N-bit already (N <= 64), which
+/// already keeps the product far below the field modulus.
+#[derive(Clone, Debug)]
+pub struct WideMulChip<F: FieldExt, const N: usize> {
+    config: WideMulConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for WideMulChip<F, N> {
+    type Config = WideMulConfig<F, N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> WideMulChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        lo: Column<Advice>,
+        hi: Column<Advice>,
+        range_check: RangeCheckConfig<F, N>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(N <= 64, "operands must be at most 64 bits");
+        let selector = meta.selector();
+
+        meta.create_gate("wide mul", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let lo = meta.query_advice(lo, Rotation::cur());
+            let hi = meta.query_advice(hi, Rotation::cur());
+            vec![s * (a * b - pow2_recompose(&[lo, hi], N))]
+        });
+
+        WideMulConfig {
+            a,
+            b,
+            lo,
+            hi,
+            selector,
+            range_check,
+        }
+    }
+
+    /// Returns the limbs of `a * b`.
+    pub fn mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<WideMulOutput<F>, Error> {
+        let config = self.config();
+
+        let (lo, hi) = layouter.assign_region(
+            || "wide mul",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "copy a", &mut region, config.a, 0)?;
+                b.copy_advice(|| "copy b", &mut region, config.b, 0)?;
+                // the gate doesn't constrain the operand widths
+                for operand in [&a, &b] {
+                    debug_assert_witness!(
+                        "WideMulChip",
+                        "wide mul",
+                        operand.value(),
+                        |v: &F| fits_in_bits(v, N),
+                        format!("a {N} bit operand"),
+                    );
+                }
+
+                let product = a
+                    .value()
+                    .zip(b.value())
+                    // wraps for operands wider than 64 bits, whose limbs
+                    // then fail the gate
+                    .map(|(a, b)| a.get_lower_128().wrapping_mul(b.get_lower_128()));
+                let lo = product.map(|p| F::from_u128(p & ((1 << N) - 1)));
+                let hi = product.map(|p| F::from_u128(p >> N));
+
+                let lo = region.assign_advice(|| "lo", config.lo, 0, || lo)?;
+                let hi = region.assign_advice(|| "hi", config.hi, 0, || hi)?;
+                Ok((lo, hi))
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::construct(config.range_check.clone());
+        range_check_chip.range_check(layouter.namespace(|| "range check lo"), lo.clone())?;
+        range_check_chip.range_check(layouter.namespace(|| "range check hi"), hi.clone())?;
+
+        Ok(WideMulOutput { lo, hi })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+    use crate::dev::without_witness_checks;
+
+    const K: u32 = 6;
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt> {
+        a: Value<F>,
+        b: Value<F>,
+        // assign these limbs instead of the ones computed by the chip
+        forged_limbs: Option<(F, F)>,
+        // the instance row of `lo`, `hi` is on the next one
+        start_row: usize,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        wide_mul_config: WideMulConfig<F, 8>,
+        instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let lo = meta.advice_column();
+            let hi = meta.advice_column();
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let instance = meta.instance_column();
+
+            for column in [advice, a, b, lo, hi, acc] {
+                meta.enable_equality(column);
+            }
+            meta.enable_equality(instance);
+
+            let range_check = RangeCheckChip::configure(meta, bit, acc);
+            TestCircuitConfig {
+                advice,
+                wide_mul_config: WideMulChip::configure(meta, a, b, lo, hi, range_check),
+                instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = WideMulChip::construct(config.wide_mul_config.clone());
+
+            let (a, b) = layouter.assign_region(
+                || "load operands",
+                |mut region| {
+                    let a = region.assign_advice(|| "a", config.advice, 0, || self.a)?;
+                    let b = region.assign_advice(|| "b", config.advice, 1, || self.b)?;
+                    Ok((a, b))
+                },
+            )?;
+
+            let output = match self.forged_limbs {
+                None => chip.mul(layouter.namespace(|| "wide mul"), a, b)?,
+                Some((lo, hi)) => {
+                    let wide_mul_config = &config.wide_mul_config;
+                    let (lo, hi) = layouter.assign_region(
+                        || "forged wide mul",
+                        |mut region| {
+                            wide_mul_config.selector.enable(&mut region, 0)?;
+                            a.copy_advice(|| "copy a", &mut region, wide_mul_config.a, 0)?;
+                            b.copy_advice(|| "copy b", &mut region, wide_mul_config.b, 0)?;
+                            let lo = region.assign_advice(
+                                || "lo",
+                                wide_mul_config.lo,
+                                0,
+                                || Value::known(lo),
+                            )?;
+                            let hi = region.assign_advice(
+                                || "hi",
+                                wide_mul_config.hi,
+                                0,
+                                || Value::known(hi),
+                            )?;
+                            Ok((lo, hi))
+                        },
+                    )?;
+                    let range_check_chip =
+                        RangeCheckChip::construct(wide_mul_config.range_check.clone());
+                    range_check_chip
+                        .range_check(layouter.namespace(|| "range check lo"), lo.clone())?;
+                    range_check_chip
+                        .range_check(layouter.namespace(|| "range check hi"), hi.clone())?;
+                    WideMulOutput { lo, hi }
+                }
+            };
+
+            output.expose_public(&mut layouter, config.instance, self.start_row)
+        }
+    }
+
+    fn circuit(a: u64, b: u64, forged_limbs: Option<(u64, u64)>) -> TestCircuit<Fp> {
+        TestCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            forged_limbs: forged_limbs.map(|(lo, hi)| (Fp::from(lo), Fp::from(hi))),
+            start_row: 0,
+        }
+    }
+
+    #[test]
+    fn test_wide_mul_pass() {
+        // 200 * 200 = 40000 = 156 * 256 + 64
+        let prover = MockProver::run(
+            K,
+            &circuit(200, 200, None),
+            vec![vec![Fp::from(64), Fp::from(156)]],
+        )
+        .unwrap();
+
+        // Should success.
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_expose_public() {
+        let circuit = TestCircuit {
+            start_row: 2,
+            ..circuit(200, 200, None)
+        };
+        let limbs = [Fp::from(64), Fp::from(156)];
+        let padded = vec![Fp::zero(), Fp::zero(), limbs[0], limbs[1]];
+        assert_eq!(
+            MockProver::run(K, &circuit, vec![padded]).unwrap().verify(),
+            Ok(())
+        );
+        // the limbs are expected two rows further down
+        let prover = MockProver::run(K, &circuit, vec![limbs.to_vec()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_wide_mul_wrong_limbs_fail() {
+        let prover = MockProver::run(
+            K,
+            &circuit(200, 200, None),
+            vec![vec![Fp::from(156), Fp::from(64)]],
+        )
+        .unwrap();
+
+        // Should fail since the limbs are swapped.
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_wide_mul_unreduced_limbs_fail() {
+        // lo = 40000, hi = 0 satisfies the multiplication gate but lo is not 8 bit.
+        let prover = MockProver::run(
+            K,
+            &circuit(200, 200, Some((40000, 0))),
+            vec![vec![Fp::from(40000), Fp::zero()]],
+        )
+        .unwrap();
+
+        // Should fail the range check on lo.
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "WideMulChip")]
+    fn test_wide_operand_panics() {
+        // 256 * 1 splits into valid limbs, only the witness check catches it
+        MockProver::run(K, &circuit(256, 1, None), vec![vec![Fp::zero(), Fp::one()]]).unwrap();
+    }
+
+    #[test]
+    fn test_overflowing_operands_fail() {
+        // 2^100 * 2^100 overflows a u128, the limbs are computed from the
+        // wrapped product and fail the gate rather than panic
+        let wide = Fp::from_u128(1 << 100);
+        let circuit = TestCircuit {
+            a: Value::known(wide),
+            b: Value::known(wide),
+            ..circuit(0, 0, None)
+        };
+        let prover = without_witness_checks(|| {
+            MockProver::run(K, &circuit, vec![vec![Fp::zero(), Fp::zero()]]).unwrap()
+        });
+        assert!(prover.verify().is_err());
+    }
+}
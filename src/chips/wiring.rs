@@ -0,0 +1,255 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+#[derive(Clone, Debug)]
+pub struct WiringConfig<F: FieldExt> {
+    column: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+/// Rearranges assigned cells with copy constraints only, no gates.
+///
+/// `route` copies `cells[perm[i]]` to row `i` of one region in one column,
+/// so a permutation of n cells costs n rows and n copies.
+#[derive(Clone, Debug)]
+pub struct WiringChip<F: FieldExt> {
+    config: WiringConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for WiringChip<F> {
+    type Config = WiringConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> WiringChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        column: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        meta.enable_equality(column);
+
+        WiringConfig {
+            column,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the cells in the order `perm` gives, the output at `i` is
+    /// `cells[perm[i]]`. Fails with `Error::Synthesis` if `perm` isn't a
+    /// permutation of `0..cells.len()`.
+    pub fn route(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+        perm: &[usize],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if !is_permutation(perm, cells.len()) {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "route",
+            |mut region| {
+                perm.iter()
+                    .enumerate()
+                    .map(|(offset, from)| {
+                        cells[*from].copy_advice(|| "routed", &mut region, config.column, offset)
+                    })
+                    .collect()
+            },
+        )
+    }
+}
+
+fn is_permutation(perm: &[usize], len: usize) -> bool {
+    if perm.len() != len {
+        return false;
+    }
+    let mut seen = vec![false; len];
+    perm.iter()
+        .all(|i| *i < len && !std::mem::replace(&mut seen[*i], true))
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        dev::{record, AssignmentEvent},
+        testing::{HarnessChip, TestCircuit, TestHarness},
+    };
+
+    trait Perm {
+        const PERM: &'static [usize];
+    }
+
+    struct Identity;
+
+    impl Perm for Identity {
+        const PERM: &'static [usize] = &[0, 1, 2, 3];
+    }
+
+    struct Reverse;
+
+    impl Perm for Reverse {
+        const PERM: &'static [usize] = &[3, 2, 1, 0];
+    }
+
+    // the bit reversal order of 8 wires
+    struct BitReversal;
+
+    impl Perm for BitReversal {
+        const PERM: &'static [usize] = &[0, 4, 2, 6, 1, 5, 3, 7];
+    }
+
+    struct Duplicate;
+
+    impl Perm for Duplicate {
+        const PERM: &'static [usize] = &[0, 1, 1, 3];
+    }
+
+    struct OutOfRange;
+
+    impl Perm for OutOfRange {
+        const PERM: &'static [usize] = &[0, 1, 2, 4];
+    }
+
+    struct Short;
+
+    impl Perm for Short {
+        const PERM: &'static [usize] = &[1, 0];
+    }
+
+    struct RouteTest<P>(PhantomData<P>);
+
+    // tests take the harness chip by value
+    fn route<P>() -> RouteTest<P> {
+        RouteTest(PhantomData)
+    }
+
+    impl<F: FieldExt, P: Perm> HarnessChip<F> for RouteTest<P> {
+        type Config = WiringConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let column = meta.advice_column();
+            WiringChip::configure(meta, column)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            WiringChip::construct(config).route(layouter.namespace(|| "route"), &witnesses, P::PERM)
+        }
+    }
+
+    fn values(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_identity() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(
+            route::<Identity>(),
+            values(&[5, 6, 7, 8]),
+            values(&[5, 6, 7, 8]),
+        );
+        harness.expect_fail(
+            route::<Identity>(),
+            values(&[5, 6, 7, 8]),
+            values(&[8, 7, 6, 5]),
+        );
+    }
+
+    #[test]
+    fn test_reversal() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(
+            route::<Reverse>(),
+            values(&[5, 6, 7, 8]),
+            values(&[8, 7, 6, 5]),
+        );
+        harness.expect_fail(
+            route::<Reverse>(),
+            values(&[5, 6, 7, 8]),
+            values(&[5, 6, 7, 8]),
+        );
+
+        let input: Vec<u64> = (10..18).collect();
+        let routed: Vec<u64> = BitReversal::PERM.iter().map(|i| input[*i]).collect();
+        harness.expect_pass(route::<BitReversal>(), values(&input), values(&routed));
+    }
+
+    #[test]
+    fn test_invalid_perm_is_error() {
+        let harness = TestHarness::auto();
+        for result in [
+            harness.try_run(route::<Duplicate>(), values(&[5, 6, 7, 8]), vec![]),
+            harness.try_run(route::<OutOfRange>(), values(&[5, 6, 7, 8]), vec![]),
+        ] {
+            assert!(matches!(result, Err(Error::Synthesis)));
+        }
+    }
+
+    #[test]
+    fn test_length_mismatch_is_error() {
+        let harness = TestHarness::auto();
+        let result = harness.try_run(route::<Short>(), values(&[5, 6, 7, 8]), vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+        let result = harness.try_run(route::<Identity>(), values(&[5, 6]), vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+
+    #[test]
+    fn test_one_region_one_column() {
+        let circuit = TestCircuit::<Fp, RouteTest<Reverse>>::new(values(&[5, 6, 7, 8]));
+        let trace = record(4, &circuit).unwrap();
+
+        let routed: Vec<_> = trace
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                AssignmentEvent::Advice {
+                    region,
+                    column,
+                    row,
+                    ..
+                } if region.as_deref() == Some("route") => Some((*column, *row)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(routed.len(), 4);
+        assert!(routed.iter().all(|(column, _)| *column == routed[0].0));
+        assert!(routed.windows(2).all(|rows| rows[1].1 == rows[0].1 + 1));
+    }
+
+    #[test]
+    fn test_is_permutation() {
+        assert!(is_permutation(&[], 0));
+        assert!(is_permutation(&[2, 0, 1], 3));
+        assert!(!is_permutation(&[0, 0, 1], 3));
+        assert!(!is_permutation(&[0, 1, 3], 3));
+        assert!(!is_permutation(&[0, 1], 3));
+    }
+}
@@ -0,0 +1,524 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use crate::{
+    debug_assert_witness,
+    utils::{constrain_instances, fits_in_bits},
+};
+
+/// `(a, b, a ^ b, a & b, a | b)` for every pair of BITS bit operands, one
+/// `2^(2 * BITS)` row table for all three operations.
+#[derive(Debug, Clone)]
+pub struct BitwiseTableConfig<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    pub left: TableColumn,
+    pub right: TableColumn,
+    pub xor: TableColumn,
+    pub and: TableColumn,
+    pub or: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> BitwiseTableConfig<F, BITS> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            left: meta.lookup_table_column(),
+            right: meta.lookup_table_column(),
+            xor: meta.lookup_table_column(),
+            and: meta.lookup_table_column(),
+            or: meta.lookup_table_column(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load bitwise table",
+            |mut table| {
+                let mut offset = 0;
+                for left in 0..(1u64 << BITS) {
+                    for right in 0..(1u64 << BITS) {
+                        for (name, column, value) in [
+                            ("left value", self.left, left),
+                            ("right value", self.right, right),
+                            ("xor", self.xor, left ^ right),
+                            ("and", self.and, left & right),
+                            ("or", self.or, left | right),
+                        ] {
+                            table.assign_cell(
+                                || name,
+                                column,
+                                offset,
+                                || Value::known(F::from(value)),
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// The results of one `BitwiseChip::all_ops` row.
+#[derive(Clone, Debug)]
+pub struct BitwiseOutput<F: FieldExt> {
+    xor: AssignedCell<F, F>,
+    and: AssignedCell<F, F>,
+    or: AssignedCell<F, F>,
+}
+
+impl<F: FieldExt> BitwiseOutput<F> {
+    pub fn xor(&self) -> &AssignedCell<F, F> {
+        &self.xor
+    }
+
+    pub fn and(&self) -> &AssignedCell<F, F> {
+        &self.and
+    }
+
+    pub fn or(&self) -> &AssignedCell<F, F> {
+        &self.or
+    }
+
+    /// Constrains `xor`, `and` and `or` to the rows `start_row` to
+    /// `start_row + 2` of `instance`.
+    pub fn expose_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        instance: Column<Instance>,
+        start_row: usize,
+    ) -> Result<(), Error> {
+        constrain_instances(
+            layouter,
+            &[self.xor.clone(), self.and.clone(), self.or.clone()],
+            instance,
+            start_row,
+        )
+    }
+}
+
+impl<F: FieldExt> From<BitwiseOutput<F>>
+    for (AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>)
+{
+    fn from(output: BitwiseOutput<F>) -> Self {
+        (output.xor, output.and, output.or)
+    }
+}
+
+/// The results of `BitwiseChip::xor_and`, `all_ops` without the or.
+#[derive(Clone, Debug)]
+pub struct XorAndOutput<F: FieldExt> {
+    xor: AssignedCell<F, F>,
+    and: AssignedCell<F, F>,
+}
+
+impl<F: FieldExt> XorAndOutput<F> {
+    pub fn xor(&self) -> &AssignedCell<F, F> {
+        &self.xor
+    }
+
+    pub fn and(&self) -> &AssignedCell<F, F> {
+        &self.and
+    }
+
+    /// Constrains `xor` and `and` to the rows `start_row` and
+    /// `start_row + 1` of `instance`.
+    pub fn expose_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        instance: Column<Instance>,
+        start_row: usize,
+    ) -> Result<(), Error> {
+        constrain_instances(
+            layouter,
+            &[self.xor.clone(), self.and.clone()],
+            instance,
+            start_row,
+        )
+    }
+}
+
+impl<F: FieldExt> From<XorAndOutput<F>> for (AssignedCell<F, F>, AssignedCell<F, F>) {
+    fn from(output: XorAndOutput<F>) -> Self {
+        (output.xor, output.and)
+    }
+}
+
+/// XOR, AND and OR of the same two operands from one lookup row. Where a
+/// circuit needs both `a ^ b` and `a & b`, like the carries of an adder or
+/// the choice and majority functions of SHA-256, `xor_and` costs one row and
+/// two operand copies instead of the two rows and four copies of separate
+/// lookups.
+#[derive(Clone, Debug)]
+pub struct BitwiseChip<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    q_lookup: Selector,
+    pub table: BitwiseTableConfig<F, BITS>,
+    left_advice: Column<Advice>,
+    right_advice: Column<Advice>,
+    xor_advice: Column<Advice>,
+    and_advice: Column<Advice>,
+    or_advice: Column<Advice>,
+}
+
+impl<F: FieldExt, const BITS: usize> BitwiseChip<F, BITS> {
+    pub fn construct(meta: &mut ConstraintSystem<F>) -> Self {
+        let q_lookup = meta.complex_selector();
+        let table = BitwiseTableConfig::configure(meta);
+
+        let [left_advice, right_advice, xor_advice, and_advice, or_advice] =
+            [(); 5].map(|_| meta.advice_column());
+        for column in [left_advice, right_advice, xor_advice, and_advice, or_advice] {
+            meta.enable_equality(column);
+        }
+
+        // every column of the row in one lookup, so no result can be taken
+        // from a different table row than the others
+        meta.lookup("bitwise lookup", |meta| {
+            let q = meta.query_selector(q_lookup);
+            [
+                (left_advice, table.left),
+                (right_advice, table.right),
+                (xor_advice, table.xor),
+                (and_advice, table.and),
+                (or_advice, table.or),
+            ]
+            .map(|(advice, table)| {
+                (
+                    q.clone() * meta.query_advice(advice, Rotation::cur()),
+                    table,
+                )
+            })
+            .to_vec()
+        });
+
+        Self {
+            q_lookup,
+            table,
+            left_advice,
+            right_advice,
+            xor_advice,
+            and_advice,
+            or_advice,
+        }
+    }
+
+    /// Returns `a ^ b` and `a & b`.
+    pub fn xor_and(
+        &self,
+        layouter: impl Layouter<F>,
+        left: AssignedCell<F, F>,
+        right: AssignedCell<F, F>,
+    ) -> Result<XorAndOutput<F>, Error> {
+        let BitwiseOutput { xor, and, .. } = self.all_ops(layouter, left, right)?;
+        Ok(XorAndOutput { xor, and })
+    }
+
+    pub fn calculate_and(
+        &self,
+        layouter: impl Layouter<F>,
+        left: AssignedCell<F, F>,
+        right: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        Ok(self.all_ops(layouter, left, right)?.and)
+    }
+
+    /// Returns `a ^ b`, `a & b` and `a | b`.
+    pub fn all_ops(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left: AssignedCell<F, F>,
+        right: AssignedCell<F, F>,
+    ) -> Result<BitwiseOutput<F>, Error> {
+        layouter.assign_region(
+            || "bitwise ops",
+            |mut region| {
+                let offset = 0;
+                self.q_lookup.enable(&mut region, offset)?;
+
+                let left =
+                    left.copy_advice(|| "copy left", &mut region, self.left_advice, offset)?;
+                let right =
+                    right.copy_advice(|| "copy right", &mut region, self.right_advice, offset)?;
+                for operand in [&left, &right] {
+                    debug_assert_witness!(
+                        "BitwiseChip",
+                        "bitwise ops",
+                        operand.value(),
+                        |v: &F| fits_in_bits(v, BITS),
+                        format!("a {BITS} bit operand"),
+                    );
+                }
+
+                let operands = left
+                    .value()
+                    .zip(right.value())
+                    .map(|(left, right)| (left.get_lower_128(), right.get_lower_128()));
+                let xor = operands.map(|(left, right)| F::from_u128(left ^ right));
+                let and = operands.map(|(left, right)| F::from_u128(left & right));
+                let or = operands.map(|(left, right)| F::from_u128(left | right));
+
+                Ok(BitwiseOutput {
+                    xor: region.assign_advice(|| "xor", self.xor_advice, offset, || xor)?,
+                    and: region.assign_advice(|| "and", self.and_advice, offset, || and)?,
+                    or: region.assign_advice(|| "or", self.or_advice, offset, || or)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{circuit::Region, halo2curves::pasta::Fp};
+
+    use super::*;
+    use crate::{
+        chips::xor::XorChip,
+        dev::{record, AssignmentEvent, AssignmentTrace},
+        testing::{HarnessChip, TestCircuit, TestHarness, TestRng},
+    };
+
+    // [a, b] -> [a ^ b, a & b, a | b]
+    struct AllOpsTest;
+
+    impl<F: FieldExt> HarnessChip<F> for AllOpsTest {
+        type Config = BitwiseChip<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            BitwiseChip::construct(meta)
+        }
+
+        fn synthesize(
+            chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            chip.table
+                .load(&mut layouter.namespace(|| "bitwise table"))?;
+            let (xor, and, or) = chip
+                .all_ops(
+                    layouter.namespace(|| "all ops"),
+                    witnesses[0].clone(),
+                    witnesses[1].clone(),
+                )?
+                .into();
+            Ok(vec![xor, and, or])
+        }
+    }
+
+    // the xor of the row is honest and the and is taken from `witnesses[2]`
+    struct ForgedAndTest;
+
+    impl<F: FieldExt> HarnessChip<F> for ForgedAndTest {
+        type Config = BitwiseChip<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            BitwiseChip::construct(meta)
+        }
+
+        fn synthesize(
+            chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            chip.table
+                .load(&mut layouter.namespace(|| "bitwise table"))?;
+            layouter.assign_region(
+                || "forged and",
+                |mut region: Region<'_, F>| {
+                    chip.q_lookup.enable(&mut region, 0)?;
+                    let copy = |region: &mut Region<'_, F>, cell: &AssignedCell<F, F>, column| {
+                        cell.copy_advice(|| "copy", region, column, 0)
+                    };
+                    let left = copy(&mut region, &witnesses[0], chip.left_advice)?;
+                    let right = copy(&mut region, &witnesses[1], chip.right_advice)?;
+                    copy(&mut region, &witnesses[2], chip.and_advice)?;
+
+                    let operands = left
+                        .value()
+                        .zip(right.value())
+                        .map(|(left, right)| (left.get_lower_128(), right.get_lower_128()));
+                    let xor = operands.map(|(left, right)| F::from_u128(left ^ right));
+                    let or = operands.map(|(left, right)| F::from_u128(left | right));
+                    region.assign_advice(|| "xor", chip.xor_advice, 0, || xor)?;
+                    region.assign_advice(|| "or", chip.or_advice, 0, || or)?;
+                    Ok(())
+                },
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_random_operands() {
+        let harness = TestHarness::auto();
+        let mut rng = TestRng::new(0xa4d);
+        for _ in 0..16 {
+            // a nonzero, so the xor and the and differ
+            let (a, b) = (rng.below(15) + 1, rng.below(16));
+            let [a_f, b_f] = [a, b].map(Fp::from);
+            harness.expect_pass(
+                AllOpsTest,
+                vec![a_f, b_f],
+                vec![Fp::from(a ^ b), Fp::from(a & b), Fp::from(a | b)],
+            );
+            // the results of another operation
+            harness.expect_fail(
+                AllOpsTest,
+                vec![a_f, b_f],
+                vec![Fp::from(a & b), Fp::from(a ^ b), Fp::from(a | b)],
+            );
+        }
+    }
+
+    #[test]
+    fn test_forged_and() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(
+            ForgedAndTest,
+            vec![Fp::from(0b1100), Fp::from(0b1010), Fp::from(0b1000)],
+            vec![],
+        );
+        // the xor and the or alone are satisfied by the row, the and isn't
+        for forged in [0b0000, 0b0110, 0b1110] {
+            harness.expect_fail(
+                ForgedAndTest,
+                vec![Fp::from(0b1100), Fp::from(0b1010), Fp::from(forged)],
+                vec![],
+            );
+        }
+    }
+
+    // the 4-bit limbs of two 32-bit words, unconstrained as only the cost
+    // of what follows is measured
+    fn limbs<F: FieldExt>(
+        layouter: &mut impl Layouter<F>,
+        columns: [Column<Advice>; 2],
+        words: &[AssignedCell<F, F>],
+    ) -> Result<Vec<[AssignedCell<F, F>; 2]>, Error> {
+        layouter.assign_region(
+            || "limbs",
+            |mut region| {
+                (0..8)
+                    .map(|i| {
+                        let [left, right] = [0, 1].map(|j| {
+                            let limb = words[j]
+                                .value()
+                                .map(|w| F::from_u128((w.get_lower_128() >> (4 * i)) & 0xf));
+                            region.assign_advice(|| "limb", columns[j], i, || limb)
+                        });
+                        Ok([left?, right?])
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    // a ^ b and a & b per limb, as the first step of an adder
+    struct FusedWorkload;
+
+    impl<F: FieldExt> HarnessChip<F> for FusedWorkload {
+        type Config = (BitwiseChip<F, 4>, [Column<Advice>; 2]);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let columns = [(); 2].map(|_| meta.advice_column());
+            for column in columns {
+                meta.enable_equality(column);
+            }
+            (BitwiseChip::construct(meta), columns)
+        }
+
+        fn synthesize(
+            (chip, columns): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            chip.table
+                .load(&mut layouter.namespace(|| "bitwise table"))?;
+            for [a, b] in limbs(&mut layouter, columns, &witnesses)? {
+                chip.xor_and(layouter.namespace(|| "xor and"), a, b)?;
+            }
+            Ok(vec![])
+        }
+    }
+
+    // the same with an XorChip and a separate lookup for the and
+    struct SeparateWorkload;
+
+    impl<F: FieldExt> HarnessChip<F> for SeparateWorkload {
+        type Config = (XorChip<F, 4>, BitwiseChip<F, 4>, [Column<Advice>; 2]);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let columns = [(); 2].map(|_| meta.advice_column());
+            for column in columns {
+                meta.enable_equality(column);
+            }
+            (
+                XorChip::construct(meta),
+                BitwiseChip::construct(meta),
+                columns,
+            )
+        }
+
+        fn synthesize(
+            (xor_chip, and_chip, columns): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            and_chip
+                .table
+                .load(&mut layouter.namespace(|| "bitwise table"))?;
+            for [a, b] in limbs(&mut layouter, columns, &witnesses)? {
+                xor_chip.calculate_xor(layouter.namespace(|| "xor"), a.clone(), b.clone())?;
+                and_chip.calculate_and(layouter.namespace(|| "and"), a, b)?;
+            }
+            Ok(vec![])
+        }
+    }
+
+    // (lookup rows, copy constraints)
+    fn cost(trace: &AssignmentTrace<Fp>) -> (usize, usize) {
+        let rows = trace
+            .events
+            .iter()
+            .filter(|event| matches!(event, AssignmentEvent::Selector { .. }))
+            .count();
+        let copies = trace
+            .events
+            .iter()
+            .filter(|event| matches!(event, AssignmentEvent::Copy { .. }))
+            .count();
+        (rows, copies)
+    }
+
+    #[test]
+    fn test_cost() {
+        let words = vec![Fp::from(0x6162_6380), Fp::from(0xdead_beef)];
+        TestHarness::auto().expect_pass(FusedWorkload, words.clone(), vec![]);
+        TestHarness::auto().expect_pass(SeparateWorkload, words.clone(), vec![]);
+
+        let fused = record(10, &TestCircuit::<Fp, FusedWorkload>::new(words.clone())).unwrap();
+        let separate = record(10, &TestCircuit::<Fp, SeparateWorkload>::new(words)).unwrap();
+
+        // 8 limbs, one row and two operand copies each against two rows
+        // and four copies
+        assert_eq!(cost(&fused), (8, 16));
+        assert_eq!(cost(&separate), (16, 32));
+    }
+}
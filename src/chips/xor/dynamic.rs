@@ -0,0 +1,297 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+mod table;
+pub use table::*;
+
+// Same as XorChip but looks up into an advice table filled with `load_dynamic`, useful
+// when the operands only come from a small known domain. Table size is domain.len()**2
+// instead of BITS**4.
+// The domain is pinned in fixed columns, so it is fixed at keygen like a fixed table and
+// is not a runtime input: a different domain needs a new verifying key.
+#[derive(Clone, Debug)]
+pub struct DynamicXorChip<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    q_lookup: Selector,
+    pub xor_table: DynamicXorTableConfig<F, BITS>,
+    left_advice: Column<Advice>,
+    right_advice: Column<Advice>,
+    result_advice: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> DynamicXorChip<F, BITS> {
+    pub fn construct(meta: &mut ConstraintSystem<F>) -> Self {
+        let q_lookup = meta.complex_selector();
+
+        // creates the tagged advice table columns
+        let xor_table = DynamicXorTableConfig::configure(meta);
+
+        let left_advice = meta.advice_column();
+        let right_advice = meta.advice_column();
+        let result_advice = meta.advice_column();
+
+        meta.enable_equality(left_advice);
+        meta.enable_equality(right_advice);
+        meta.enable_equality(result_advice);
+
+        meta.lookup_any("dynamic xor lookup", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let left_cur = meta.query_advice(left_advice, Rotation::cur());
+            let right_cur = meta.query_advice(right_advice, Rotation::cur());
+            let result_cur = meta.query_advice(result_advice, Rotation::cur());
+
+            let tag = meta.query_advice(xor_table.tag, Rotation::cur());
+            let left = meta.query_advice(xor_table.left, Rotation::cur());
+            let right = meta.query_advice(xor_table.right, Rotation::cur());
+            let result = meta.query_advice(xor_table.result, Rotation::cur());
+
+            // an enabled lookup only matches tagged rows
+            vec![
+                (q.clone(), tag),
+                (q.clone() * left_cur, left),
+                (q.clone() * right_cur, right),
+                (q * result_cur, result),
+            ]
+        });
+
+        Self {
+            q_lookup,
+            xor_table,
+            left_advice,
+            right_advice,
+            result_advice,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn calculate_xor(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left_cell_advice: AssignedCell<F, F>,
+        right_cell_advice: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "Assign value for dynamic lookup XOR check",
+            |mut region| {
+                let offset = 0;
+
+                self.q_lookup.enable(&mut region, offset)?;
+
+                // Copy advice to lookup columns, this also checks the inputs are in the domain
+                let left_cell = left_cell_advice.copy_advice(
+                    || "copy left",
+                    &mut region,
+                    self.left_advice,
+                    offset,
+                )?;
+                let right_cell = right_cell_advice.copy_advice(
+                    || "copy right",
+                    &mut region,
+                    self.right_advice,
+                    offset,
+                )?;
+
+                let xor_result = left_cell
+                    .value()
+                    .zip(right_cell.value())
+                    .map(|(left, right)| left.get_lower_128() ^ right.get_lower_128())
+                    .map(F::from_u128);
+                region.assign_advice(|| "result", self.result_advice, offset, || xor_result)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+
+    const K: u32 = 8;
+
+    const DIGITS: [u64; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    #[derive(Default)]
+    struct TestCircuit<F: FieldExt, const BITS: usize> {
+        left: F,
+        right: F,
+        // assigns an extra tagged row (left, right, left ^ right) outside of the loader
+        forge_row: bool,
+        // loads `left` in place of the last digit in the advice rows of the loader
+        forge_domain: bool,
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt, const BITS: usize> {
+        advice: Column<Advice>,
+        xor_chip: DynamicXorChip<F, BITS>,
+        result_instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt, const BITS: usize> Circuit<F> for TestCircuit<F, BITS> {
+        type Config = TestCircuitConfig<F, BITS>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                forge_row: self.forge_row,
+                forge_domain: self.forge_domain,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let result_instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(result_instance);
+
+            TestCircuitConfig {
+                advice,
+                xor_chip: DynamicXorChip::construct(meta),
+                result_instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let xor_chip = config.xor_chip.clone();
+
+            {
+                let mut layouter = layouter.namespace(|| "dynamic xor table");
+                if self.forge_domain {
+                    let mut witness = DIGITS;
+                    witness[DIGITS.len() - 1] = self.left.get_lower_128() as u64;
+                    xor_chip
+                        .xor_table
+                        .load_rows(&mut layouter, &DIGITS, &witness)?;
+                } else {
+                    xor_chip.xor_table.load_dynamic(&mut layouter, &DIGITS)?;
+                }
+            }
+
+            if self.forge_row {
+                let table = &xor_chip.xor_table;
+                let result = F::from_u128(self.left.get_lower_128() ^ self.right.get_lower_128());
+                layouter.assign_region(
+                    || "forged table row",
+                    |mut region| {
+                        region.assign_advice(|| "tag", table.tag, 0, || Value::known(F::one()))?;
+                        region.assign_advice(
+                            || "left",
+                            table.left,
+                            0,
+                            || Value::known(self.left),
+                        )?;
+                        region.assign_advice(
+                            || "right",
+                            table.right,
+                            0,
+                            || Value::known(self.right),
+                        )?;
+                        region.assign_advice(|| "result", table.result, 0, || Value::known(result))
+                    },
+                )?;
+            }
+
+            let (left_cell, right_cell) = layouter.assign_region(
+                || "load advice",
+                |mut region| {
+                    let left = region.assign_advice(
+                        || "left",
+                        config.advice,
+                        0,
+                        || Value::known(self.left),
+                    )?;
+                    let right = region.assign_advice(
+                        || "right",
+                        config.advice,
+                        1,
+                        || Value::known(self.right),
+                    )?;
+                    Ok((left, right))
+                },
+            )?;
+
+            let result_cell =
+                xor_chip.calculate_xor(layouter.namespace(|| "xor"), left_cell, right_cell)?;
+
+            layouter.constrain_instance(result_cell.cell(), config.result_instance, 0)
+        }
+    }
+
+    fn run(left: u64, right: u64, result: u64, forge_row: bool) -> MockProver<Fp> {
+        run_circuit(
+            TestCircuit::<Fp, 4> {
+                left: Fp::from(left),
+                right: Fp::from(right),
+                forge_row,
+                forge_domain: false,
+            },
+            result,
+        )
+    }
+
+    fn run_circuit(circuit: TestCircuit<Fp, 4>, result: u64) -> MockProver<Fp> {
+        MockProver::run(K, &circuit, vec![vec![Fp::from(result)]]).unwrap()
+    }
+
+    #[test]
+    fn test_digit_domain_pass() {
+        // Should success.
+        assert_eq!(run(3, 9, 10, false).verify(), Ok(()));
+        assert_eq!(run(7, 7, 0, false).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_wrong_result_fail() {
+        assert!(run(3, 9, 11, false).verify().is_err());
+    }
+
+    #[test]
+    fn test_operand_outside_domain_fail() {
+        // 12 is a valid 4 bit value but it was not loaded into the table.
+        assert!(run(12, 1, 13, false).verify().is_err());
+    }
+
+    #[test]
+    fn test_malicious_tagged_row_fail() {
+        // The forged row makes the lookup itself succeed, but setting the tag
+        // outside of the loader region violates the tag gate.
+        assert!(run(12, 1, 13, true).verify().is_err());
+    }
+
+    #[test]
+    fn test_loaded_row_outside_domain_fail() {
+        // The loader rows hold 12 in place of 9, every row is still a tagged
+        // valid xor so the lookup of (12, 1) succeeds, only the binding to
+        // the fixed domain columns rejects it.
+        let circuit = TestCircuit::<Fp, 4> {
+            left: Fp::from(12),
+            right: Fp::from(1),
+            forge_row: false,
+            forge_domain: true,
+        };
+        assert!(run_circuit(circuit, 13).verify().is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+// Table size is domain.len()**2, in tagged advice columns instead of a full fixed table.
+// Rows are only valid lookup targets if `tag` is set, and `tag` is forced to equal `q_load`
+// on every row so only the loader region can set it. The loader rows additionally carry the
+// bit decomposition of left and right, which pins `result` to the actual xor. The domain
+// itself lives in fixed columns that left and right are constrained to, so the loaded
+// pairs are part of the verifying key and the prover can't load any other pair.
+
+#[derive(Debug, Clone)]
+pub struct DynamicXorTableConfig<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    q_load: Selector,
+    pub tag: Column<Advice>,
+    pub left: Column<Advice>,
+    pub right: Column<Advice>,
+    pub result: Column<Advice>,
+    left_bits: [Column<Advice>; BITS],
+    right_bits: [Column<Advice>; BITS],
+    domain_left: Column<Fixed>,
+    domain_right: Column<Fixed>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> DynamicXorTableConfig<F, BITS> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        // complex since it is used outside of a plain multiplication below
+        let q_load = meta.complex_selector();
+
+        let tag = meta.advice_column();
+        let left = meta.advice_column();
+        let right = meta.advice_column();
+        let result = meta.advice_column();
+        let left_bits = [(); BITS].map(|_| meta.advice_column());
+        let right_bits = [(); BITS].map(|_| meta.advice_column());
+        let domain_left = meta.fixed_column();
+        let domain_right = meta.fixed_column();
+
+        // not gated by a selector, so it applies to every row of the table columns
+        meta.create_gate("dynamic xor table tag", |meta| {
+            let q = meta.query_selector(q_load);
+            let tag = meta.query_advice(tag, Rotation::cur());
+            vec![tag - q]
+        });
+
+        meta.create_gate("dynamic xor table row", |meta| {
+            let q = meta.query_selector(q_load);
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+
+            let mut constraints = vec![];
+            let mut left_sum = Expression::Constant(F::zero());
+            let mut right_sum = Expression::Constant(F::zero());
+            let mut result_sum = Expression::Constant(F::zero());
+            for i in 0..BITS {
+                let l = meta.query_advice(left_bits[i], Rotation::cur());
+                let r = meta.query_advice(right_bits[i], Rotation::cur());
+                let coeff = Expression::Constant(F::from(1u64 << i));
+
+                constraints.push(q.clone() * l.clone() * (one.clone() - l.clone()));
+                constraints.push(q.clone() * r.clone() * (one.clone() - r.clone()));
+
+                left_sum = left_sum + l.clone() * coeff.clone();
+                right_sum = right_sum + r.clone() * coeff.clone();
+                // l ^ r == l + r - 2 * l * r for bits
+                result_sum = result_sum + (l.clone() + r.clone() - two.clone() * l * r) * coeff;
+            }
+
+            let left = meta.query_advice(left, Rotation::cur());
+            let right = meta.query_advice(right, Rotation::cur());
+            let result = meta.query_advice(result, Rotation::cur());
+            let domain_left = meta.query_fixed(domain_left, Rotation::cur());
+            let domain_right = meta.query_fixed(domain_right, Rotation::cur());
+            constraints.push(q.clone() * (left.clone() - domain_left));
+            constraints.push(q.clone() * (right.clone() - domain_right));
+            constraints.push(q.clone() * (left - left_sum));
+            constraints.push(q.clone() * (right - right_sum));
+            constraints.push(q * (result - result_sum));
+            constraints
+        });
+
+        Self {
+            q_load,
+            tag,
+            left,
+            right,
+            result,
+            left_bits,
+            right_bits,
+            domain_left,
+            domain_right,
+            _marker: PhantomData,
+        }
+    }
+
+    // fill the xor of all pairs of values in the domain
+    pub fn load_dynamic(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        domain: &[u64],
+    ) -> Result<(), Error> {
+        self.assign_rows(layouter, domain, domain)
+    }
+
+    // `load_dynamic` with the advice rows of `witness` against the fixed
+    // columns of `domain`, for tests that forge the loaded pairs
+    #[cfg(test)]
+    pub(super) fn load_rows(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        domain: &[u64],
+        witness: &[u64],
+    ) -> Result<(), Error> {
+        if domain.len() != witness.len() {
+            return Err(Error::Synthesis);
+        }
+        self.assign_rows(layouter, domain, witness)
+    }
+
+    fn assign_rows(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        domain: &[u64],
+        witness: &[u64],
+    ) -> Result<(), Error> {
+        if witness.iter().any(|v| *v >= 1 << BITS) {
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "load dynamic xor table",
+            |mut region| {
+                let mut offset = 0;
+                for (left_domain, left_value) in domain.iter().zip(witness) {
+                    for (right_domain, right_value) in domain.iter().zip(witness) {
+                        self.q_load.enable(&mut region, offset)?;
+                        region.assign_fixed(
+                            || "domain left",
+                            self.domain_left,
+                            offset,
+                            || Value::known(F::from(*left_domain)),
+                        )?;
+                        region.assign_fixed(
+                            || "domain right",
+                            self.domain_right,
+                            offset,
+                            || Value::known(F::from(*right_domain)),
+                        )?;
+                        region.assign_advice(
+                            || "tag",
+                            self.tag,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                        region.assign_advice(
+                            || "left value",
+                            self.left,
+                            offset,
+                            || Value::known(F::from(*left_value)),
+                        )?;
+                        region.assign_advice(
+                            || "right value",
+                            self.right,
+                            offset,
+                            || Value::known(F::from(*right_value)),
+                        )?;
+                        region.assign_advice(
+                            || "output",
+                            self.result,
+                            offset,
+                            || Value::known(F::from(left_value ^ right_value)),
+                        )?;
+                        for i in 0..BITS {
+                            region.assign_advice(
+                                || "left bit",
+                                self.left_bits[i],
+                                offset,
+                                || Value::known(F::from((left_value >> i) & 1)),
+                            )?;
+                            region.assign_advice(
+                                || "right bit",
+                                self.right_bits[i],
+                                offset,
+                                || Value::known(F::from((right_value >> i) & 1)),
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
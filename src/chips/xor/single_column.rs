@@ -0,0 +1,159 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::XorTableConfig;
+use crate::{debug_assert_witness, utils::fits_in_bits};
+
+// Same lookup as XorChip but with left, right and result in three consecutive
+// rows of one advice column, the lookup queries cur, next and next + 1.
+// Trades two advice columns for two extra rows per XOR, for circuits that are
+// short on columns rather than rows.
+#[derive(Clone, Debug)]
+pub struct SingleColumnXorChip<F, const BITS: usize>
+where
+    F: FieldExt,
+{
+    q_lookup: Selector,
+    pub xor_table: XorTableConfig<F, BITS>,
+    advice: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> SingleColumnXorChip<F, BITS> {
+    pub fn construct(meta: &mut ConstraintSystem<F>) -> Self {
+        let q_lookup = meta.complex_selector();
+        let xor_table = XorTableConfig::configure(meta);
+
+        let advice = meta.advice_column();
+        meta.enable_equality(advice);
+
+        meta.lookup("single column lookup", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let left = meta.query_advice(advice, Rotation::cur());
+            let right = meta.query_advice(advice, Rotation::next());
+            let result = meta.query_advice(advice, Rotation(2));
+
+            vec![
+                (q.clone() * left, xor_table.left),
+                (q.clone() * right, xor_table.right),
+                (q * result, xor_table.result),
+            ]
+        });
+
+        Self {
+            q_lookup,
+            xor_table,
+            advice,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn calculate_xor(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left_cell_advice: AssignedCell<F, F>,
+        right_cell_advice: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "Assign value for single column XOR check",
+            |mut region| {
+                self.q_lookup.enable(&mut region, 0)?;
+
+                let left_cell =
+                    left_cell_advice.copy_advice(|| "copy left", &mut region, self.advice, 0)?;
+                let right_cell =
+                    right_cell_advice.copy_advice(|| "copy right", &mut region, self.advice, 1)?;
+                for operand in [&left_cell, &right_cell] {
+                    debug_assert_witness!(
+                        "SingleColumnXorChip",
+                        "Assign value for single column XOR check",
+                        operand.value(),
+                        |v: &F| fits_in_bits(v, BITS),
+                        format!("a {BITS} bit operand"),
+                    );
+                }
+
+                let xor_result = left_cell
+                    .value()
+                    .zip(right_cell.value())
+                    .map(|(left, right)| left.get_lower_128() ^ right.get_lower_128())
+                    .map(F::from_u128);
+                region.assign_advice(|| "result", self.advice, 2, || xor_result)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::xor::XorChip,
+        testing::{HarnessChip, TestHarness},
+    };
+
+    struct SingleColumnXorTest;
+
+    impl<F: FieldExt> HarnessChip<F> for SingleColumnXorTest {
+        type Config = SingleColumnXorChip<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            SingleColumnXorChip::construct(meta)
+        }
+
+        fn synthesize(
+            xor_chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            let result = xor_chip.calculate_xor(
+                layouter.namespace(|| "xor"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![result])
+        }
+    }
+
+    #[test]
+    fn test_single_column_xor() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(
+            SingleColumnXorTest,
+            vec![Fp::from(3), Fp::from(1)],
+            vec![Fp::from(2)],
+        );
+        harness.expect_pass(
+            SingleColumnXorTest,
+            vec![Fp::from(0b1010), Fp::from(0b0110)],
+            vec![Fp::from(0b1100)],
+        );
+        harness.expect_fail(
+            SingleColumnXorTest,
+            vec![Fp::from(3), Fp::from(3)],
+            vec![Fp::from(3)],
+        );
+    }
+
+    #[test]
+    fn test_column_count() {
+        let mut single = ConstraintSystem::<Fp>::default();
+        SingleColumnXorChip::<Fp, 4>::construct(&mut single);
+        let mut standard = ConstraintSystem::<Fp>::default();
+        XorChip::<Fp, 4>::construct(&mut standard);
+
+        assert_eq!(single.num_advice_columns(), 1);
+        assert_eq!(standard.num_advice_columns(), 3);
+    }
+}
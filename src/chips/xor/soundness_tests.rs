@@ -0,0 +1,193 @@
+//! Adversarial circuits against `XorChip`. They lay out the lookup region by
+//! hand instead of going through `calculate_xor`, so every witness the chip
+//! would compute honestly can be replaced by a forged one.
+
+use halo2_proofs::{
+    circuit::{SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::{keygen_vk, Circuit, Instance},
+    poly::{commitment::ParamsProver, ipa::commitment::ParamsIPA},
+};
+
+use super::*;
+
+const K: u32 = 9;
+
+#[derive(Clone)]
+struct ForgedCircuit {
+    // (left, right, result) per lookup row
+    rows: Vec<(u64, u64, u64)>,
+    enable_selector: bool,
+    // copy the result of the first row into every other row
+    share_result: bool,
+}
+
+#[derive(Clone, Debug)]
+struct ForgedCircuitConfig<F: FieldExt> {
+    xor_chip: XorChip<F, 4>,
+    result_instance: Column<Instance>,
+}
+
+impl<F: FieldExt> Circuit<F> for ForgedCircuit {
+    type Config = ForgedCircuitConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // the flags change the circuit shape so keep everything
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let result_instance = meta.instance_column();
+        meta.enable_equality(result_instance);
+
+        ForgedCircuitConfig {
+            xor_chip: XorChip::construct(meta),
+            result_instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let xor_chip = config.xor_chip;
+        xor_chip
+            .xor_table
+            .load(&mut layouter.namespace(|| "xor table"))?;
+
+        let result_cell = layouter.assign_region(
+            || "forged lookups",
+            |mut region| {
+                let mut first_result: Option<AssignedCell<F, F>> = None;
+                for (offset, (left, right, result)) in self.rows.iter().enumerate() {
+                    if self.enable_selector {
+                        xor_chip.q_lookup.enable(&mut region, offset)?;
+                    }
+                    region.assign_advice(
+                        || "left",
+                        xor_chip.left_advice,
+                        offset,
+                        || Value::known(F::from(*left)),
+                    )?;
+                    region.assign_advice(
+                        || "right",
+                        xor_chip.right_advice,
+                        offset,
+                        || Value::known(F::from(*right)),
+                    )?;
+
+                    match &first_result {
+                        Some(first) if self.share_result => {
+                            first.copy_advice(
+                                || "shared result",
+                                &mut region,
+                                xor_chip.result_advice,
+                                offset,
+                            )?;
+                        }
+                        _ => {
+                            let cell = region.assign_advice(
+                                || "result",
+                                xor_chip.result_advice,
+                                offset,
+                                || Value::known(F::from(*result)),
+                            )?;
+                            first_result.get_or_insert(cell);
+                        }
+                    }
+                }
+                Ok(first_result.expect("at least one row"))
+            },
+        )?;
+
+        layouter.constrain_instance(result_cell.cell(), config.result_instance, 0)
+    }
+}
+
+fn run(circuit: &ForgedCircuit) -> MockProver<Fp> {
+    let claimed = circuit.rows[0].2;
+    MockProver::run(K, circuit, vec![vec![Fp::from(claimed)]]).unwrap()
+}
+
+fn pinned_vk(circuit: &ForgedCircuit) -> String {
+    let params = ParamsIPA::<EqAffine>::new(K);
+    let vk = keygen_vk(&params, circuit).unwrap();
+    format!("{:?}", vk.pinned())
+}
+
+#[test]
+fn test_selector_off_is_a_different_circuit() {
+    // With q_lookup off every input expression is q * expr = 0, and (0, 0, 0)
+    // is a table row, so any result is accepted...
+    let forged = ForgedCircuit {
+        rows: vec![(3, 1, 7)],
+        enable_selector: false,
+        share_result: false,
+    };
+    assert_eq!(run(&forged).verify(), Ok(()));
+
+    // ...but the selector is a fixed column, so leaving it off changes the
+    // verifying key. A proof of the forged circuit doesn't verify against the
+    // circuit that enables the lookup.
+    let honest = ForgedCircuit {
+        enable_selector: true,
+        ..forged.clone()
+    };
+    assert_ne!(pinned_vk(&forged), pinned_vk(&honest));
+}
+
+#[test]
+fn test_witness_outside_table_fail() {
+    for rows in [
+        // left doesn't fit in 4 bits, 16 ^ 1 = 17 isn't a table result either
+        vec![(16, 1, 17)],
+        // right doesn't fit in 4 bits
+        vec![(1, 16, 17)],
+        // valid operands, result off by 2^4
+        vec![(3, 1, 2 + 16)],
+    ] {
+        let forged = ForgedCircuit {
+            rows: rows.clone(),
+            enable_selector: true,
+            share_result: false,
+        };
+        assert!(run(&forged).verify().is_err(), "{rows:?} must not verify");
+    }
+
+    let prover = MockProver::run(
+        K,
+        &ForgedCircuit {
+            rows: vec![(0, 0, 0)],
+            enable_selector: true,
+            share_result: false,
+        },
+        vec![vec![-Fp::one()]],
+    )
+    .unwrap();
+    // The zero row is a genuine table entry, but its result can't be claimed
+    // to be -1.
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn test_shared_result_inconsistent_operands_fail() {
+    // 3 ^ 1 = 2 but 3 ^ 5 = 6, the copied result can't satisfy both rows.
+    let forged = ForgedCircuit {
+        rows: vec![(3, 1, 2), (3, 5, 2)],
+        enable_selector: true,
+        share_result: true,
+    };
+    assert!(run(&forged).verify().is_err());
+
+    // Reusing the result for operands with the same xor is fine.
+    let consistent = ForgedCircuit {
+        rows: vec![(3, 1, 2), (1, 3, 2), (7, 5, 2)],
+        enable_selector: true,
+        share_result: true,
+    };
+    assert_eq!(run(&consistent).verify(), Ok(()));
+}
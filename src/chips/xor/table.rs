@@ -6,6 +6,8 @@ use halo2_proofs::{
     plonk::{ConstraintSystem, Error, TableColumn},
 };
 
+use crate::{error::ChipError, utils::MIN_RESERVED_ROWS};
+
 // Table size is BITS**4
 // use BITS as 4 so that there are 16 unique elements and table size is 256
 
@@ -34,34 +36,55 @@ impl<F: FieldExt, const BITS: usize> XorTableConfig<F, BITS> {
         }
     }
 
+    /// Fails with `ChipError::TableTooLarge` if the `2^(2 * BITS)` table
+    /// rows don't fit in a circuit of `2^k` rows. Only the rows halo2
+    /// reserves for every circuit are accounted for, a circuit querying
+    /// advice at many rotations reserves a few more.
+    pub fn check_fits(k: u32) -> Result<(), ChipError> {
+        let rows_needed = 1 << (2 * BITS);
+        let rows_available = (1usize << k).saturating_sub(MIN_RESERVED_ROWS);
+        if rows_needed > rows_available {
+            return Err(ChipError::TableTooLarge {
+                k,
+                rows_needed,
+                rows_available,
+            });
+        }
+        Ok(())
+    }
+
+    /// `load` for a circuit of `2^k` rows, checking the table fits before
+    /// assigning any of it.
+    pub fn load_checked(&self, layouter: &mut impl Layouter<F>, k: u32) -> Result<(), ChipError> {
+        Self::check_fits(k)?;
+        Ok(self.load(layouter)?)
+    }
+
+    /// Every `(left, right, left ^ right)` row `load` assigns, in order.
+    pub fn table_values() -> Vec<(F, F, F)> {
+        (0..1u64 << BITS)
+            .flat_map(|left| {
+                (0..1u64 << BITS)
+                    .map(move |right| (F::from(left), F::from(right), F::from(left ^ right)))
+            })
+            .collect()
+    }
+
     // fill all possibilities of 4 BIT string XORs
     pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_table(
             || "load xor table",
             |mut table| {
-                let mut offset = 0;
-                for left_value in 0..(1 << BITS) {
-                    for right_value in 0..(1 << BITS) {
-                        table.assign_cell(
-                            || "left value",
-                            self.left,
-                            offset,
-                            || Value::known(F::from(left_value as u64)),
-                        )?;
-                        table.assign_cell(
-                            || "right value",
-                            self.right,
-                            offset,
-                            || Value::known(F::from(right_value as u64)),
-                        )?;
-                        table.assign_cell(
-                            || "output",
-                            self.result,
-                            offset,
-                            || Value::known(F::from((left_value ^ right_value) as u64)),
-                        )?;
-                        offset += 1;
-                    }
+                for (offset, (left, right, result)) in Self::table_values().into_iter().enumerate()
+                {
+                    table.assign_cell(|| "left value", self.left, offset, || Value::known(left))?;
+                    table.assign_cell(
+                        || "right value",
+                        self.right,
+                        offset,
+                        || Value::known(right),
+                    )?;
+                    table.assign_cell(|| "output", self.result, offset, || Value::known(result))?;
                 }
 
                 Ok(())
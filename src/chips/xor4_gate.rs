@@ -0,0 +1,181 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct Xor4GateConfig<F: FieldExt> {
+    inputs: [Column<Advice>; 4],
+    out: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// `a ^ b ^ c ^ d` of four bits in one row and without a lookup table, as
+/// the multilinear polynomial
+///
+/// `a + b + c + d - 2 * (ab + ac + ad + bc + bd + cd)
+///     + 4 * (abc + abd + acd + bcd) - 8 * abcd`
+///
+/// which is `(1 - (1 - 2a)(1 - 2b)(1 - 2c)(1 - 2d)) / 2` expanded. It only
+/// agrees with xor on bits, so the gate also constrains every input to be
+/// boolean. The output needs no check of its own, the polynomial is 0 or 1
+/// on bits. The gate has degree 5 with the selector.
+#[derive(Clone, Debug)]
+pub struct Xor4GateChip<F: FieldExt> {
+    config: Xor4GateConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for Xor4GateChip<F> {
+    type Config = Xor4GateConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Xor4GateChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// The inputs are copied in, their columns need equality.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        inputs: [Column<Advice>; 4],
+        out: Column<Advice>,
+    ) -> <Self as Chip<F>>::Config {
+        let selector = meta.selector();
+
+        meta.create_gate("xor4", |meta| {
+            let s = meta.query_selector(selector);
+            let [a, b, c, d] = inputs.map(|column| meta.query_advice(column, Rotation::cur()));
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            let constant = |c: u64| Expression::Constant(F::from(c));
+
+            let linear = a.clone() + b.clone() + c.clone() + d.clone();
+            let quadratic = a.clone() * b.clone()
+                + a.clone() * c.clone()
+                + a.clone() * d.clone()
+                + b.clone() * c.clone()
+                + b.clone() * d.clone()
+                + c.clone() * d.clone();
+            let cubic = a.clone() * b.clone() * c.clone()
+                + a.clone() * b.clone() * d.clone()
+                + a.clone() * c.clone() * d.clone()
+                + b.clone() * c.clone() * d.clone();
+            let quartic = a.clone() * b.clone() * c.clone() * d.clone();
+            let xor =
+                linear - quadratic * constant(2) + cubic * constant(4) - quartic * constant(8);
+
+            let mut constraints: Vec<_> = [a, b, c, d]
+                .into_iter()
+                .map(|x| s.clone() * x.clone() * (one.clone() - x))
+                .collect();
+            constraints.push(s * (xor - out));
+            constraints
+        });
+
+        Xor4GateConfig {
+            inputs,
+            out,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn xor4(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: [AssignedCell<F, F>; 4],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "xor4",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                for (input, column) in inputs.iter().zip(config.inputs) {
+                    input.copy_advice(|| "input", &mut region, column, 0)?;
+                }
+
+                // the parity of the sum, for bits
+                let out = inputs
+                    .iter()
+                    .fold(Value::known(0u64), |acc, input| {
+                        acc.zip(input.value())
+                            .map(|(acc, bit)| acc ^ bit.get_lower_128() as u64)
+                    })
+                    .map(F::from);
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    struct Xor4Test;
+
+    impl<F: FieldExt> HarnessChip<F> for Xor4Test {
+        type Config = Xor4GateConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let inputs = [(); 4].map(|_| meta.advice_column());
+            for column in inputs {
+                meta.enable_equality(column);
+            }
+            let out = meta.advice_column();
+            meta.enable_equality(out);
+            Xor4GateChip::configure(meta, inputs, out)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let inputs = witnesses.try_into().expect("4 inputs");
+            let out =
+                Xor4GateChip::construct(config).xor4(layouter.namespace(|| "xor4"), inputs)?;
+            Ok(vec![out])
+        }
+    }
+
+    #[test]
+    fn test_truth_table() {
+        let harness = TestHarness::auto();
+        for row in 0..16u64 {
+            let bits: Vec<_> = (0..4).map(|i| (row >> i) & 1).collect();
+            let xor = bits.iter().fold(0, |acc, bit| acc ^ bit);
+            let inputs: Vec<_> = bits.into_iter().map(Fp::from).collect();
+
+            harness.expect_pass(Xor4Test, inputs.clone(), vec![Fp::from(xor)]);
+            harness.expect_fail(Xor4Test, inputs, vec![Fp::from(1 - xor)]);
+        }
+    }
+
+    #[test]
+    fn test_non_boolean_input() {
+        // the polynomial is -1 here, a 2 is rejected whatever the output
+        let harness = TestHarness::auto();
+        let inputs = [2, 1, 0, 0].map(Fp::from).to_vec();
+        for out in [-Fp::one(), Fp::zero(), Fp::one()] {
+            harness.expect_fail(Xor4Test, inputs.clone(), vec![out]);
+        }
+    }
+}
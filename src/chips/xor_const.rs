@@ -0,0 +1,235 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use crate::error::ChipError;
+
+// Table size is 2^BITS, one row per input
+#[derive(Clone, Debug)]
+pub struct XorConstTableConfig<F: FieldExt, const BITS: usize, const CONSTANT: u64> {
+    pub input: TableColumn,
+    pub result: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize, const CONSTANT: u64> XorConstTableConfig<F, BITS, CONSTANT> {
+    /// Fails with `ChipError::ConstantOutOfRange` if CONSTANT is wider than
+    /// BITS, before any column is allocated.
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Result<Self, ChipError> {
+        ChipError::check_constant_bits("XorConstTableConfig", CONSTANT, BITS)?;
+        Ok(Self {
+            input: meta.lookup_table_column(),
+            result: meta.lookup_table_column(),
+            _marker: PhantomData,
+        })
+    }
+
+    // fill (input, input ^ CONSTANT) for every BITS bit input
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load xor const table",
+            |mut table| {
+                for input in 0..(1u64 << BITS) {
+                    let offset = input as usize;
+                    table.assign_cell(
+                        || "input",
+                        self.input,
+                        offset,
+                        || Value::known(F::from(input)),
+                    )?;
+                    table.assign_cell(
+                        || "result",
+                        self.result,
+                        offset,
+                        || Value::known(F::from(input ^ CONSTANT)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// XOR with a constant fixed at keygen. The table only has the `2^BITS` rows
+/// `(input, input ^ CONSTANT)` instead of the `2^(2 * BITS)` rows of
+/// `XorChip`, and the row takes one operand.
+///
+/// Disabled rows look up `(0, CONSTANT)`, the table row of input 0, rather
+/// than `(0, 0)` which isn't in the table unless `CONSTANT` is 0.
+#[derive(Clone, Debug)]
+pub struct XorConstChip<F: FieldExt, const BITS: usize, const CONSTANT: u64> {
+    q_lookup: Selector,
+    pub table: XorConstTableConfig<F, BITS, CONSTANT>,
+    input: Column<Advice>,
+    result: Column<Advice>,
+}
+
+impl<F: FieldExt, const BITS: usize, const CONSTANT: u64> XorConstChip<F, BITS, CONSTANT> {
+    /// Fails like `XorConstTableConfig::configure`.
+    pub fn construct(meta: &mut ConstraintSystem<F>) -> Result<Self, ChipError> {
+        let table = XorConstTableConfig::configure(meta)?;
+        let q_lookup = meta.complex_selector();
+
+        let input = meta.advice_column();
+        let result = meta.advice_column();
+        meta.enable_equality(input);
+        meta.enable_equality(result);
+
+        meta.lookup("xor const", |meta| {
+            let q = meta.query_selector(q_lookup);
+            let not_q = Expression::Constant(F::one()) - q.clone();
+            let input = meta.query_advice(input, Rotation::cur());
+            let result = meta.query_advice(result, Rotation::cur());
+            vec![
+                (q.clone() * input, table.input),
+                (
+                    q * result + not_q * Expression::Constant(F::from(CONSTANT)),
+                    table.result,
+                ),
+            ]
+        });
+
+        Ok(Self {
+            q_lookup,
+            table,
+            input,
+            result,
+        })
+    }
+
+    pub fn calculate_xor_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "xor constant",
+            |mut region| {
+                self.q_lookup.enable(&mut region, 0)?;
+                input.copy_advice(|| "input", &mut region, self.input, 0)?;
+                let result = input
+                    .value()
+                    .map(|v| F::from_u128(v.get_lower_128() ^ u128::from(CONSTANT)));
+                region.assign_advice(|| "result", self.result, 0, || result)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::{
+        chips::xor::XorChip,
+        dev::{record, AssignmentEvent},
+        testing::{HarnessChip, TestCircuit, TestHarness},
+    };
+
+    struct XorConstTest;
+
+    impl<F: FieldExt> HarnessChip<F> for XorConstTest {
+        type Config = XorConstChip<F, 4, 0xa>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            XorConstChip::construct(meta).expect("0xa fits in 4 bits")
+        }
+
+        fn synthesize(
+            chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            chip.table
+                .load(&mut layouter.namespace(|| "xor const table"))?;
+            let result = chip.calculate_xor_constant(
+                layouter.namespace(|| "xor constant"),
+                witnesses[0].clone(),
+            )?;
+            Ok(vec![result])
+        }
+    }
+
+    struct XorTest;
+
+    impl<F: FieldExt> HarnessChip<F> for XorTest {
+        type Config = XorChip<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            XorChip::construct(meta)
+        }
+
+        fn synthesize(
+            xor_chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            let result = xor_chip.calculate_xor(
+                layouter.namespace(|| "xor"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![result])
+        }
+    }
+
+    // The tables are the only fixed cells these circuits assign, padding
+    // aside.
+    fn table_cells<C: HarnessChip<Fp>>(witnesses: Vec<Fp>) -> usize {
+        let trace = record(9, &TestCircuit::<Fp, C>::new(witnesses)).unwrap();
+        trace
+            .events
+            .iter()
+            .filter(|event| matches!(event, AssignmentEvent::Fixed { .. }))
+            .count()
+    }
+
+    #[test]
+    fn test_xor_constant() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(XorConstTest, vec![Fp::from(3)], vec![Fp::from(9)]);
+        harness.expect_pass(XorConstTest, vec![Fp::from(0)], vec![Fp::from(0xa)]);
+    }
+
+    #[test]
+    fn test_xor_constant_wrong_result_fail() {
+        let harness = TestHarness::auto();
+        harness.expect_fail(XorConstTest, vec![Fp::from(3)], vec![Fp::from(3)]);
+        harness.expect_fail(XorConstTest, vec![Fp::from(0)], vec![Fp::from(0)]);
+        // outside the table
+        harness.expect_fail(XorConstTest, vec![Fp::from(16)], vec![Fp::from(16 ^ 0xa)]);
+    }
+
+    #[test]
+    fn test_constant_out_of_range() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        assert!(matches!(
+            XorConstChip::<Fp, 4, 0x10>::construct(&mut meta),
+            Err(ChipError::ConstantOutOfRange {
+                chip: "XorConstTableConfig",
+                value: 0x10,
+                max: 0xf
+            })
+        ));
+        assert!(XorConstChip::<Fp, 4, 0xf>::construct(&mut meta).is_ok());
+    }
+
+    #[test]
+    fn test_table_size() {
+        // 16 rows of 2 columns against 256 rows of 3
+        assert_eq!(table_cells::<XorConstTest>(vec![Fp::from(3)]), 16 * 2);
+        assert_eq!(
+            table_cells::<XorTest>(vec![Fp::from(3), Fp::from(1)]),
+            256 * 3
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    halo2curves::FieldExt,
+    plonk::Error,
+};
+
+use super::xor::XorChip;
+
+// Reduces N leaves to their xor with a balanced binary tree of XorChip lookups,
+// log2(N) levels deep instead of N - 1 when xoring the leaves one after another.
+// Every level reuses the same XorChip, so there is only one xor table.
+#[derive(Clone, Debug)]
+pub struct XorTreeChip<F, const N: usize, const BITS: usize>
+where
+    F: FieldExt,
+{
+    pub xor_chip: XorChip<F, BITS>,
+}
+
+impl<F: FieldExt, const N: usize, const BITS: usize> XorTreeChip<F, N, BITS> {
+    pub fn construct(xor_chip: XorChip<F, BITS>) -> Self {
+        assert!(N.is_power_of_two(), "number of leaves must be a power of 2");
+        Self { xor_chip }
+    }
+
+    pub fn reduce(
+        &self,
+        mut layouter: impl Layouter<F>,
+        leaves: [AssignedCell<F, F>; N],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let mut level = leaves.to_vec();
+        let mut depth = 0;
+        while level.len() > 1 {
+            let mut next_level = Vec::with_capacity(level.len() / 2);
+            for (i, pair) in level.chunks(2).enumerate() {
+                next_level.push(self.xor_chip.calculate_xor(
+                    layouter.namespace(|| format!("xor level {depth} node {i}")),
+                    pair[0].clone(),
+                    pair[1].clone(),
+                )?);
+            }
+            level = next_level;
+            depth += 1;
+        }
+
+        Ok(level.remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Instance},
+    };
+
+    use super::*;
+
+    const K: u32 = 9;
+
+    struct TestCircuit<F: FieldExt, const N: usize> {
+        leaves: [F; N],
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt, const N: usize> {
+        advice: Column<Advice>,
+        xor_tree_chip: XorTreeChip<F, N, 4>,
+        result_instance: Column<Instance>,
+    }
+
+    impl<F: FieldExt, const N: usize> Circuit<F> for TestCircuit<F, N> {
+        type Config = TestCircuitConfig<F, N>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                leaves: [F::zero(); N],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let result_instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(result_instance);
+
+            TestCircuitConfig {
+                advice,
+                xor_tree_chip: XorTreeChip::construct(XorChip::construct(meta)),
+                result_instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let xor_tree_chip = config.xor_tree_chip.clone();
+
+            xor_tree_chip
+                .xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+
+            let leaves = layouter.assign_region(
+                || "load leaves",
+                |mut region| {
+                    let mut cells = Vec::with_capacity(N);
+                    for (offset, leaf) in self.leaves.iter().enumerate() {
+                        cells.push(region.assign_advice(
+                            || "leaf",
+                            config.advice,
+                            offset,
+                            || Value::known(*leaf),
+                        )?);
+                    }
+                    Ok(cells)
+                },
+            )?;
+
+            let root = xor_tree_chip.reduce(
+                layouter.namespace(|| "xor tree"),
+                leaves.try_into().expect("N leaves are assigned"),
+            )?;
+
+            layouter.constrain_instance(root.cell(), config.result_instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_xor_tree_matches_sequential() {
+        let leaves = [1u64, 2, 3, 4, 5, 6, 7, 8];
+        let expected = leaves.iter().fold(0, |acc, leaf| acc ^ leaf);
+
+        let prover = MockProver::run(
+            K,
+            &TestCircuit::<Fp, 8> {
+                leaves: leaves.map(Fp::from),
+            },
+            vec![vec![Fp::from(expected)]],
+        )
+        .unwrap();
+
+        // Should success.
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_xor_tree_wrong_root_fail() {
+        let leaves = [1u64, 2, 3, 4, 5, 6, 7, 8];
+        let expected = leaves.iter().fold(0, |acc, leaf| acc ^ leaf);
+
+        let prover = MockProver::run(
+            K,
+            &TestCircuit::<Fp, 8> {
+                leaves: leaves.map(Fp::from),
+            },
+            vec![vec![Fp::from(expected ^ 1)]],
+        )
+        .unwrap();
+
+        // Should error.
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_xor_tree_single_leaf() {
+        let prover = MockProver::run(
+            K,
+            &TestCircuit::<Fp, 1> {
+                leaves: [Fp::from(11)],
+            },
+            vec![vec![Fp::from(11)]],
+        )
+        .unwrap();
+
+        // The root of a single leaf tree is the leaf itself.
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
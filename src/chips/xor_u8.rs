@@ -0,0 +1,79 @@
+use halo2_proofs::halo2curves::FieldExt;
+
+use super::xor::{XorChip, XorTableConfig};
+
+/// `XorChip` over bytes. The table has `2^16` rows, so the circuit needs
+/// `k >= K_REQUIRED`.
+pub type XorU8Chip<F> = XorChip<F, 8>;
+
+impl<F: FieldExt> XorChip<F, 8> {
+    /// The smallest k the byte table fits in, with the rows halo2 reserves.
+    pub const K_REQUIRED: u32 = 17;
+
+    /// Checks a circuit of `2^k` rows has room for the byte table, to be
+    /// called before loading it.
+    pub fn load_and_verify_k(k: u32) -> Result<(), &'static str> {
+        XorTableConfig::<F, 8>::check_fits(k)
+            .map_err(|_| "k is too small for the 2^16 row byte xor table, use K_REQUIRED")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{AssignedCell, Layouter},
+        halo2curves::pasta::Fp,
+        plonk::{ConstraintSystem, Error},
+    };
+
+    use super::*;
+    use crate::{
+        dev::run_auto,
+        testing::{HarnessChip, TestCircuit},
+    };
+
+    struct XorU8Test;
+
+    impl<F: FieldExt> HarnessChip<F> for XorU8Test {
+        type Config = XorU8Chip<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            XorU8Chip::construct(meta)
+        }
+
+        fn synthesize(
+            xor_chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            let result = xor_chip.calculate_xor(
+                layouter.namespace(|| "xor"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![result])
+        }
+    }
+
+    #[test]
+    fn test_load_and_verify_k() {
+        assert_eq!(
+            XorU8Chip::<Fp>::load_and_verify_k(XorU8Chip::<Fp>::K_REQUIRED),
+            Ok(())
+        );
+        assert!(XorU8Chip::<Fp>::load_and_verify_k(16).is_err());
+        // enough for the 4 bit table only
+        assert!(XorU8Chip::<Fp>::load_and_verify_k(9).is_err());
+    }
+
+    #[test]
+    fn test_k_required_is_smallest() {
+        let circuit = TestCircuit::<Fp, XorU8Test>::new(vec![Fp::from(0xa5), Fp::from(0x0f)]);
+        let (k, prover) = run_auto(&circuit, vec![vec![Fp::from(0xa5 ^ 0x0f)]]).unwrap();
+        assert_eq!(k, XorU8Chip::<Fp>::K_REQUIRED);
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -0,0 +1,276 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::is_zero::is_zero_constraints;
+use crate::error::ChipError;
+
+#[derive(Clone, Debug)]
+pub struct ZeroCountConfig<F: FieldExt> {
+    value: Column<Advice>,
+    value_inverse: Column<Advice>,
+    is_zero: Column<Advice>,
+    count: Column<Advice>,
+    q_is_zero: Selector,
+    q_first: Selector,
+    q_step: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// Counts the zero cells of a slice. Every input gets its own row holding the
+/// is zero gate of `IsZeroChip`, and a running sum of the is zero bits:
+///
+/// | value | value_inverse | is_zero | count               | q_first | q_step |
+/// |-------|---------------|---------|---------------------|---------|--------|
+/// | v_0   | 1/v_0 or 0    | z_0     | z_0                 | 1       | 0      |
+/// | v_1   | 1/v_1 or 0    | z_1     | count_0 + z_1       | 0       | 1      |
+/// | ...   | ...           | ...     | ...                 | 0       | 1      |
+///
+/// `q_is_zero` is enabled on every row.
+#[derive(Clone, Debug)]
+pub struct ZeroCountChip<F: FieldExt> {
+    config: ZeroCountConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for ZeroCountChip<F> {
+    type Config = ZeroCountConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> ZeroCountChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { config }
+    }
+
+    /// `constants` is enabled for constants, it holds the expected counts of
+    /// `assert_zero_count`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        value_inverse: Column<Advice>,
+        is_zero: Column<Advice>,
+        count: Column<Advice>,
+        constants: Column<Fixed>,
+    ) -> <Self as Chip<F>>::Config {
+        let q_is_zero = meta.selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        meta.enable_constant(constants);
+
+        meta.create_gate("zero count is zero", |meta| {
+            let q = meta.query_selector(q_is_zero);
+            let v = meta.query_advice(value, Rotation::cur());
+            let v_inv = meta.query_advice(value_inverse, Rotation::cur());
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+            is_zero_constraints(v, v_inv, is_zero)
+                .into_iter()
+                .map(|constraint| q.clone() * constraint)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("zero count first", |meta| {
+            let q = meta.query_selector(q_first);
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+            let count = meta.query_advice(count, Rotation::cur());
+            vec![q * (count - is_zero)]
+        });
+
+        meta.create_gate("zero count step", |meta| {
+            let q = meta.query_selector(q_step);
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+            let count = meta.query_advice(count, Rotation::cur());
+            let count_prev = meta.query_advice(count, Rotation::prev());
+            vec![q * (count - count_prev - is_zero)]
+        });
+
+        ZeroCountConfig {
+            value,
+            value_inverse,
+            is_zero,
+            count,
+            q_is_zero,
+            q_first,
+            q_step,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of zero cells in `cells`.
+    pub fn count_zeros(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!cells.is_empty(), "nothing to count");
+        let config = self.config();
+
+        layouter.assign_region(
+            || "zero count",
+            |mut region| {
+                let mut count = Value::known(F::zero());
+                let mut count_cell = None;
+                for (offset, cell) in cells.iter().enumerate() {
+                    config.q_is_zero.enable(&mut region, offset)?;
+                    if offset == 0 {
+                        config.q_first.enable(&mut region, offset)?;
+                    } else {
+                        config.q_step.enable(&mut region, offset)?;
+                    }
+
+                    cell.copy_advice(|| "value", &mut region, config.value, offset)?;
+                    let value_inverse = cell.value().map(|v| v.invert().unwrap_or(F::zero()));
+                    region.assign_advice(
+                        || "value inverse",
+                        config.value_inverse,
+                        offset,
+                        || value_inverse,
+                    )?;
+
+                    let is_zero = cell.value().map(|v| F::from(*v == F::zero()));
+                    region.assign_advice(|| "is zero", config.is_zero, offset, || is_zero)?;
+
+                    count = count + is_zero;
+                    count_cell =
+                        Some(region.assign_advice(|| "count", config.count, offset, || count)?);
+                }
+                Ok(count_cell.unwrap())
+            },
+        )
+    }
+
+    /// Constrains exactly `expected` of `cells` to be zero. No assignment
+    /// has more zeros than cells, so `expected > cells.len()` is rejected
+    /// with `ChipError::ConstantOutOfRange` before anything is assigned.
+    pub fn assert_zero_count(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+        expected: u64,
+    ) -> Result<(), ChipError> {
+        ChipError::check_constant("ZeroCountChip", expected, cells.len() as u64)?;
+        let count = self.count_zeros(layouter.namespace(|| "count zeros"), cells)?;
+        Ok(layouter.assign_region(
+            || "expected zero count",
+            |mut region| region.constrain_constant(count.cell(), F::from(expected)),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{arithmetic::Field, halo2curves::pasta::Fp};
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::testing::{HarnessChip, TestHarness};
+
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> ZeroCountConfig<F> {
+        let value = meta.advice_column();
+        let value_inverse = meta.advice_column();
+        let is_zero = meta.advice_column();
+        let count = meta.advice_column();
+        let constants = meta.fixed_column();
+
+        meta.enable_equality(value);
+        meta.enable_equality(count);
+
+        ZeroCountChip::configure(meta, value, value_inverse, is_zero, count, constants)
+    }
+
+    struct CountZerosTest;
+
+    impl<F: FieldExt> HarnessChip<F> for CountZerosTest {
+        type Config = ZeroCountConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = ZeroCountChip::construct(config);
+            let count = chip.count_zeros(layouter.namespace(|| "count zeros"), &witnesses)?;
+            Ok(vec![count])
+        }
+    }
+
+    // expects exactly 2 zeros
+    struct AssertTwoZerosTest;
+
+    impl<F: FieldExt> HarnessChip<F> for AssertTwoZerosTest {
+        type Config = ZeroCountConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = ZeroCountChip::construct(config);
+            chip.assert_zero_count(layouter.namespace(|| "zero count"), &witnesses, 2)?;
+            Ok(vec![])
+        }
+    }
+
+    // random values are nonzero with overwhelming probability
+    fn inputs(zeros: &[usize]) -> Vec<Fp> {
+        (0..4)
+            .map(|i| {
+                if zeros.contains(&i) {
+                    Fp::zero()
+                } else {
+                    Fp::random(OsRng)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_count_zeros_pass() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(CountZerosTest, inputs(&[]), vec![Fp::from(0)]);
+        harness.expect_pass(CountZerosTest, inputs(&[2]), vec![Fp::from(1)]);
+        harness.expect_pass(CountZerosTest, inputs(&[0, 1, 2, 3]), vec![Fp::from(4)]);
+    }
+
+    #[test]
+    fn test_count_zeros_wrong_count_fail() {
+        let harness = TestHarness::auto();
+        harness.expect_fail(CountZerosTest, inputs(&[2]), vec![Fp::from(0)]);
+        harness.expect_fail(CountZerosTest, inputs(&[0, 3]), vec![Fp::from(1)]);
+    }
+
+    #[test]
+    fn test_assert_zero_count() {
+        let harness = TestHarness::auto();
+        harness.expect_pass(AssertTwoZerosTest, inputs(&[1, 3]), vec![]);
+        harness.expect_fail(AssertTwoZerosTest, inputs(&[1]), vec![]);
+        harness.expect_fail(AssertTwoZerosTest, inputs(&[0, 1, 2]), vec![]);
+    }
+
+    #[test]
+    fn test_expected_above_cells() {
+        // 2 zeros of a single cell
+        let result = TestHarness::auto().try_run(AssertTwoZerosTest, vec![Fp::zero()], vec![]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
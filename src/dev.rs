@@ -0,0 +1,820 @@
+use std::{cell::Cell, fmt};
+
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    halo2curves::FieldExt,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Challenge, Circuit, Column, ConstraintSystem, Error,
+        Fixed, FloorPlanner, Instance, Selector,
+    },
+};
+
+/// The largest k `run_auto` tries.
+pub const DEFAULT_MAX_K: u32 = 20;
+
+// leaves room for the blinding rows of any circuit in this crate
+const MIN_K: u32 = 4;
+
+/// Runs `MockProver` at the smallest k the circuit fits in, between 4 and
+/// `DEFAULT_MAX_K`, and returns that k with the prover.
+pub fn run_auto<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+) -> Result<(u32, MockProver<F>), Error> {
+    run_auto_with_max_k(circuit, instances, DEFAULT_MAX_K).map_err(Error::from)
+}
+
+/// Why `run_auto_with_max_k` found no k.
+#[derive(Debug)]
+pub enum RunAutoError {
+    /// The circuit didn't fit in `2^max_k` rows, the last k tried.
+    GaveUp { max_k: u32 },
+    /// Synthesis failed for another reason than the size of the circuit.
+    Synthesis(Error),
+}
+
+impl fmt::Display for RunAutoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GaveUp { max_k } => {
+                write!(
+                    f,
+                    "the circuit doesn't fit in 2^k rows for any k <= {max_k}"
+                )
+            }
+            Self::Synthesis(err) => write!(f, "synthesis failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RunAutoError {}
+
+impl From<RunAutoError> for Error {
+    fn from(err: RunAutoError) -> Self {
+        match err {
+            RunAutoError::GaveUp { max_k } => Error::NotEnoughRowsAvailable { current_k: max_k },
+            RunAutoError::Synthesis(err) => err,
+        }
+    }
+}
+
+/// `run_auto` capped at `max_k`. A circuit that doesn't fit in `2^max_k`
+/// rows fails with `RunAutoError::GaveUp`, any other synthesis error is
+/// returned in `RunAutoError::Synthesis`. `run_auto` turns them back into
+/// `Error::NotEnoughRowsAvailable { current_k: max_k }` and the error.
+pub fn run_auto_with_max_k<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+    max_k: u32,
+) -> Result<(u32, MockProver<F>), RunAutoError> {
+    for k in MIN_K..=max_k {
+        match MockProver::run(k, circuit, instances.clone()) {
+            Ok(prover) => return Ok((k, prover)),
+            Err(Error::NotEnoughRowsAvailable { .. } | Error::InstanceTooLarge) => continue,
+            Err(err) => return Err(RunAutoError::Synthesis(err)),
+        }
+    }
+    Err(RunAutoError::GaveUp { max_k })
+}
+
+// marks the label in a region name, failures show region names in quotes
+const LABEL_START: &str = "[label: ";
+const LABEL_END: char = ']';
+
+/// The region name of a labeled chip call, e.g. `IsZeroChip::is_zero_labeled`.
+/// `label` shouldn't contain `]`.
+pub fn labeled(name: &str, label: &str) -> String {
+    format!("{name} {LABEL_START}{label}{LABEL_END}")
+}
+
+/// Verifies `prover` and returns one line per failure, prefixed with the label
+/// of the region it is in, or `unlabeled` if it isn't in a labeled one.
+/// Empty if the circuit is satisfied.
+pub fn explain_failures<F: FieldExt>(prover: &MockProver<F>) -> Vec<String> {
+    let failures = match prover.verify() {
+        Ok(()) => return vec![],
+        Err(failures) => failures,
+    };
+    failures
+        .iter()
+        .map(|failure| {
+            // `metadata::Region` doesn't expose its name, its display does
+            let failure = failure.to_string();
+            let label = failure_label(&failure).unwrap_or("unlabeled");
+            format!("{label}: {failure}")
+        })
+        .collect()
+}
+
+fn failure_label(failure: &str) -> Option<&str> {
+    let start = failure.find(LABEL_START)? + LABEL_START.len();
+    let len = failure[start..].find(LABEL_END)?;
+    Some(&failure[start..start + len])
+}
+
+thread_local! {
+    static WITNESS_CHECKS: Cell<bool> = Cell::new(true);
+}
+
+/// Whether `debug_assert_witness!` checks on this thread.
+#[doc(hidden)]
+pub fn witness_checks_enabled() -> bool {
+    WITNESS_CHECKS.with(Cell::get)
+}
+
+/// Runs `f` with `debug_assert_witness!` disabled on this thread, for tests
+/// that feed a chip a bad witness on purpose to exercise its constraints.
+pub fn without_witness_checks<R>(f: impl FnOnce() -> R) -> R {
+    let enabled = WITNESS_CHECKS.with(|checks| checks.replace(false));
+    let result = f();
+    WITNESS_CHECKS.with(|checks| checks.set(enabled));
+    result
+}
+
+/// Checks a chip precondition on a witness while it is generated, so a
+/// caller passing e.g. an operand that is too wide panics at the chip
+/// rather than surfacing as a constraint failure somewhere else. Unknown
+/// values aren't checked and the whole check is compiled out without
+/// `debug_assertions`.
+///
+/// ```ignore
+/// debug_assert_witness!(
+///     "SelectChip",
+///     "select",
+///     cond.value(),
+///     |cond: &F| *cond == F::zero() || *cond == F::one(),
+///     "boolean"
+/// );
+/// ```
+#[macro_export]
+macro_rules! debug_assert_witness {
+    ($chip:expr, $region:expr, $value:expr, $check:expr, $requirement:expr $(,)?) => {
+        if cfg!(debug_assertions) && $crate::dev::witness_checks_enabled() {
+            $value.map(|value| {
+                assert!(
+                    ($check)(value),
+                    "{}: region {:?}: witness {:?} is not {}",
+                    $chip,
+                    $region,
+                    value,
+                    $requirement,
+                );
+            });
+        }
+    };
+}
+
+/// One call the floor planner made while synthesizing a circuit. Rows are
+/// absolute, `region` is the innermost region the call was made in and
+/// values are `None` when they weren't known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssignmentEvent<F: FieldExt> {
+    Advice {
+        region: Option<String>,
+        column: Column<Advice>,
+        row: usize,
+        value: Option<F>,
+    },
+    Fixed {
+        region: Option<String>,
+        column: Column<Fixed>,
+        row: usize,
+        value: Option<F>,
+    },
+    /// `fill_from_row`, used for the padding of lookup tables.
+    Fill {
+        column: Column<Fixed>,
+        from_row: usize,
+        value: Option<F>,
+    },
+    Copy {
+        left: (Column<Any>, usize),
+        right: (Column<Any>, usize),
+    },
+    Selector {
+        region: Option<String>,
+        // `Selector` doesn't expose its index, its debug output is the
+        // stable identity
+        selector: String,
+        row: usize,
+    },
+}
+
+/// An event present at `index` in one trace but different or missing in the
+/// other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference<F: FieldExt> {
+    pub index: usize,
+    pub left: Option<AssignmentEvent<F>>,
+    pub right: Option<AssignmentEvent<F>>,
+}
+
+/// Every assignment made while synthesizing a circuit, in call order.
+///
+/// `namespaces` has the path of every namespace entered, outermost name
+/// first, in the order they were entered.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssignmentTrace<F: FieldExt> {
+    pub events: Vec<AssignmentEvent<F>>,
+    pub namespaces: Vec<Vec<String>>,
+}
+
+impl<F: FieldExt> AssignmentTrace<F> {
+    /// Compares the traces event by event.
+    pub fn diff(&self, other: &Self) -> Vec<Difference<F>> {
+        (0..self.events.len().max(other.events.len()))
+            .filter_map(|index| {
+                let left = self.events.get(index);
+                let right = other.events.get(index);
+                (left != right).then(|| Difference {
+                    index,
+                    left: left.cloned(),
+                    right: right.cloned(),
+                })
+            })
+            .collect()
+    }
+
+    /// The namespace paths entered more than once, that is sibling
+    /// namespaces under one name, each once in the order first seen. Region
+    /// names in failures are only as clear as the namespaces around them,
+    /// so a test can insist on this being empty.
+    pub fn duplicate_namespaces(&self) -> Vec<Vec<String>> {
+        let mut duplicates: Vec<Vec<String>> = vec![];
+        for (i, path) in self.namespaces.iter().enumerate() {
+            if self.namespaces[..i].contains(path) && !duplicates.contains(path) {
+                duplicates.push(path.clone());
+            }
+        }
+        duplicates
+    }
+}
+
+/// Synthesizes `circuit` with its floor planner against an `Assignment` that
+/// only records the calls. Instance values and challenges are unknown to the
+/// recorder, so values derived from them are recorded as `None`.
+pub fn record<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+) -> Result<AssignmentTrace<F>, Error> {
+    let mut cs = ConstraintSystem::default();
+    let config = C::configure(&mut cs);
+    let constants = cs.constants().clone();
+
+    let mut recorder = Recorder {
+        n: 1 << k,
+        region: None,
+        namespace: vec![],
+        trace: AssignmentTrace::default(),
+    };
+    C::FloorPlanner::synthesize(&mut recorder, circuit, config, constants)?;
+    Ok(recorder.trace)
+}
+
+/// Where synthesizing a circuit without witnesses, as keygen does, and with
+/// them, as proving does, first lays out something different.
+#[derive(Debug)]
+pub enum ShapeMismatch<F: FieldExt> {
+    /// One of the two syntheses failed.
+    Synthesis { with_witnesses: bool, error: Error },
+    /// The first differing event, `left` without witnesses and `right` with
+    /// them. Advice values are left out of the comparison.
+    Event(Difference<F>),
+}
+
+impl<F: FieldExt> fmt::Display for ShapeMismatch<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Synthesis {
+                with_witnesses,
+                error,
+            } => {
+                let witnesses = if *with_witnesses { "with" } else { "without" };
+                write!(f, "synthesis {witnesses} witnesses failed: {error}")
+            }
+            Self::Event(difference) => write!(
+                f,
+                "event {} is {} without witnesses but {} with them",
+                difference.index,
+                describe(&difference.left),
+                describe(&difference.right),
+            ),
+        }
+    }
+}
+
+fn describe<F: FieldExt>(event: &Option<AssignmentEvent<F>>) -> String {
+    match event {
+        Some(event) => format!("{event:?}"),
+        None => "missing".to_string(),
+    }
+}
+
+/// Synthesizes `circuit.without_witnesses()` and `circuit` and compares
+/// where they assign advice, the fixed values, the selectors they enable and
+/// the copies they make. Keygen only sees the former, so a circuit whose
+/// synthesis branches on a witness gets keys for another layout than the
+/// one it proves with, and only finds out when a proof fails to verify.
+pub fn check_synthesis_determinism<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+) -> Result<(), ShapeMismatch<F>> {
+    let shape = |circuit: &C, with_witnesses| -> Result<AssignmentTrace<F>, ShapeMismatch<F>> {
+        let mut trace = record(k, circuit).map_err(|error| ShapeMismatch::Synthesis {
+            with_witnesses,
+            error,
+        })?;
+        for event in trace.events.iter_mut() {
+            if let AssignmentEvent::Advice { value, .. } = event {
+                *value = None;
+            }
+        }
+        Ok(trace)
+    };
+    let keygen = shape(&circuit.without_witnesses(), false)?;
+    let proving = shape(circuit, true)?;
+    match keygen.diff(&proving).into_iter().next() {
+        None => Ok(()),
+        Some(difference) => Err(ShapeMismatch::Event(difference)),
+    }
+}
+
+struct Recorder<F: FieldExt> {
+    n: usize,
+    region: Option<String>,
+    namespace: Vec<String>,
+    trace: AssignmentTrace<F>,
+}
+
+impl<F: FieldExt> Recorder<F> {
+    fn check_row(&self, row: usize) -> Result<(), Error> {
+        if row < self.n {
+            Ok(())
+        } else {
+            Err(Error::NotEnoughRowsAvailable {
+                current_k: self.n.trailing_zeros(),
+            })
+        }
+    }
+}
+
+fn known<F: FieldExt>(value: Value<Assigned<F>>) -> Option<F> {
+    let mut known = None;
+    value.map(|v| known = Some(v.evaluate()));
+    known
+}
+
+impl<F: FieldExt> Assignment<F> for Recorder<F> {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.region = Some(name_fn().into());
+    }
+
+    fn exit_region(&mut self) {
+        self.region = None;
+    }
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        _annotation: A,
+        selector: &Selector,
+        row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        self.trace.events.push(AssignmentEvent::Selector {
+            region: self.region.clone(),
+            selector: format!("{selector:?}"),
+            row,
+        });
+        Ok(())
+    }
+
+    fn query_instance(&self, _column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
+        self.check_row(row)?;
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        self.trace.events.push(AssignmentEvent::Advice {
+            region: self.region.clone(),
+            column,
+            row,
+            value: known(to().map(|v| v.into())),
+        });
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        self.trace.events.push(AssignmentEvent::Fixed {
+            region: self.region.clone(),
+            column,
+            row,
+            value: known(to().map(|v| v.into())),
+        });
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.check_row(left_row)?;
+        self.check_row(right_row)?;
+        self.trace.events.push(AssignmentEvent::Copy {
+            left: (left_column, left_row),
+            right: (right_column, right_row),
+        });
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        from_row: usize,
+        to: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        self.check_row(from_row)?;
+        self.trace.events.push(AssignmentEvent::Fill {
+            column,
+            from_row,
+            value: known(to),
+        });
+        Ok(())
+    }
+
+    fn get_challenge(&self, _challenge: Challenge) -> Value<F> {
+        Value::unknown()
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.namespace.push(name_fn().into());
+        self.trace.namespaces.push(self.namespace.clone());
+    }
+
+    fn pop_namespace(&mut self, _gadget_name: Option<String>) {
+        self.namespace.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{AssignedCell, Layouter, SimpleFloorPlanner},
+        halo2curves::pasta::Fp,
+    };
+
+    use super::*;
+    use crate::{
+        chips::{
+            is_zero::{IsZeroChip, IsZeroConfig},
+            xor::XorChip,
+        },
+        testing::{HarnessChip, TestCircuit, TestHarness},
+    };
+
+    #[derive(Clone, Debug)]
+    struct XorCircuitConfig<F: FieldExt> {
+        advice: Column<Advice>,
+        xor_chip: XorChip<F, 4>,
+    }
+
+    // xors 3 and 5 `count` times
+    struct XorCircuit {
+        count: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for XorCircuit {
+        type Config = XorCircuitConfig<F>;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { count: self.count }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            meta.enable_equality(advice);
+
+            XorCircuitConfig {
+                advice,
+                xor_chip: XorChip::construct(meta),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config
+                .xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+
+            for _ in 0..self.count {
+                let (left, right) = layouter.assign_region(
+                    || "load operands",
+                    |mut region| {
+                        let left = region.assign_advice(
+                            || "left",
+                            config.advice,
+                            0,
+                            || Value::known(F::from(3)),
+                        )?;
+                        let right = region.assign_advice(
+                            || "right",
+                            config.advice,
+                            1,
+                            || Value::known(F::from(5)),
+                        )?;
+                        Ok((left, right))
+                    },
+                )?;
+                config
+                    .xor_chip
+                    .calculate_xor(layouter.namespace(|| "xor"), left, right)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_xor_auto_sizes_to_9() {
+        // the 256 row table doesn't fit in 2^8 rows once the blinding rows
+        // are reserved
+        let (k, prover) = run_auto::<Fp, _>(&XorCircuit { count: 1 }, vec![]).unwrap();
+        assert_eq!(k, 9);
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_over_max_k_fail() {
+        let result = run_auto_with_max_k::<Fp, _>(&XorCircuit { count: 1000 }, vec![], 10);
+        let err = result.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the circuit doesn't fit in 2^k rows for any k <= 10"
+        );
+        assert!(matches!(err, RunAutoError::GaveUp { max_k: 10 }));
+        assert!(matches!(
+            Error::from(err),
+            Error::NotEnoughRowsAvailable { current_k: 10 }
+        ));
+    }
+
+    // `configure` and `configure_with_selector` on a fresh selector should
+    // be indistinguishable
+    struct IsZeroTest<const SHARED_SELECTOR: bool>;
+
+    impl<F: FieldExt, const SHARED_SELECTOR: bool> HarnessChip<F> for IsZeroTest<SHARED_SELECTOR> {
+        type Config = IsZeroConfig<F>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let value_inverse = meta.advice_column();
+            let result = meta.advice_column();
+
+            meta.enable_equality(value);
+            meta.enable_equality(value_inverse);
+            meta.enable_equality(result);
+
+            if SHARED_SELECTOR {
+                let selector = meta.selector();
+                IsZeroChip::configure_with_selector(meta, value, value_inverse, result, selector)
+            } else {
+                IsZeroChip::configure(meta, value, value_inverse, result)
+            }
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = IsZeroChip::construct(config);
+            let value = chip.load_cell(layouter.namespace(|| "load cell"), witnesses[0].clone())?;
+            let result = chip.is_zero(layouter.namespace(|| "is zero"), value)?;
+            Ok(vec![result])
+        }
+    }
+
+    fn record_is_zero<const SHARED_SELECTOR: bool>(value: u64) -> AssignmentTrace<Fp> {
+        let circuit = TestCircuit::<Fp, IsZeroTest<SHARED_SELECTOR>>::new(vec![Fp::from(value)]);
+        record(4, &circuit).unwrap()
+    }
+
+    #[test]
+    fn test_record_same_assignments() {
+        let standard = record_is_zero::<false>(9);
+        let shared = record_is_zero::<true>(9);
+        assert!(!standard.events.is_empty());
+        assert_eq!(standard.diff(&shared), vec![]);
+    }
+
+    #[test]
+    fn test_record_diff() {
+        let nine = record_is_zero::<false>(9);
+        let zero = record_is_zero::<false>(0);
+        let differences = nine.diff(&zero);
+
+        // the witness, its copy, the inverse and its copy and the result
+        assert_eq!(differences.len(), 5);
+        for difference in differences {
+            assert!(matches!(
+                (difference.left, difference.right),
+                (
+                    Some(AssignmentEvent::Advice { row: left_row, .. }),
+                    Some(AssignmentEvent::Advice { row: right_row, .. }),
+                ) if left_row == right_row
+            ));
+        }
+    }
+
+    #[test]
+    fn test_record_namespaces() {
+        let trace = record_is_zero::<false>(9);
+        let path =
+            |names: &[&str]| -> Vec<String> { names.iter().map(|name| name.to_string()).collect() };
+        assert_eq!(
+            trace.namespaces,
+            vec![
+                path(&["chip"]),
+                path(&["chip", "load cell"]),
+                path(&["chip", "is zero"]),
+            ]
+        );
+        assert_eq!(trace.duplicate_namespaces(), Vec::<Vec<String>>::new());
+
+        // the same name under different parents is fine, twice under one
+        // parent is not
+        let mut trace = trace;
+        trace.namespaces.push(path(&["load cell"]));
+        assert_eq!(trace.duplicate_namespaces(), Vec::<Vec<String>>::new());
+        trace.namespaces.push(path(&["chip", "is zero"]));
+        assert_eq!(
+            trace.duplicate_namespaces(),
+            vec![path(&["chip", "is zero"])]
+        );
+    }
+
+    // assigns an extra cell when its witness is known, so only while
+    // proving
+    struct BranchingTest;
+
+    impl<F: FieldExt> HarnessChip<F> for BranchingTest {
+        type Config = Column<Advice>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            meta.advice_column()
+        }
+
+        fn synthesize(
+            advice: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let mut known = false;
+            witnesses[0].value().map(|_| known = true);
+            layouter.assign_region(
+                || "branch",
+                |mut region| {
+                    if known {
+                        region.assign_advice(|| "extra", advice, 0, || Value::known(F::one()))?;
+                    }
+                    Ok(())
+                },
+            )?;
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_synthesis_determinism() {
+        let circuit = TestCircuit::<Fp, IsZeroTest<false>>::new(vec![Fp::from(9)]);
+        assert!(check_synthesis_determinism(4, &circuit).is_ok());
+
+        let circuit = TestCircuit::<Fp, BranchingTest>::new(vec![Fp::from(9)]);
+        let mismatch = check_synthesis_determinism(4, &circuit).unwrap_err();
+        assert!(matches!(
+            &mismatch,
+            ShapeMismatch::Event(Difference {
+                left: None,
+                right: Some(AssignmentEvent::Advice { region: Some(region), .. }),
+                ..
+            }) if region == "branch"
+        ));
+        assert!(mismatch.to_string().contains("\"branch\""));
+    }
+
+    // an is zero and an xor check with different labels
+    struct LabeledTest;
+
+    impl<F: FieldExt> HarnessChip<F> for LabeledTest {
+        type Config = (IsZeroConfig<F>, XorChip<F, 4>);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            (
+                IsZeroTest::<false>::configure(meta),
+                XorChip::construct(meta),
+            )
+        }
+
+        fn synthesize(
+            (is_zero_config, xor_chip): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let chip = IsZeroChip::construct(is_zero_config);
+            let value = chip.load_cell(layouter.namespace(|| "load cell"), witnesses[0].clone())?;
+            let result =
+                chip.is_zero_labeled(layouter.namespace(|| "is zero"), value, "nullifier check")?;
+
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            let xor = xor_chip.calculate_xor_labeled(
+                layouter.namespace(|| "xor"),
+                witnesses[1].clone(),
+                witnesses[2].clone(),
+                "mask check",
+            )?;
+            Ok(vec![result, xor])
+        }
+    }
+
+    #[test]
+    fn test_explain_failures() {
+        let harness = TestHarness::auto();
+        let witnesses = vec![Fp::zero(), Fp::from(3), Fp::from(5)];
+        let prover = harness.run(LabeledTest, witnesses.clone(), vec![Fp::one(), Fp::from(6)]);
+        assert_eq!(explain_failures(&prover), Vec::<String>::new());
+
+        // a wrong is zero result and an operand too wide for the xor table
+        let prover = without_witness_checks(|| {
+            harness.run(
+                LabeledTest,
+                vec![Fp::zero(), Fp::from(16), Fp::from(5)],
+                vec![Fp::zero(), Fp::from(21)],
+            )
+        });
+        let failures = explain_failures(&prover);
+        assert!(failures
+            .iter()
+            .any(|failure| failure.starts_with("nullifier check: ")));
+        assert!(failures
+            .iter()
+            .any(|failure| failure.starts_with("mask check: ")));
+    }
+
+    #[test]
+    fn test_failure_label() {
+        let failure = format!(
+            "Lookup 0 is not satisfied in Region 1 ('{}') at offset 0",
+            labeled("xor", "mask")
+        );
+        assert_eq!(failure_label(&failure), Some("mask"));
+        assert_eq!(failure_label("Region 1 ('region') at offset 0"), None);
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use halo2_proofs::plonk::Error;
+
+/// The errors of chip calls that `plonk::Error` has no variant for. They
+/// convert into `Error::Synthesis`, so `?` in a `synthesize` still works,
+/// and halo2's own errors pass through `Plonk` unchanged.
+#[derive(Debug)]
+pub enum ChipError {
+    /// A constant fixed at keygen above the `max` the chip can handle, e.g.
+    /// one with no row in a lookup table.
+    ConstantOutOfRange {
+        chip: &'static str,
+        value: u64,
+        max: u64,
+    },
+    /// A table of `rows_needed` rows in a circuit of `2^k` rows, of which
+    /// `rows_available` are usable.
+    TableTooLarge {
+        k: u32,
+        rows_needed: usize,
+        rows_available: usize,
+    },
+    Plonk(Error),
+}
+
+impl ChipError {
+    /// `ConstantOutOfRange` unless `value <= max`.
+    pub(crate) fn check_constant(chip: &'static str, value: u64, max: u64) -> Result<(), Self> {
+        if value > max {
+            return Err(Self::ConstantOutOfRange { chip, value, max });
+        }
+        Ok(())
+    }
+
+    /// `ConstantOutOfRange` unless `value` fits in `bits` bits.
+    pub(crate) fn check_constant_bits(
+        chip: &'static str,
+        value: u64,
+        bits: usize,
+    ) -> Result<(), Self> {
+        let max = if bits >= 64 {
+            u64::MAX
+        } else {
+            (1 << bits) - 1
+        };
+        Self::check_constant(chip, value, max)
+    }
+}
+
+impl fmt::Display for ChipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConstantOutOfRange { chip, value, max } => {
+                write!(f, "{chip} takes constants up to {max}, got {value}")
+            }
+            Self::TableTooLarge {
+                k,
+                rows_needed,
+                rows_available,
+            } => write!(
+                f,
+                "the table needs {rows_needed} rows, k = {k} has {rows_available} usable rows"
+            ),
+            Self::Plonk(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ChipError {}
+
+impl From<Error> for ChipError {
+    fn from(err: Error) -> Self {
+        Self::Plonk(err)
+    }
+}
+
+impl From<ChipError> for Error {
+    fn from(err: ChipError) -> Self {
+        match err {
+            ChipError::Plonk(err) => err,
+            ChipError::ConstantOutOfRange { .. } => Error::Synthesis,
+            // so `run_auto` keeps searching
+            ChipError::TableTooLarge { k, .. } => Error::NotEnoughRowsAvailable { current_k: k },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_constant_bits() {
+        assert!(ChipError::check_constant_bits("test", 15, 4).is_ok());
+        assert!(matches!(
+            ChipError::check_constant_bits("test", 16, 4),
+            Err(ChipError::ConstantOutOfRange {
+                chip: "test",
+                value: 16,
+                max: 15
+            })
+        ));
+        assert!(ChipError::check_constant_bits("test", u64::MAX, 64).is_ok());
+        assert!(ChipError::check_constant_bits("test", 0, 0).is_ok());
+        assert!(ChipError::check_constant_bits("test", 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_into_plonk_error() {
+        let err = ChipError::check_constant("test", 3, 2).unwrap_err();
+        assert_eq!(err.to_string(), "test takes constants up to 2, got 3");
+        assert!(matches!(Error::from(err), Error::Synthesis));
+        assert!(matches!(
+            Error::from(ChipError::from(Error::InstanceTooLarge)),
+            Error::InstanceTooLarge
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::group::ff::PrimeField,
+    plonk::VerifyingKey,
+    transcript::{
+        Blake2bWrite, Challenge255, EncodedChallenge, Transcript, TranscriptWriterBuffer,
+    },
+};
+
+/// The crate version written into cache headers, a key cached by another
+/// version is stale even if its fingerprint matches.
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const HEADER_MAGIC: &str = "halo2-playground-keys";
+
+/// A digest of everything the verifying key commits the verifier to, the
+/// same Blake2b transcript input the prover starts from. Any change to the
+/// gates, the lookups, the fixed columns or k changes it.
+pub fn vk_fingerprint<C: CurveAffine>(vk: &VerifyingKey<C>) -> [u8; 32] {
+    let mut transcript = Blake2bWrite::<_, C, Challenge255<C>>::init(vec![]);
+    vk.hash_into(&mut transcript)
+        .expect("writing to a vec doesn't fail");
+    let digest = PrimeField::to_repr(&transcript.squeeze_challenge().get_scalar());
+
+    let mut fingerprint = [0; 32];
+    fingerprint.copy_from_slice(digest.as_ref());
+    fingerprint
+}
+
+/// Lower case hex, as the fingerprint is written in cache headers.
+pub fn fingerprint_hex(fingerprint: &[u8; 32]) -> String {
+    fingerprint
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum KeyCacheError {
+    Io(io::Error),
+    Malformed(String),
+    VersionMismatch { cached: String },
+    FingerprintMismatch { cached: String, current: String },
+}
+
+impl fmt::Display for KeyCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "reading the key cache failed: {err}"),
+            Self::Malformed(header) => write!(f, "malformed key cache header {header:?}"),
+            Self::VersionMismatch { cached } => write!(
+                f,
+                "the key cache was written by version {cached}, this is {CRATE_VERSION}"
+            ),
+            Self::FingerprintMismatch { cached, current } => write!(
+                f,
+                "the key cache is for the circuit with fingerprint {cached}, \
+                 the circuit changed since and its fingerprint is {current}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeyCacheError {}
+
+impl From<io::Error> for KeyCacheError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Writes the one line header `halo2-playground-keys <version> <fingerprint>`
+/// that starts a key cache file.
+pub fn write_header<C: CurveAffine>(
+    writer: &mut impl Write,
+    vk: &VerifyingKey<C>,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{HEADER_MAGIC} {CRATE_VERSION} {}",
+        fingerprint_hex(&vk_fingerprint(vk))
+    )
+}
+
+/// Reads the header `write_header` wrote and checks the cache is for `vk`,
+/// the key of the circuit as it is now. An error means the cache is stale
+/// and the keys need to be generated again.
+pub fn check_header<C: CurveAffine>(
+    reader: &mut impl BufRead,
+    vk: &VerifyingKey<C>,
+) -> Result<(), KeyCacheError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let header = line.trim_end();
+
+    let (version, cached) = match header.split(' ').collect::<Vec<_>>()[..] {
+        [HEADER_MAGIC, version, fingerprint] => (version, fingerprint),
+        _ => return Err(KeyCacheError::Malformed(header.to_string())),
+    };
+    if version != CRATE_VERSION {
+        return Err(KeyCacheError::VersionMismatch {
+            cached: version.to_string(),
+        });
+    }
+    let current = fingerprint_hex(&vk_fingerprint(vk));
+    if cached != current {
+        return Err(KeyCacheError::FingerprintMismatch {
+            cached: cached.to_string(),
+            current,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{AssignedCell, Layouter},
+        halo2curves::{
+            pasta::{EqAffine, Fp},
+            FieldExt,
+        },
+        plonk::{keygen_vk, ConstraintSystem, Error},
+        poly::{commitment::ParamsProver, ipa::commitment::ParamsIPA},
+    };
+
+    use super::*;
+    use crate::{
+        chips::linear_combination::{LinearCombinationChip, LinearCombinationConfig},
+        testing::{HarnessChip, TestCircuit},
+    };
+
+    const K: u32 = 4;
+
+    // a + C * b
+    struct ScaledSumTest<const C: u64>;
+
+    impl<F: FieldExt, const C: u64> HarnessChip<F> for ScaledSumTest<C> {
+        type Config = LinearCombinationConfig<F, 2>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let [a, b, sum] = [(); 3].map(|_| meta.advice_column());
+            for column in [a, b, sum] {
+                meta.enable_equality(column);
+            }
+            LinearCombinationChip::configure(meta, [a, b], sum, [F::one(), F::from(C)])
+        }
+
+        fn synthesize(
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let sum = LinearCombinationChip::construct(config)
+                .combine(layouter.namespace(|| "a + C * b"), &witnesses)?;
+            Ok(vec![sum])
+        }
+    }
+
+    fn vk<const C: u64>() -> VerifyingKey<EqAffine> {
+        let params = ParamsIPA::<EqAffine>::new(K);
+        let circuit = TestCircuit::<Fp, ScaledSumTest<C>>::new(vec![Fp::one(), Fp::one()]);
+        keygen_vk(&params, &circuit).unwrap()
+    }
+
+    #[test]
+    fn test_fingerprint_stable() {
+        assert_eq!(vk_fingerprint(&vk::<3>()), vk_fingerprint(&vk::<3>()));
+    }
+
+    #[test]
+    fn test_gate_constant_changes_fingerprint() {
+        assert_ne!(vk_fingerprint(&vk::<3>()), vk_fingerprint(&vk::<4>()));
+    }
+
+    #[test]
+    fn test_header() {
+        let mut cache = vec![];
+        write_header(&mut cache, &vk::<3>()).unwrap();
+        assert!(check_header(&mut cache.as_slice(), &vk::<3>()).is_ok());
+
+        // the circuit changed since the cache was written
+        let err = check_header(&mut cache.as_slice(), &vk::<4>()).unwrap_err();
+        assert!(matches!(err, KeyCacheError::FingerprintMismatch { .. }));
+        assert!(err.to_string().contains("the circuit changed"));
+    }
+
+    #[test]
+    fn test_stale_header() {
+        let fingerprint = fingerprint_hex(&vk_fingerprint(&vk::<3>()));
+        let stale = format!("{HEADER_MAGIC} 0.0.0 {fingerprint}\n");
+        let err = check_header(&mut stale.as_bytes(), &vk::<3>()).unwrap_err();
+        assert!(matches!(err, KeyCacheError::VersionMismatch { cached } if cached == "0.0.0"));
+
+        let err = check_header(&mut "not a header\n".as_bytes(), &vk::<3>()).unwrap_err();
+        assert!(matches!(err, KeyCacheError::Malformed(_)));
+    }
+}
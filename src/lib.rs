@@ -1 +1,9 @@
 pub mod chips;
+pub mod dev;
+pub mod error;
+pub mod keys;
+pub mod prove;
+pub mod report;
+pub mod testing;
+pub mod utils;
+pub mod witness;
@@ -0,0 +1,94 @@
+use halo2_proofs::{
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Error, ProvingKey, VerifyingKey,
+    },
+    poly::{
+        commitment::ParamsProver,
+        ipa::{
+            commitment::{IPACommitmentScheme, ParamsIPA},
+            multiopen::{ProverIPA, VerifierIPA},
+            strategy::SingleStrategy,
+        },
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use rand_core::OsRng;
+
+// IPA parameters only depend on k, the prover and the verifier generate the
+// same ones
+fn keygen<C: Circuit<Fp>>(
+    k: u32,
+    circuit: &C,
+) -> Result<(ParamsIPA<EqAffine>, VerifyingKey<EqAffine>), Error> {
+    let params = ParamsIPA::new(k);
+    let vk = keygen_vk(&params, &circuit.without_witnesses())?;
+    Ok((params, vk))
+}
+
+fn prove<C: Circuit<Fp>>(
+    params: &ParamsIPA<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+) -> Result<Vec<u8>, Error> {
+    let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+    create_proof::<IPACommitmentScheme<EqAffine>, ProverIPA<EqAffine>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[instances],
+        OsRng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+/// Proves `circuit` with the IPA commitment scheme over the Pasta curves,
+/// verifies the proof and returns it. `instances` has one slice per
+/// instance column. The prover doesn't check the constraints, an
+/// unsatisfied circuit fails with the verifier's error.
+pub fn prove_and_verify<C: Circuit<Fp>>(
+    k: u32,
+    circuit: C,
+    instances: &[&[Fp]],
+) -> Result<Vec<u8>, Error> {
+    let (params, vk) = keygen(k, &circuit)?;
+    let pk = keygen_pk(&params, vk, &circuit.without_witnesses())?;
+    let proof = prove(&params, &pk, circuit, instances)?;
+
+    verify_with_key(&params, pk.get_vk(), &proof, instances)?;
+    Ok(proof)
+}
+
+/// Verifies a proof `prove_and_verify` returned. The keys are generated
+/// again from `circuit`, which only needs to be the circuit without its
+/// witnesses.
+pub fn verify<C: Circuit<Fp>>(
+    k: u32,
+    circuit: &C,
+    proof: &[u8],
+    instances: &[&[Fp]],
+) -> Result<(), Error> {
+    let (params, vk) = keygen(k, circuit)?;
+    verify_with_key(&params, &vk, proof, instances)
+}
+
+fn verify_with_key(
+    params: &ParamsIPA<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &[u8],
+    instances: &[&[Fp]],
+) -> Result<(), Error> {
+    let strategy = SingleStrategy::new(params);
+    let mut transcript = Blake2bRead::<_, EqAffine, Challenge255<_>>::init(proof);
+    verify_proof::<IPACommitmentScheme<EqAffine>, VerifierIPA<EqAffine>, _, _, _>(
+        params,
+        vk,
+        strategy,
+        &[instances],
+        &mut transcript,
+    )
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use halo2_proofs::{
+    halo2curves::FieldExt,
+    plonk::{Circuit, ConstraintSystem, Expression},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GateReport {
+    pub name: String,
+    // (constraint name, degree) per polynomial
+    pub polynomials: Vec<(String, usize)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupReport {
+    pub inputs: usize,
+    pub degree: usize,
+}
+
+/// A summary of what a configured `ConstraintSystem` enforces: every gate
+/// polynomial with its degree, every lookup argument and the column counts.
+/// `Display` prints it as a plain text report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintReport {
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    pub gates: Vec<GateReport>,
+    pub lookups: Vec<LookupReport>,
+    pub degree: usize,
+}
+
+impl ConstraintReport {
+    pub fn new<F: FieldExt>(meta: &ConstraintSystem<F>) -> Self {
+        let gates = meta
+            .gates()
+            .iter()
+            .map(|gate| GateReport {
+                name: gate.name().to_string(),
+                polynomials: gate
+                    .polynomials()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, poly)| (gate.constraint_name(i).to_string(), poly.degree()))
+                    .collect(),
+            })
+            .collect();
+
+        let lookups = meta
+            .lookups()
+            .iter()
+            .map(|lookup| LookupReport {
+                inputs: lookup.input_expressions().len(),
+                degree: max_degree(lookup.input_expressions())
+                    .max(max_degree(lookup.table_expressions())),
+            })
+            .collect();
+
+        Self {
+            advice_columns: meta.num_advice_columns(),
+            fixed_columns: meta.num_fixed_columns(),
+            instance_columns: meta.num_instance_columns(),
+            selectors: meta.num_selectors(),
+            gates,
+            lookups,
+            degree: meta.degree(),
+        }
+    }
+
+    /// Report for the constraints `C::configure` creates.
+    pub fn for_circuit<F: FieldExt, C: Circuit<F>>() -> Self {
+        let mut meta = ConstraintSystem::default();
+        C::configure(&mut meta);
+        Self::new(&meta)
+    }
+
+    pub fn num_polynomials(&self) -> usize {
+        self.gates.iter().map(|gate| gate.polynomials.len()).sum()
+    }
+}
+
+fn max_degree<F: FieldExt>(expressions: &[Expression<F>]) -> usize {
+    expressions.iter().map(|e| e.degree()).max().unwrap_or(0)
+}
+
+impl fmt::Display for ConstraintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "columns: {} advice, {} fixed, {} instance, {} selectors",
+            self.advice_columns, self.fixed_columns, self.instance_columns, self.selectors
+        )?;
+
+        writeln!(f, "gates: {}", self.gates.len())?;
+        for gate in &self.gates {
+            writeln!(f, "  {}", gate.name)?;
+            for (i, (name, degree)) in gate.polynomials.iter().enumerate() {
+                if name.is_empty() {
+                    writeln!(f, "    [{i}] degree {degree}")?;
+                } else {
+                    writeln!(f, "    [{i}] {name}: degree {degree}")?;
+                }
+            }
+        }
+
+        writeln!(f, "lookups: {}", self.lookups.len())?;
+        for (i, lookup) in self.lookups.iter().enumerate() {
+            writeln!(
+                f,
+                "  [{i}] {} inputs, degree {}",
+                lookup.inputs, lookup.degree
+            )?;
+        }
+
+        write!(f, "max degree: {}", self.degree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::pasta::Fp;
+
+    use super::*;
+    use crate::chips::{is_zero::IsZeroChip, xor::XorChip};
+
+    #[test]
+    fn test_is_zero_report() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let value = meta.advice_column();
+        let value_inverse = meta.advice_column();
+        let result = meta.advice_column();
+        IsZeroChip::configure(&mut meta, value, value_inverse, result);
+
+        let report = ConstraintReport::new(&meta);
+        assert_eq!(report.gates.len(), 1);
+        assert_eq!(report.gates[0].name, "is zero gate");
+        assert_eq!(report.num_polynomials(), 3);
+        assert!(report.lookups.is_empty());
+        assert_eq!(report.advice_columns, 3);
+        assert!(report.to_string().contains("is zero gate"));
+    }
+
+    #[test]
+    fn test_xor_report() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        XorChip::<Fp, 4>::construct(&mut meta);
+
+        let report = ConstraintReport::new(&meta);
+        assert!(report.gates.is_empty());
+        assert_eq!(
+            report.lookups,
+            vec![LookupReport {
+                inputs: 3,
+                degree: 2
+            }]
+        );
+        assert!(report.to_string().contains("lookups: 1"));
+    }
+}
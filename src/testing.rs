@@ -0,0 +1,537 @@
+use std::{cell::RefCell, collections::HashMap, marker::PhantomData};
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::FieldExt,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    dev::{run_auto, without_witness_checks},
+    utils::constrain_instances,
+};
+
+/// A chip that can be dropped into `TestHarness`. Implementors are usually
+/// unit structs in a test module that configure the chip and wire it up to
+/// the loaded witnesses.
+pub trait HarnessChip<F: FieldExt> {
+    type Config: Clone;
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config;
+
+    /// Runs the chip on the witness cells and returns the cells that are
+    /// constrained to the public inputs, in order.
+    fn synthesize(
+        config: Self::Config,
+        layouter: impl Layouter<F>,
+        witnesses: Vec<AssignedCell<F, F>>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct TestCircuitConfig<C: Clone> {
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+    chip: C,
+}
+
+/// The values of `cells`, `None` for the ones that aren't known, e.g. during
+/// keygen.
+pub fn cell_values<F: FieldExt>(cells: &[AssignedCell<F, F>]) -> Vec<Option<F>> {
+    cells
+        .iter()
+        .map(|cell| {
+            let mut value = None;
+            cell.value().map(|v| value = Some(*v));
+            value
+        })
+        .collect()
+}
+
+/// Loads the witnesses into one advice column, hands them to the chip and
+/// exposes the returned cells as public inputs.
+pub struct TestCircuit<F: FieldExt, C: HarnessChip<F>> {
+    witnesses: Vec<Value<F>>,
+    // what the chip returned in the last synthesis, for diagnostics
+    outputs: RefCell<Vec<Option<F>>>,
+    _marker: PhantomData<C>,
+}
+
+impl<F: FieldExt, C: HarnessChip<F>> TestCircuit<F, C> {
+    pub fn new(witnesses: Vec<F>) -> Self {
+        Self {
+            witnesses: witnesses.into_iter().map(Value::known).collect(),
+            outputs: RefCell::new(vec![]),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The values of the cells the chip returned when last synthesized.
+    pub fn outputs(&self) -> Vec<Option<F>> {
+        self.outputs.borrow().clone()
+    }
+}
+
+impl<F: FieldExt, C: HarnessChip<F>> Circuit<F> for TestCircuit<F, C> {
+    type Config = TestCircuitConfig<C::Config>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            witnesses: vec![Value::unknown(); self.witnesses.len()],
+            outputs: RefCell::new(vec![]),
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        TestCircuitConfig {
+            advice,
+            instance,
+            chip: C::configure(meta),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witnesses = layouter.assign_region(
+            || "load witnesses",
+            |mut region| {
+                self.witnesses
+                    .iter()
+                    .enumerate()
+                    .map(|(row, value)| {
+                        region.assign_advice(|| "witness", config.advice, row, || *value)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+
+        let outputs = C::synthesize(config.chip, layouter.namespace(|| "chip"), witnesses)?;
+        *self.outputs.borrow_mut() = cell_values(&outputs);
+        constrain_instances(&mut layouter, &outputs, config.instance, 0)
+    }
+}
+
+/// One line `MockProver` checks for a single chip.
+///
+/// ```ignore
+/// let harness = TestHarness::<Fp>::auto();
+/// harness.expect_pass(XorTest::<4>, vec![Fp::from(3), Fp::from(1)], vec![Fp::from(2)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TestHarness<F: FieldExt> {
+    // `None` sizes k with `run_auto`
+    k: Option<u32>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> TestHarness<F> {
+    pub fn new(k: u32) -> Self {
+        Self {
+            k: Some(k),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Runs at the smallest k the circuit fits in.
+    pub fn auto() -> Self {
+        Self {
+            k: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn run<C: HarnessChip<F>>(
+        &self,
+        chip: C,
+        witnesses: Vec<F>,
+        public_inputs: Vec<F>,
+    ) -> MockProver<F> {
+        self.try_run(chip, witnesses, public_inputs).unwrap()
+    }
+
+    /// Like `run`, but hands back the synthesis error of a chip that refuses
+    /// its witnesses instead of panicking.
+    pub fn try_run<C: HarnessChip<F>>(
+        &self,
+        _chip: C,
+        witnesses: Vec<F>,
+        public_inputs: Vec<F>,
+    ) -> Result<MockProver<F>, Error> {
+        self.run_circuit(&TestCircuit::<F, C>::new(witnesses), public_inputs)
+    }
+
+    fn run_circuit<C: HarnessChip<F>>(
+        &self,
+        circuit: &TestCircuit<F, C>,
+        public_inputs: Vec<F>,
+    ) -> Result<MockProver<F>, Error> {
+        match self.k {
+            Some(k) => MockProver::run(k, circuit, vec![public_inputs]),
+            None => run_auto(circuit, vec![public_inputs]).map(|(_, prover)| prover),
+        }
+    }
+
+    /// Runs and verifies the chip. On failure the error lists the witnesses,
+    /// the values the chip returned, the public inputs they were checked
+    /// against and the `MockProver` failures.
+    pub fn check<C: HarnessChip<F>>(
+        &self,
+        _chip: C,
+        witnesses: Vec<F>,
+        public_inputs: Vec<F>,
+    ) -> Result<(), String> {
+        let circuit = TestCircuit::<F, C>::new(witnesses.clone());
+        let prover = self.run_circuit(&circuit, public_inputs.clone()).unwrap();
+        prover.verify().map_err(|failures| {
+            format!(
+                "verification failed\n  witnesses: {witnesses:?}\n  chip outputs: {:?}\n  \
+                 public inputs: {public_inputs:?}\n  failures: {failures:#?}",
+                circuit.outputs()
+            )
+        })
+    }
+
+    pub fn expect_pass<C: HarnessChip<F>>(
+        &self,
+        chip: C,
+        witnesses: Vec<F>,
+        public_inputs: Vec<F>,
+    ) {
+        if let Err(report) = self.check(chip, witnesses, public_inputs) {
+            panic!("{report}");
+        }
+    }
+
+    pub fn expect_fail<C: HarnessChip<F>>(
+        &self,
+        chip: C,
+        witnesses: Vec<F>,
+        public_inputs: Vec<F>,
+    ) {
+        assert!(
+            self.check(chip, witnesses.clone(), public_inputs.clone())
+                .is_err(),
+            "verification passed\n  witnesses: {witnesses:?}\n  public inputs: {public_inputs:?}"
+        );
+    }
+
+    /// `mutation_test` of the chip on these witnesses, at the harness k or
+    /// the smallest k the clean run fits in.
+    pub fn mutation_test<C: HarnessChip<F>>(
+        &self,
+        _chip: C,
+        witnesses: Vec<F>,
+        public_inputs: Vec<F>,
+    ) -> MutationReport {
+        let build = || TestCircuit::<F, C>::new(witnesses.clone());
+        let k = match self.k {
+            Some(k) => k,
+            None => run_auto(&build(), vec![public_inputs.clone()]).unwrap().0,
+        };
+        mutation_test(k, build, vec![public_inputs])
+    }
+}
+
+/// A witness `mutation_test` corrupts: the `index`th value that passed
+/// through `mutation_hook` with this `name` in one synthesis.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mutation {
+    pub name: &'static str,
+    pub index: usize,
+}
+
+struct MutationSession {
+    active: Option<Mutation>,
+    counts: HashMap<&'static str, usize>,
+    hits: Vec<Mutation>,
+}
+
+thread_local! {
+    // only set while `mutation_test` runs the prover
+    static MUTATIONS: RefCell<Option<MutationSession>> = RefCell::new(None);
+}
+
+/// Returns `value`, plus 1 if it is the witness `mutation_test` is
+/// corrupting. Chips call it on the witnesses they assign, inside the
+/// closure handed to `assign_advice` so only the pass that assigns counts.
+/// A no-op outside of `mutation_test`.
+pub fn mutation_hook<F: FieldExt>(name: &'static str, value: Value<F>) -> Value<F> {
+    MUTATIONS.with(|session| {
+        let mut session = session.borrow_mut();
+        let session = match session.as_mut() {
+            Some(session) => session,
+            None => return value,
+        };
+        let count = session.counts.entry(name).or_default();
+        let mutation = Mutation {
+            name,
+            index: *count,
+        };
+        *count += 1;
+
+        let corrupt = session.active.as_ref() == Some(&mutation);
+        session.hits.push(mutation);
+        if corrupt {
+            value + Value::known(F::one())
+        } else {
+            value
+        }
+    })
+}
+
+/// What `mutation_test` found: every hooked witness of the clean run and
+/// the ones that could be corrupted without the circuit failing.
+#[derive(Clone, Debug)]
+pub struct MutationReport {
+    pub mutations: Vec<Mutation>,
+    pub survivors: Vec<Mutation>,
+}
+
+impl MutationReport {
+    /// Panics unless some witness was hooked and every mutation was caught.
+    pub fn assert_all_caught(&self) {
+        assert!(
+            !self.mutations.is_empty(),
+            "no witness passed through a mutation hook"
+        );
+        assert!(
+            self.survivors.is_empty(),
+            "under-constrained, these mutations still verify: {:?}",
+            self.survivors
+        );
+    }
+}
+
+/// Runs the circuit `build` returns once as is, which has to verify, and
+/// then once per witness that went through `mutation_hook` in that run, with
+/// only that witness plus 1. A mutated run that still verifies points at an
+/// under-constrained witness and is reported as a survivor.
+///
+/// Mutated runs skip `debug_assert_witness!`, a corrupted value handed on
+/// to the next chip would trip it. A mutation the chip refuses with a
+/// synthesis error counts as caught.
+pub fn mutation_test<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    build: impl Fn() -> C,
+    instances: Vec<Vec<F>>,
+) -> MutationReport {
+    let run = |active: Option<Mutation>| {
+        MUTATIONS.with(|session| {
+            *session.borrow_mut() = Some(MutationSession {
+                active,
+                counts: HashMap::new(),
+                hits: vec![],
+            })
+        });
+        let result = MockProver::run(k, &build(), instances.clone());
+        let session = MUTATIONS.with(|session| session.borrow_mut().take());
+        (result, session.expect("set above").hits)
+    };
+
+    let (clean, mutations) = run(None);
+    if let Err(failures) = clean.unwrap().verify() {
+        panic!("the unmutated circuit fails to verify: {failures:#?}");
+    }
+
+    let survivors = mutations
+        .iter()
+        .filter(|mutation| {
+            let (result, _) = without_witness_checks(|| run(Some((*mutation).clone())));
+            matches!(result.map(|prover| prover.verify()), Ok(Ok(())))
+        })
+        .cloned()
+        .collect();
+    MutationReport {
+        mutations,
+        survivors,
+    }
+}
+
+/// Seeded xorshift64* generator for reproducible test operands. Not
+/// cryptographic, only meant to make property tests deterministic.
+#[derive(Clone, Debug)]
+pub struct TestRng {
+    state: u64,
+}
+
+impl TestRng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on a zero state
+        Self { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Returns a value in `[0, bound)`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        halo2curves::pasta::Fp,
+        plonk::{Expression, Selector},
+        poly::Rotation,
+    };
+
+    use super::*;
+    use crate::chips::xor::XorChip;
+
+    struct XorTest;
+
+    impl<F: FieldExt> HarnessChip<F> for XorTest {
+        type Config = XorChip<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            XorChip::construct(meta)
+        }
+
+        fn synthesize(
+            xor_chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            let result = xor_chip.calculate_xor(
+                layouter.namespace(|| "xor"),
+                witnesses[0].clone(),
+                witnesses[1].clone(),
+            )?;
+            Ok(vec![result])
+        }
+    }
+
+    #[test]
+    fn test_check_reports_operands() {
+        let report = TestHarness::auto()
+            .check(XorTest, vec![Fp::from(3), Fp::from(1)], vec![Fp::from(3)])
+            .unwrap_err();
+
+        // the chip computed 3 ^ 1 = 2, which doesn't match the claimed 3
+        assert!(report.contains(&format!("witnesses: {:?}", [Fp::from(3), Fp::from(1)])));
+        assert!(report.contains(&format!("chip outputs: {:?}", [Some(Fp::from(2))])));
+        assert!(report.contains(&format!("public inputs: {:?}", [Fp::from(3)])));
+    }
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let run = || {
+            let mut rng = TestRng::new(42);
+            (0..16).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+
+        let mut other = TestRng::new(43);
+        assert_ne!(run()[0], other.next_u64());
+    }
+
+    // `IsZeroChip` with the `(1 - is_zero) * (value * inverse - 1)` term
+    // removed, which leaves the inverse of a nonzero value unconstrained
+    struct WeakIsZeroTest;
+
+    impl<F: FieldExt> HarnessChip<F> for WeakIsZeroTest {
+        type Config = ([Column<Advice>; 3], Selector);
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let columns = [(); 3].map(|_| meta.advice_column());
+            for column in columns {
+                meta.enable_equality(column);
+            }
+            let selector = meta.selector();
+
+            meta.create_gate("weak is zero", |meta| {
+                let s = meta.query_selector(selector);
+                let [value, inverse, is_zero] =
+                    columns.map(|column| meta.query_advice(column, Rotation::cur()));
+                let one = Expression::Constant(F::one());
+                vec![
+                    s.clone() * is_zero.clone() * (one - is_zero.clone()),
+                    s.clone() * value.clone() * is_zero.clone(),
+                    s * is_zero * (value - inverse),
+                ]
+            });
+            (columns, selector)
+        }
+
+        fn synthesize(
+            ([value, inverse, is_zero], selector): Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            let result = layouter.assign_region(
+                || "weak is zero",
+                |mut region| {
+                    selector.enable(&mut region, 0)?;
+                    let cell = witnesses[0].copy_advice(|| "value", &mut region, value, 0)?;
+                    let v = cell.value().copied();
+                    let inv = v.map(|v| v.invert().unwrap_or(F::zero()));
+                    let result = v.map(|v| F::from(v == F::zero()));
+                    region.assign_advice(
+                        || "inverse",
+                        inverse,
+                        0,
+                        || mutation_hook("WeakIsZero inverse", inv),
+                    )?;
+                    region.assign_advice(
+                        || "result",
+                        is_zero,
+                        0,
+                        || mutation_hook("WeakIsZero result", result),
+                    )
+                },
+            )?;
+            Ok(vec![result])
+        }
+    }
+
+    #[test]
+    fn test_mutation_test_finds_removed_constraint() {
+        let harness = TestHarness::auto();
+        let report = harness.mutation_test(WeakIsZeroTest, vec![Fp::from(9)], vec![Fp::zero()]);
+        assert_eq!(
+            report.mutations,
+            [
+                Mutation {
+                    name: "WeakIsZero inverse",
+                    index: 0
+                },
+                Mutation {
+                    name: "WeakIsZero result",
+                    index: 0
+                },
+            ]
+        );
+        assert_eq!(report.survivors, report.mutations[..1].to_vec());
+        assert!(std::panic::catch_unwind(|| report.assert_all_caught()).is_err());
+    }
+
+    #[test]
+    fn test_mutation_hook_outside_mutation_test() {
+        let value = Value::known(Fp::from(7));
+        let mut hooked = None;
+        mutation_hook("unused", value).map(|v| hooked = Some(v));
+        assert_eq!(hooked, Some(Fp::from(7)));
+    }
+}
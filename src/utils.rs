@@ -0,0 +1,478 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::FieldExt,
+    plonk::{Advice, Column, Error, Instance},
+};
+
+use crate::debug_assert_witness;
+
+/// Assigns `value` to a cell of `advice` in a region of its own. `advice`
+/// needs equality for the cell to be copied into a chip.
+pub fn load_private<F: FieldExt>(
+    mut layouter: impl Layouter<F>,
+    advice: Column<Advice>,
+    value: Value<F>,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "load private",
+        |mut region| region.assign_advice(|| "private", advice, 0, || value),
+    )
+}
+
+/// Copies `row` of `instance` into a cell of `advice` in a region of its
+/// own. Both columns need equality.
+pub fn load_public<F: FieldExt>(
+    mut layouter: impl Layouter<F>,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+    row: usize,
+) -> Result<AssignedCell<F, F>, Error> {
+    layouter.assign_region(
+        || "load public",
+        |mut region| region.assign_advice_from_instance(|| "public", instance, row, advice, 0),
+    )
+}
+
+/// Loads every constant and every keyed witness into `advice` once, repeat
+/// requests get the cell of the first one, which a chip then copies like any
+/// other. A composed circuit needing the constant 1 in a dozen places pays
+/// for one cell instead of a dozen.
+///
+/// The methods take `&self`, so a cache made at the top of `synthesize` can
+/// be shared by the helpers it calls. Cells are only valid in the synthesis
+/// that assigned them, a cache must not outlive one `synthesize` call.
+///
+/// `advice` needs equality, and `constant` needs a column enabled for
+/// constants.
+#[derive(Debug)]
+pub struct CellCache<F: FieldExt> {
+    advice: Column<Advice>,
+    // few enough for a linear search, `F` isn't `Hash`
+    constants: RefCell<Vec<(F, AssignedCell<F, F>)>>,
+    witnesses: RefCell<HashMap<String, AssignedCell<F, F>>>,
+}
+
+impl<F: FieldExt> CellCache<F> {
+    pub fn new(advice: Column<Advice>) -> Self {
+        Self {
+            advice,
+            constants: RefCell::new(vec![]),
+            witnesses: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// A cell pinned to `constant`, assigned on the first request.
+    pub fn constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        constant: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if let Some((_, cell)) = self.constants.borrow().iter().find(|(c, _)| *c == constant) {
+            return Ok(cell.clone());
+        }
+        let cell = layouter.assign_region(
+            || "cached constant",
+            |mut region| {
+                region.assign_advice_from_constant(|| "constant", self.advice, 0, constant)
+            },
+        )?;
+        self.constants.borrow_mut().push((constant, cell.clone()));
+        Ok(cell)
+    }
+
+    /// The cell of the witness cached under `key`, assigned from `value` on
+    /// the first request. Later requests must pass the same value, only the
+    /// first one is assigned.
+    pub fn witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        key: &str,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if let Some(cell) = self.witnesses.borrow().get(key) {
+            debug_assert_witness!(
+                "CellCache",
+                key,
+                value.zip(cell.value()),
+                |(new, cached): (F, &F)| new == *cached,
+                "the value first cached under its key",
+            );
+            return Ok(cell.clone());
+        }
+        let cell = layouter.assign_region(
+            || "cached witness",
+            |mut region| region.assign_advice(|| key, self.advice, 0, || value),
+        )?;
+        self.witnesses
+            .borrow_mut()
+            .insert(key.to_string(), cell.clone());
+        Ok(cell)
+    }
+}
+
+/// Constrains `cells` to the consecutive rows of `instance` starting at
+/// `start_row`.
+pub fn constrain_instances<F: FieldExt>(
+    layouter: &mut impl Layouter<F>,
+    cells: &[AssignedCell<F, F>],
+    instance: Column<Instance>,
+    start_row: usize,
+) -> Result<(), Error> {
+    for (i, cell) in cells.iter().enumerate() {
+        layouter.constrain_instance(cell.cell(), instance, start_row + i)?;
+    }
+    Ok(())
+}
+
+/// Threads an accumulator from `init` through `items`, `step` takes the
+/// accumulator and the next item and returns the new accumulator. Returns
+/// `init` for no items.
+///
+/// ```ignore
+/// let xor = fold_cells(&mut layouter, init, &items, |layouter, acc, item| {
+///     xor_chip.calculate_xor(layouter.namespace(|| "xor"), acc, item)
+/// })?;
+/// ```
+pub fn fold_cells<F: FieldExt, L: Layouter<F>>(
+    layouter: &mut L,
+    init: AssignedCell<F, F>,
+    items: &[AssignedCell<F, F>],
+    mut step: impl FnMut(
+        &mut L,
+        AssignedCell<F, F>,
+        AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error>,
+) -> Result<AssignedCell<F, F>, Error> {
+    items
+        .iter()
+        .try_fold(init, |acc, item| step(layouter, acc, item.clone()))
+}
+
+/// The last row and at least 5 blinding rows of every circuit are never
+/// usable.
+pub const MIN_RESERVED_ROWS: usize = 6;
+
+/// The smallest k with room for `rows` usable rows, counting only the
+/// `MIN_RESERVED_ROWS`.
+pub fn min_k_for_rows(rows: usize) -> u32 {
+    let mut k = 0;
+    while (1usize << k) < rows + MIN_RESERVED_ROWS {
+        k += 1;
+    }
+    k
+}
+
+/// Whether `value` is below `2^bits`. Only values below `2^128` are
+/// recognised, a wider one doesn't fit whatever `bits` is.
+pub fn fits_in_bits<F: FieldExt>(value: &F, bits: usize) -> bool {
+    let lower = value.get_lower_128();
+    F::from_u128(lower) == *value && (bits >= 128 || lower >> bits == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::pasta::Fp,
+        plonk::{Circuit, ConstraintSystem},
+    };
+
+    use super::*;
+    use crate::{
+        chips::{
+            is_zero::{IsZeroChip, IsZeroConfig},
+            xor::XorChip,
+        },
+        dev::{record, run_auto, AssignmentEvent},
+        testing::{HarnessChip, TestHarness},
+    };
+
+    const K: u32 = 4;
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    }
+
+    struct TestCircuit<F: FieldExt> {
+        values: Vec<Value<F>>,
+        start_row: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                start_row: self.start_row,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+
+            TestCircuitConfig { advice, instance }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let cells = layouter.assign_region(
+                || "load values",
+                |mut region| {
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .map(|(row, value)| {
+                            region.assign_advice(|| "value", config.advice, row, || *value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            constrain_instances(&mut layouter, &cells, config.instance, self.start_row)
+        }
+    }
+
+    fn run(start_row: usize, public_inputs: &[u64]) -> MockProver<Fp> {
+        let circuit = TestCircuit {
+            values: [7, 8, 9].map(|v| Value::known(Fp::from(v))).to_vec(),
+            start_row,
+        };
+        let public_inputs = public_inputs.iter().map(|v| Fp::from(*v)).collect();
+        MockProver::run(K, &circuit, vec![public_inputs]).unwrap()
+    }
+
+    #[test]
+    fn test_constrain_instances_pass() {
+        assert_eq!(run(0, &[7, 8, 9]).verify(), Ok(()));
+        assert_eq!(run(2, &[0, 0, 7, 8, 9]).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_constrain_instances_mismatch_fail() {
+        assert!(run(0, &[7, 9, 8]).verify().is_err());
+        // right values, wrong rows
+        assert!(run(1, &[7, 8, 9, 0]).verify().is_err());
+    }
+
+    #[test]
+    fn test_fits_in_bits() {
+        assert!(fits_in_bits(&Fp::from(15), 4));
+        assert!(!fits_in_bits(&Fp::from(16), 4));
+        assert!(fits_in_bits(&Fp::zero(), 0));
+        // p - 1 is wider than its lower 128 bits
+        assert!(!fits_in_bits(&-Fp::one(), 127));
+        // a shift by 128 or more would overflow
+        assert!(fits_in_bits(&Fp::from_u128(u128::MAX), 128));
+        assert!(fits_in_bits(&Fp::from(15), 200));
+        assert!(!fits_in_bits(&-Fp::one(), 255));
+    }
+
+    // the xor of all witnesses, folded from the first one
+    struct FoldXorTest;
+
+    impl<F: FieldExt> HarnessChip<F> for FoldXorTest {
+        type Config = XorChip<F, 4>;
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            XorChip::construct(meta)
+        }
+
+        fn synthesize(
+            xor_chip: Self::Config,
+            mut layouter: impl Layouter<F>,
+            witnesses: Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            let (init, items) = witnesses.split_first().expect("at least one witness");
+            let xor = fold_cells(&mut layouter, init.clone(), items, |layouter, acc, item| {
+                xor_chip.calculate_xor(layouter.namespace(|| "xor"), acc, item)
+            })?;
+            Ok(vec![xor])
+        }
+    }
+
+    #[test]
+    fn test_fold_cells() {
+        let harness = TestHarness::auto();
+        let witnesses = [0b0011, 0b0101, 0b1001, 0b1110].map(Fp::from).to_vec();
+        let xor = Fp::from(0b0011 ^ 0b0101 ^ 0b1001 ^ 0b1110);
+        harness.expect_pass(FoldXorTest, witnesses.clone(), vec![xor]);
+        harness.expect_fail(FoldXorTest, witnesses, vec![Fp::from(0b0011 ^ 0b0101)]);
+        // nothing to fold
+        harness.expect_pass(FoldXorTest, vec![Fp::from(7)], vec![Fp::from(7)]);
+    }
+
+    #[test]
+    fn test_min_k_for_rows() {
+        assert_eq!(min_k_for_rows(0), 3);
+        assert_eq!(min_k_for_rows(256), 9);
+        assert_eq!(min_k_for_rows(1 << 16), 17);
+        assert_eq!(min_k_for_rows((1 << 9) - MIN_RESERVED_ROWS), 9);
+    }
+
+    // `private ^ public == 0` with the private value loaded by `load_private`
+    // and the public one, instance row 0, by `load_public`. The result is
+    // exposed on row 1.
+    struct LoadCircuit<F: FieldExt> {
+        private: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for LoadCircuit<F> {
+        type Config = (TestCircuitConfig, XorChip<F, 4>, IsZeroConfig<F>);
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                private: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let config = <TestCircuit<F> as Circuit<F>>::configure(meta);
+            let [value, value_inverse, is_zero] = [(); 3].map(|_| meta.advice_column());
+            for column in [value, is_zero] {
+                meta.enable_equality(column);
+            }
+            (
+                config,
+                XorChip::construct(meta),
+                IsZeroChip::configure(meta, value, value_inverse, is_zero),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, xor_chip, is_zero_config): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            xor_chip
+                .xor_table
+                .load(&mut layouter.namespace(|| "xor table"))?;
+            let private = load_private(
+                layouter.namespace(|| "private"),
+                config.advice,
+                self.private,
+            )?;
+            let public = load_public(
+                layouter.namespace(|| "public"),
+                config.advice,
+                config.instance,
+                0,
+            )?;
+
+            let xor = xor_chip.calculate_xor(layouter.namespace(|| "xor"), private, public)?;
+            let is_zero_chip = IsZeroChip::construct(is_zero_config);
+            let loaded = is_zero_chip.load_cell(layouter.namespace(|| "load xor"), xor)?;
+            let equal = is_zero_chip.is_zero(layouter.namespace(|| "is zero"), loaded)?;
+            layouter.constrain_instance(equal.cell(), config.instance, 1)
+        }
+    }
+
+    fn run_load(private: u64, public: u64, equal: bool) -> MockProver<Fp> {
+        let circuit = LoadCircuit {
+            private: Value::known(Fp::from(private)),
+        };
+        let public_inputs = vec![Fp::from(public), Fp::from(equal)];
+        run_auto(&circuit, vec![public_inputs]).unwrap().1
+    }
+
+    #[test]
+    fn test_load_private_and_public() {
+        assert_eq!(run_load(5, 5, true).verify(), Ok(()));
+        assert_eq!(run_load(5, 6, false).verify(), Ok(()));
+        assert!(run_load(5, 6, true).verify().is_err());
+        assert!(run_load(5, 5, false).verify().is_err());
+    }
+
+    // requests 1, 7 and the witness `x` `repeat` times each and exposes them
+    // on rows 0, 1 and 2 every time
+    struct CacheCircuit<F: FieldExt> {
+        x: Value<F>,
+        repeat: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for CacheCircuit<F> {
+        type Config = TestCircuitConfig;
+
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                x: Value::unknown(),
+                repeat: self.repeat,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+            <TestCircuit<F> as Circuit<F>>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let cache = CellCache::new(config.advice);
+            for _ in 0..self.repeat {
+                let cells = [
+                    cache.constant(layouter.namespace(|| "one"), F::one())?,
+                    cache.constant(layouter.namespace(|| "seven"), F::from(7))?,
+                    cache.witness(layouter.namespace(|| "x"), "x", self.x)?,
+                ];
+                constrain_instances(&mut layouter, &cells, config.instance, 0)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn cached_assignments(circuit: &CacheCircuit<Fp>) -> usize {
+        let trace = record(K, circuit).unwrap();
+        trace
+            .events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    AssignmentEvent::Advice { region: Some(region), .. }
+                        if region == "cached constant" || region == "cached witness"
+                )
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_cell_cache() {
+        let x = Value::known(Fp::from(42));
+        for repeat in [1, 2, 5] {
+            let circuit = CacheCircuit { x, repeat };
+            let prover = MockProver::run(K, &circuit, vec![[1, 7, 42].map(Fp::from).to_vec()]);
+            assert_eq!(prover.unwrap().verify(), Ok(()));
+            // one cell each, however often they are requested
+            assert_eq!(cached_assignments(&circuit), 3);
+        }
+
+        let circuit = CacheCircuit { x, repeat: 3 };
+        let prover = MockProver::run(K, &circuit, vec![[1, 7, 43].map(Fp::from).to_vec()]);
+        assert!(prover.unwrap().verify().is_err());
+    }
+}
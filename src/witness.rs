@@ -0,0 +1,25 @@
+use rayon::prelude::*;
+
+/// Witness values computed in plain Rust before synthesis, one row per
+/// input. Region closures borrow the layouter and run one after the other,
+/// so the inversions and lookups results a chip computes inside them can't
+/// be spread over threads. A plan computes the same values up front with
+/// rayon, and the chip's `_precomputed` methods only assign them.
+///
+/// Inputs are `Value`s, so a plan computes unknown rows for keygen just
+/// like the chip would.
+pub trait WitnessPlan {
+    type Input: Sync;
+    type Row: Send;
+
+    fn row(input: &Self::Input) -> Self::Row;
+
+    fn compute(inputs: &[Self::Input]) -> Vec<Self::Row> {
+        inputs.par_iter().map(Self::row).collect()
+    }
+
+    /// `compute` on the calling thread.
+    fn compute_serial(inputs: &[Self::Input]) -> Vec<Self::Row> {
+        inputs.iter().map(Self::row).collect()
+    }
+}
@@ -0,0 +1,116 @@
+//! XORs two private values with `XorChip` and exposes whether the result is
+//! zero via `IsZeroChip`, i.e. whether the two values are equal.
+
+use halo2_playground::chips::{
+    is_zero::{IsZeroChip, IsZeroConfig},
+    xor::XorChip,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const K: u32 = 9;
+
+#[derive(Default)]
+struct XorIsZeroCircuit<F: FieldExt> {
+    left: Value<F>,
+    right: Value<F>,
+}
+
+#[derive(Clone, Debug)]
+struct XorIsZeroConfig<F: FieldExt> {
+    advice: Column<Advice>,
+    xor_chip: XorChip<F, 4>,
+    is_zero_config: IsZeroConfig<F>,
+    instance: Column<Instance>,
+}
+
+impl<F: FieldExt> Circuit<F> for XorIsZeroCircuit<F> {
+    type Config = XorIsZeroConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let value = meta.advice_column();
+        let value_inverse = meta.advice_column();
+        let result = meta.advice_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(value);
+        meta.enable_equality(value_inverse);
+        meta.enable_equality(result);
+        meta.enable_equality(instance);
+
+        XorIsZeroConfig {
+            advice,
+            xor_chip: XorChip::construct(meta),
+            is_zero_config: IsZeroChip::configure(meta, value, value_inverse, result),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let xor_chip = config.xor_chip;
+        let is_zero_chip = IsZeroChip::construct(config.is_zero_config);
+
+        xor_chip
+            .xor_table
+            .load(&mut layouter.namespace(|| "xor table"))?;
+
+        let (left, right) = layouter.assign_region(
+            || "load operands",
+            |mut region| {
+                let left = region.assign_advice(|| "left", config.advice, 0, || self.left)?;
+                let right = region.assign_advice(|| "right", config.advice, 1, || self.right)?;
+                Ok((left, right))
+            },
+        )?;
+
+        let xor_cell = xor_chip.calculate_xor(layouter.namespace(|| "xor"), left, right)?;
+        let value = is_zero_chip.load_cell(layouter.namespace(|| "load xor result"), xor_cell)?;
+        let is_zero_cell = is_zero_chip.is_zero(layouter.namespace(|| "is zero"), value)?;
+
+        layouter.constrain_instance(is_zero_cell.cell(), config.instance, 0)
+    }
+}
+
+fn circuit(left: u64, right: u64) -> XorIsZeroCircuit<Fp> {
+    XorIsZeroCircuit {
+        left: Value::known(Fp::from(left)),
+        right: Value::known(Fp::from(right)),
+    }
+}
+
+#[test]
+fn test_xor_result_non_zero() {
+    // 3 ^ 1 = 2, which is non-zero.
+    let prover = MockProver::run(K, &circuit(3, 1), vec![vec![Fp::zero()]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Claiming the xor result is zero should fail.
+    let prover = MockProver::run(K, &circuit(3, 1), vec![vec![Fp::one()]]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn test_xor_result_zero() {
+    // 5 ^ 5 = 0, so the non-zero claim should fail.
+    let prover = MockProver::run(K, &circuit(5, 5), vec![vec![Fp::zero()]]).unwrap();
+    assert!(prover.verify().is_err());
+
+    let prover = MockProver::run(K, &circuit(5, 5), vec![vec![Fp::one()]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
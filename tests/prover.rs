@@ -0,0 +1,96 @@
+//! Runs the is-zero circuit through the real IPA prover and verifier instead
+//! of `MockProver`.
+
+use halo2_playground::{
+    chips::is_zero::{IsZeroChip, IsZeroConfig},
+    prove::{prove_and_verify, verify},
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::{pasta::Fp, FieldExt},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const K: u32 = 4;
+
+#[derive(Default)]
+struct IsZeroCircuit<F: FieldExt> {
+    number: Value<F>,
+}
+
+#[derive(Clone, Debug)]
+struct IsZeroCircuitConfig<F: FieldExt> {
+    is_zero_config: IsZeroConfig<F>,
+    instance: Column<Instance>,
+}
+
+impl<F: FieldExt> Circuit<F> for IsZeroCircuit<F> {
+    type Config = IsZeroCircuitConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let value_inverse = meta.advice_column();
+        let result = meta.advice_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(value);
+        meta.enable_equality(value_inverse);
+        meta.enable_equality(result);
+        meta.enable_equality(instance);
+
+        IsZeroCircuitConfig {
+            is_zero_config: IsZeroChip::configure(meta, value, value_inverse, result),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = IsZeroChip::construct(config.is_zero_config);
+        let value = chip.load_value(layouter.namespace(|| "load value"), self.number)?;
+        let result_cell = chip.is_zero(layouter.namespace(|| "is zero"), value)?;
+
+        layouter.constrain_instance(result_cell.cell(), config.instance, 0)
+    }
+}
+
+#[test]
+fn test_prove_and_verify_round_trip() {
+    let circuit = IsZeroCircuit {
+        number: Value::known(Fp::zero()),
+    };
+    let proof = prove_and_verify(K, circuit, &[&[Fp::one()]]).unwrap();
+
+    // the verifier only has the circuit without witnesses
+    let circuit = IsZeroCircuit::<Fp>::default();
+    assert!(verify(K, &circuit, &proof, &[&[Fp::one()]]).is_ok());
+    assert!(verify(K, &circuit, &proof, &[&[Fp::zero()]]).is_err());
+}
+
+#[test]
+fn test_tampered_proof() {
+    let circuit = IsZeroCircuit {
+        number: Value::known(Fp::from(123)),
+    };
+    let mut proof = prove_and_verify(K, circuit, &[&[Fp::zero()]]).unwrap();
+    let middle = proof.len() / 2;
+    proof[middle] ^= 1;
+    assert!(verify(K, &IsZeroCircuit::default(), &proof, &[&[Fp::zero()]]).is_err());
+}
+
+#[test]
+fn test_prove_and_verify_wrong_public_input() {
+    let circuit = IsZeroCircuit {
+        number: Value::known(Fp::from(123)),
+    };
+    assert!(prove_and_verify(K, circuit, &[&[Fp::one()]]).is_err());
+}